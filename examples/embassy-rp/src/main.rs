@@ -0,0 +1,41 @@
+//! Sample AIN0 on a timer with the async driver and log the readings over defmt.
+
+#![no_std]
+#![no_main]
+
+use ads1119::{asynch::Ads1119Async, single_ended_rdata_to_scaled_voltage, InputSelection};
+use defmt::{info, warn, Debug2Format};
+use embassy_executor::Spawner;
+use embassy_rp::bind_interrupts;
+use embassy_rp::i2c::{self, I2c, InterruptHandler};
+use embassy_rp::peripherals::I2C0;
+use embassy_time::{Delay, Duration, Ticker};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    I2C0_IRQ => InterruptHandler<I2C0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+    let i2c = I2c::new_async(p.I2C0, p.PIN_5, p.PIN_4, Irqs, i2c::Config::default());
+    let mut adc = Ads1119Async::new(i2c, 0x40, Delay);
+
+    if let Err(e) = adc.reset().await {
+        warn!("reset failed: {}", Debug2Format(&e));
+    }
+
+    let mut ticker = Ticker::every(Duration::from_millis(100));
+    loop {
+        match adc.read_input_oneshot(&InputSelection::AN0SingleEnded).await {
+            Ok(raw) => info!(
+                "AIN0: {} ({} V)",
+                raw,
+                single_ended_rdata_to_scaled_voltage(raw)
+            ),
+            Err(e) => warn!("read failed: {}", Debug2Format(&e)),
+        }
+        ticker.next().await;
+    }
+}
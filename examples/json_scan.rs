@@ -0,0 +1,34 @@
+use std::{error::Error, io, time::SystemTime};
+
+use linux_embedded_hal::I2cdev;
+
+use ads1119::{
+    json::JsonRecord,
+    scan::{ScanEntry, ScanPlan},
+    Ads1119, InputSelection,
+};
+
+// Example of scanning the ADS1119's 4 inputs and printing each reading as a line of JSON,
+// e.g. `cargo run --example json_scan --features json | jq .volts`
+fn main() -> Result<(), Box<dyn Error>> {
+    let dev = I2cdev::new("/dev/i2c-7").unwrap();
+    let mut driver = Ads1119::new(dev, 0x40);
+    let plan = [
+        InputSelection::AN0SingleEnded,
+        InputSelection::AN1SingleEnded,
+        InputSelection::AN2SingleEnded,
+        InputSelection::AN3SingleEnded,
+    ]
+    .into_iter()
+    .fold(ScanPlan::new(), |plan, input| {
+        plan.with_entry(ScanEntry::new(input))
+    });
+
+    let stdout = io::stdout();
+    // loop forever
+    loop {
+        for reading in driver.run_scan(&plan)? {
+            JsonRecord::from_scan_reading(&reading, SystemTime::now()).write_line(stdout.lock())?;
+        }
+    }
+}
@@ -11,17 +11,9 @@ fn main() -> Result<(), Box<dyn Error>> {
     let dev = I2cdev::new("/dev/i2c-7").unwrap();
     let mut driver = Ads1119::new(dev, 0x40);
 
-    // Reset the device to a known state (default)
-    let _ = driver.reset().unwrap();
-    println!("Reset called.");
-
-    // default config shoul be 0x0
-    let config = driver.read_config().unwrap();
-    println!("Read config value: {:X}", config);
-
-    // default status should not have the MSBit set
-    let status = driver.read_status().unwrap();
-    println!("Read status value: {:#010b}", status);
+    // Reset the device to a known state (default), checking the config and status registers
+    driver.reset_and_verify()?;
+    println!("Reset and verified.");
 
     // loop forever
     loop {
@@ -41,7 +33,7 @@ fn main() -> Result<(), Box<dyn Error>> {
             println!("Read config value: {:X}", config);
 
             // start a "one-shot" conversion on the selected input
-            let _ = driver.start_sync().unwrap();
+            driver.start_sync().unwrap();
 
             // wait until the status register tells us there is data to read
             loop {
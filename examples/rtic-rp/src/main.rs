@@ -0,0 +1,94 @@
+//! Read continuous conversions of AIN0 from the DRDY interrupt and log them over defmt.
+
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use panic_probe as _;
+
+#[rtic::app(device = rp_pico::hal::pac, peripherals = true)]
+mod app {
+    use ads1119::{
+        single_ended_rdata_to_scaled_voltage, Ads1119, Config, ConversionMode, InputSelection,
+    };
+    use defmt::{info, warn, Debug2Format};
+    use rp_pico::hal::{
+        clocks::init_clocks_and_plls,
+        fugit::RateExtU32,
+        gpio::{bank0, FunctionI2C, FunctionSioInput, Interrupt::EdgeLow, Pin, PullUp},
+        pac, Sio, Watchdog, I2C,
+    };
+
+    type Sda = Pin<bank0::Gpio4, FunctionI2C, PullUp>;
+    type Scl = Pin<bank0::Gpio5, FunctionI2C, PullUp>;
+    type Drdy = Pin<bank0::Gpio6, FunctionSioInput, PullUp>;
+
+    #[shared]
+    struct Shared {}
+
+    #[local]
+    struct Local {
+        adc: Ads1119<I2C<pac::I2C0, (Sda, Scl)>>,
+        drdy: Drdy,
+    }
+
+    #[init]
+    fn init(cx: init::Context) -> (Shared, Local) {
+        let mut pac = cx.device;
+        let mut watchdog = Watchdog::new(pac.WATCHDOG);
+        let clocks = init_clocks_and_plls(
+            rp_pico::XOSC_CRYSTAL_FREQ,
+            pac.XOSC,
+            pac.CLOCKS,
+            pac.PLL_SYS,
+            pac.PLL_USB,
+            &mut pac.RESETS,
+            &mut watchdog,
+        )
+        .ok()
+        .unwrap();
+        let sio = Sio::new(pac.SIO);
+        let pins = rp_pico::Pins::new(
+            pac.IO_BANK0,
+            pac.PADS_BANK0,
+            sio.gpio_bank0,
+            &mut pac.RESETS,
+        );
+
+        let sda: Sda = pins.gpio4.reconfigure();
+        let scl: Scl = pins.gpio5.reconfigure();
+        let i2c = I2C::i2c0(
+            pac.I2C0,
+            sda,
+            scl,
+            400.kHz(),
+            &mut pac.RESETS,
+            &clocks.system_clock,
+        );
+        let drdy: Drdy = pins.gpio6.reconfigure();
+        drdy.set_interrupt_enabled(EdgeLow, true);
+
+        let mut adc = Ads1119::new(i2c, 0x40);
+        let config = Config::new(InputSelection::AN0SingleEnded)
+            .with_conversion_mode(ConversionMode::Continuous);
+        adc.start(&config).unwrap();
+
+        (Shared {}, Local { adc, drdy })
+    }
+
+    #[task(binds = IO_IRQ_BANK0, local = [adc, drdy])]
+    fn conversion_ready(cx: conversion_ready::Context) {
+        cx.local.drdy.clear_interrupt(EdgeLow);
+        if let Err(e) = cx.local.adc.on_drdy_interrupt() {
+            warn!("read failed: {}", Debug2Format(&e));
+            return;
+        }
+        if let Some(raw) = cx.local.adc.take_result() {
+            info!(
+                "AIN0: {} ({} V)",
+                raw,
+                single_ended_rdata_to_scaled_voltage(raw)
+            );
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Decode arbitrary config register values and command bytes: no panics, and what's
+//! decoded encodes back to the bits it was decoded from.
+
+#![no_main]
+
+use ads1119::{Command, Config, InputSelection};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    for &bits in data {
+        let input = InputSelection::from_bits(bits);
+        assert_eq!(input.bits(), bits & 0b1110_0000);
+        assert_eq!(InputSelection::from_bits(input.bits()), input);
+
+        let config = Config::from_bits(bits);
+        assert_eq!(config.bits(), bits);
+        assert_eq!(Config::from(bits), config);
+
+        if let Some(command) = Command::decode(bits) {
+            assert_eq!(command.encode(), bits);
+        }
+    }
+});
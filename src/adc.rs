@@ -0,0 +1,127 @@
+//! A small ADC trait, so application code can be written against it and run with an
+//! [Ads1119], an [Ads1219], a simulator or another ADC (e.g. in tests).
+
+use embedded_hal::i2c::I2c;
+
+use crate::ads1219::{ads1219_code_to_voltage, Ads1219};
+use crate::{Ads1119, Ads1119Err, Gain, InputSelection};
+
+/// An ADC with a set of channels that can be read one at a time
+pub trait Adc {
+    /// How a channel is selected, e.g. [InputSelection]
+    type Channel;
+    type Error;
+
+    /// Run a conversion on the given channel and return its raw code.
+    /// Codes of ADCs narrower than 32 bits are sign-extended.
+    fn read_raw(&mut self, channel: &Self::Channel) -> Result<i32, Self::Error>;
+
+    /// Run a conversion on the given channel and return it in volts
+    fn read_voltage(&mut self, channel: &Self::Channel) -> Result<f32, Self::Error>;
+}
+
+impl<T: Adc + ?Sized> Adc for &mut T {
+    type Channel = T::Channel;
+    type Error = T::Error;
+
+    fn read_raw(&mut self, channel: &Self::Channel) -> Result<i32, Self::Error> {
+        T::read_raw(self, channel)
+    }
+
+    fn read_voltage(&mut self, channel: &Self::Channel) -> Result<f32, Self::Error> {
+        T::read_voltage(self, channel)
+    }
+}
+
+/// One-shot reads with the default config, see [Ads1119::read_input_oneshot].
+/// Voltages include the input's calibration and divider, see [Ads1119::read_input_voltage].
+impl<I2C> Adc for Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    type Channel = InputSelection;
+    type Error = Ads1119Err<I2C::Error>;
+
+    fn read_raw(&mut self, channel: &InputSelection) -> Result<i32, Self::Error> {
+        self.read_input_oneshot(channel).map(i32::from)
+    }
+
+    fn read_voltage(&mut self, channel: &InputSelection) -> Result<f32, Self::Error> {
+        self.read_input_voltage(channel)
+    }
+}
+
+/// One-shot reads with the default config, see [Ads1219::read_input_oneshot]
+impl<I2C> Adc for Ads1219<I2C>
+where
+    I2C: I2c,
+{
+    type Channel = InputSelection;
+    type Error = Ads1119Err<I2C::Error>;
+
+    fn read_raw(&mut self, channel: &InputSelection) -> Result<i32, Self::Error> {
+        self.read_input_oneshot(channel)
+    }
+
+    fn read_voltage(&mut self, channel: &InputSelection) -> Result<f32, Self::Error> {
+        self.read_input_oneshot(channel)
+            .map(|raw| ads1219_code_to_voltage(raw, Gain::One))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_oneshot_read;
+    use crate::CmdFlags;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    // application code only knows about the trait
+    fn read_both<A: Adc>(adc: &mut A, channel: &A::Channel) -> Result<(i32, f32), A::Error> {
+        Ok((adc.read_raw(channel)?, adc.read_voltage(channel)?))
+    }
+
+    // with a result of any width
+    fn oneshot(input: &InputSelection, data: Vec<u8>) -> Vec<I2cTransaction> {
+        let mut transactions = expect_oneshot_read(DEVICE_ADDRESS, input, 0);
+        transactions[3] = I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], data);
+        transactions
+    }
+
+    #[test]
+    fn drivers_implement_adc() {
+        let input = InputSelection::AN0SingleEnded;
+
+        let mut ads1119 = Ads1119::new(
+            I2cMock::new(
+                &[
+                    oneshot(&input, vec![0x7F, 0xFF]),
+                    oneshot(&input, vec![0x7F, 0xFF]),
+                ]
+                .concat(),
+            ),
+            DEVICE_ADDRESS,
+        );
+        let (raw, volts) = read_both(&mut ads1119, &input).unwrap();
+        assert_eq!(raw, 0x7FFF);
+        assert!((volts - 2.048).abs() < 1e-6);
+        ads1119.destroy().done();
+
+        let mut ads1219 = Ads1219::new(
+            I2cMock::new(
+                &[
+                    oneshot(&input, vec![0xC0, 0x00, 0x00]),
+                    oneshot(&input, vec![0xC0, 0x00, 0x00]),
+                ]
+                .concat(),
+            ),
+            DEVICE_ADDRESS,
+        );
+        let (raw, volts) = read_both(&mut &mut ads1219, &input).unwrap();
+        assert_eq!(raw, -0x40_0000);
+        assert!((volts + 1.024).abs() < 1e-6);
+        ads1219.destroy().done();
+    }
+}
@@ -0,0 +1,201 @@
+//! Driver for the ADS1219, the 24-bit part of the family.
+//!
+//! The ADS1219 has the same commands, register map and config layout as the ADS1119,
+//! so [Config] and the other config types are shared. Conversion results are 24 bits,
+//! read as 3 bytes and sign-extended to `i32`.
+
+use embedded_hal::i2c::I2c;
+
+use crate::INTERNAL_REFERENCE_VOLTAGE;
+#[cfg(feature = "std")]
+use crate::{
+    clock::{StdClock, StdDelay},
+    wait::AdaptivePoll,
+    Ads1119Err, InputSelection, READ_INPUT_TIMEOUT,
+};
+use crate::{family, Command, Config, ConversionMode, Gain, Register};
+
+/// Largest positive conversion result of the ADS1219
+pub const ADS1219_FULL_SCALE: i32 = 0x7F_FFFF;
+
+pub struct Ads1219<I2C> {
+    i2c: I2C,
+    // I2C address
+    address: u8,
+}
+
+impl<I2C> Ads1219<I2C>
+where
+    I2C: I2c,
+{
+    pub fn new(i2c: I2C, i2c_address: u8) -> Self {
+        Ads1219 {
+            i2c,
+            address: i2c_address,
+        }
+    }
+
+    /// Destroy the `Ads1219` instance and return its I2C instance
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+
+    /// Low-level access to the device through the driver's bus, see [crate::ll]
+    pub fn ll(&mut self) -> crate::ll::Ll<&mut I2C> {
+        crate::ll::Ll::new(&mut self.i2c, self.address)
+    }
+
+    /// Read the config register. The layout is the same as the ADS1119's, see [Config].
+    pub fn read_config(&mut self) -> Result<u8, I2C::Error> {
+        family::read_register(&mut self.i2c, self.address, Register::Config)
+    }
+
+    /// Write the config register with the given value, see [Config::bits]
+    pub fn write_config(&mut self, value: u8) -> Result<(), I2C::Error> {
+        family::write_config(&mut self.i2c, self.address, value)
+    }
+
+    /// Read the status register. See [crate::STATUS_CONV_RDY]
+    pub fn read_status(&mut self) -> Result<u8, I2C::Error> {
+        family::read_register(&mut self.i2c, self.address, Register::Status)
+    }
+
+    /// Start a single-shot conversion, or (re)start continuous conversions
+    pub fn start_sync(&mut self) -> Result<(), I2C::Error> {
+        family::command(&mut self.i2c, self.address, Command::StartSync)
+    }
+
+    /// Enter power-down mode. Issue [Ads1219::start_sync] to wake the device up again.
+    pub fn power_down(&mut self) -> Result<(), I2C::Error> {
+        family::command(&mut self.i2c, self.address, Command::PowerDown)
+    }
+
+    /// Resets the device to a default state.
+    pub fn reset(&mut self) -> Result<(), I2C::Error> {
+        family::command(&mut self.i2c, self.address, Command::Reset)
+    }
+
+    /// Reads the latest 24-bit conversion result, sign-extended.
+    pub fn read_data(&mut self) -> Result<i32, I2C::Error> {
+        self.read_data_raw()
+            .map(|[msb, mid, lsb]| i32::from_be_bytes([msb, mid, lsb, 0]) >> 8)
+    }
+
+    /// Reads the latest conversion result as the three bytes sent by the device, MSB first
+    pub fn read_data_raw(&mut self) -> Result<[u8; 3], I2C::Error> {
+        family::read_data(&mut self.i2c, self.address)
+    }
+
+    /// Write the given config in continuous conversion mode and start converting.
+    /// Results can be read with [Ads1219::read_data] whenever the status register
+    /// reports a new conversion.
+    pub fn start_continuous(&mut self, config: &Config) -> Result<(), I2C::Error> {
+        let config = config
+            .clone()
+            .with_conversion_mode(ConversionMode::Continuous);
+        self.write_config(config.bits())?;
+        self.start_sync()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Ads1219<I2C>
+where
+    I2C: I2c,
+{
+    /// Read data from the given input with "one-shot" semantics.
+    ///
+    /// Same precondition as [crate::Ads1119::read_input_oneshot]: the device must not be
+    /// accessed by anyone else during the call.
+    pub fn read_input_oneshot(
+        &mut self,
+        input: &InputSelection,
+    ) -> Result<i32, Ads1119Err<I2C::Error>> {
+        self.write_config(input.bits())?;
+        self.start_sync()?;
+        let waiter = family::Waiter {
+            strategy: &mut AdaptivePoll::default(),
+            clock: Some(&StdClock),
+            delay: &mut StdDelay,
+            cancelled: &|| false,
+        };
+        family::wait_conversion_ready(
+            &mut self.i2c,
+            self.address,
+            waiter,
+            None,
+            READ_INPUT_TIMEOUT,
+        )?;
+        Ok(self.read_data()?)
+    }
+}
+
+/// Interpret a 24-bit conversion result as a voltage, for any input and gain.
+/// Assumes the internal 2.048V reference, so the full-scale range is ±2.048V / gain.
+pub fn ads1219_code_to_voltage(raw_data: i32, gain: Gain) -> f32 {
+    // f64 keeps all 24 bits of the code before scaling
+    (raw_data as f64 / ADS1219_FULL_SCALE as f64 * INTERNAL_REFERENCE_VOLTAGE as f64
+        / gain.factor() as f64) as f32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn reads_24_bit_results() {
+        let input = InputSelection::AN1SingleEnded;
+        let mut device = Ads1219::new(
+            I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RDATA],
+                    vec![0x12, 0x34, 0x56],
+                ),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RDATA],
+                    vec![0xFF, 0xFF, 0xFE],
+                ),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RDATA],
+                    vec![0xFF, 0xFF, 0xFE],
+                ),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        assert_eq!(device.read_input_oneshot(&input).unwrap(), 0x12_3456);
+        // negative codes are sign-extended
+        assert_eq!(device.read_data().unwrap(), -2);
+        assert_eq!(device.read_data_raw().unwrap(), [0xFF, 0xFF, 0xFE]);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn code_to_voltage() {
+        assert_eq!(
+            ads1219_code_to_voltage(ADS1219_FULL_SCALE, Gain::One),
+            2.048
+        );
+        assert_eq!(
+            ads1219_code_to_voltage(-ADS1219_FULL_SCALE, Gain::Four),
+            -0.512
+        );
+        assert_eq!(ads1219_code_to_voltage(0, Gain::One), 0.0);
+    }
+}
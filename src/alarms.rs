@@ -0,0 +1,459 @@
+//! Software threshold alarms.
+//!
+//! The ADS1119 has no hardware comparator, so thresholds are checked on the values read by the
+//! application. Feed values to [Alarms::update] (or read through
+//! [Ads1119::read_input_monitored]) and collect the resulting [AlarmEvent]s from the queue or a
+//! callback.
+
+use embedded_hal::i2c::I2c;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::clock::{Clock, StdClock};
+use crate::{Ads1119, Ads1119Err, InputSelection};
+
+/// Thresholds for one input. Values are in the units fed to [Alarms::update]
+/// (volts when using [Ads1119::read_input_monitored]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alarm {
+    pub input: InputSelection,
+    /// Raise an alarm when the value goes above this
+    pub high: Option<f32>,
+    /// Raise an alarm when the value goes below this
+    pub low: Option<f32>,
+    /// How far back past a threshold the value must go for its alarm to clear
+    pub hysteresis: f32,
+    /// Raise an alarm when the value changes faster than this, in units per second
+    /// (in either direction). It clears once the rate drops back to or below it.
+    pub max_rate: Option<f32>,
+}
+
+impl Alarm {
+    pub fn new(input: InputSelection) -> Self {
+        Alarm {
+            input,
+            high: None,
+            low: None,
+            hysteresis: 0.0,
+            max_rate: None,
+        }
+    }
+
+    pub fn with_high(mut self, high: f32) -> Self {
+        self.high = Some(high);
+        self
+    }
+
+    pub fn with_low(mut self, low: f32) -> Self {
+        self.low = Some(low);
+        self
+    }
+
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    pub fn with_max_rate(mut self, max_rate: f32) -> Self {
+        self.max_rate = Some(max_rate);
+        self
+    }
+}
+
+/// Which threshold an event is about
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AlarmKind {
+    High,
+    Low,
+    /// The value changed faster than [Alarm::max_rate]
+    RateOfChange,
+}
+
+/// A change of state of an alarm
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlarmEvent {
+    /// The value crossed the threshold
+    Raised {
+        input: InputSelection,
+        kind: AlarmKind,
+        value: f32,
+    },
+    /// The value came back past the threshold and the hysteresis
+    Cleared {
+        input: InputSelection,
+        kind: AlarmKind,
+        value: f32,
+    },
+}
+
+#[derive(Clone, Debug, Default)]
+struct AlarmState {
+    high_active: bool,
+    low_active: bool,
+    rate_active: bool,
+    // previous value and when it was seen on the clock, for the rate of change
+    previous: Option<(f32, Duration)>,
+}
+
+/// Callback invoked for each [AlarmEvent], see [Alarms::set_callback]
+pub type AlarmCallback = Box<dyn FnMut(&AlarmEvent) + Send>;
+
+/// The set of registered alarms and their pending events
+#[derive(Default)]
+pub struct Alarms {
+    alarms: Vec<(Alarm, AlarmState)>,
+    events: VecDeque<AlarmEvent>,
+    callback: Option<AlarmCallback>,
+    // None for StdClock
+    clock: Option<&'static (dyn Clock + Sync)>,
+}
+
+impl Alarms {
+    pub fn new() -> Self {
+        Alarms::default()
+    }
+
+    /// Register an alarm. Several alarms may watch the same input.
+    pub fn add(&mut self, alarm: Alarm) {
+        self.alarms.push((alarm, AlarmState::default()));
+    }
+
+    /// Call `callback` for every event, in addition to queueing it
+    pub fn set_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&AlarmEvent) + Send + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+    }
+
+    /// Time the values passed to [Alarms::update] with `clock` instead of [StdClock]
+    pub fn set_clock(&mut self, clock: &'static (dyn Clock + Sync)) {
+        self.clock = Some(clock);
+    }
+
+    /// Check a new value of the given input against its alarms, timestamped now on the
+    /// clock (see [Alarms::set_clock]). Returns the number of events it generated.
+    pub fn update(&mut self, input: &InputSelection, value: f32) -> usize {
+        let at = self
+            .clock
+            .map_or_else(|| StdClock.now(), |clock| clock.now());
+        self.update_at(input, value, at)
+    }
+
+    /// Same as [Alarms::update], for a value taken at the given time on a [Clock]
+    pub fn update_at(&mut self, input: &InputSelection, value: f32, at: Duration) -> usize {
+        let mut new_events = Vec::new();
+        for (alarm, state) in self.alarms.iter_mut().filter(|(a, _)| &a.input == input) {
+            if let Some(high) = alarm.high {
+                if !state.high_active && value > high {
+                    state.high_active = true;
+                    new_events.push(AlarmEvent::Raised {
+                        input: input.clone(),
+                        kind: AlarmKind::High,
+                        value,
+                    });
+                } else if state.high_active && value < high - alarm.hysteresis {
+                    state.high_active = false;
+                    new_events.push(AlarmEvent::Cleared {
+                        input: input.clone(),
+                        kind: AlarmKind::High,
+                        value,
+                    });
+                }
+            }
+            if let Some(low) = alarm.low {
+                if !state.low_active && value < low {
+                    state.low_active = true;
+                    new_events.push(AlarmEvent::Raised {
+                        input: input.clone(),
+                        kind: AlarmKind::Low,
+                        value,
+                    });
+                } else if state.low_active && value > low + alarm.hysteresis {
+                    state.low_active = false;
+                    new_events.push(AlarmEvent::Cleared {
+                        input: input.clone(),
+                        kind: AlarmKind::Low,
+                        value,
+                    });
+                }
+            }
+            if let Some(max_rate) = alarm.max_rate {
+                let rate = state.previous.and_then(|(previous, previous_at)| {
+                    let elapsed = at.checked_sub(previous_at)?.as_secs_f32();
+                    (elapsed > 0.0).then(|| (value - previous).abs() / elapsed)
+                });
+                if let Some(rate) = rate {
+                    if !state.rate_active && rate > max_rate {
+                        state.rate_active = true;
+                        new_events.push(AlarmEvent::Raised {
+                            input: input.clone(),
+                            kind: AlarmKind::RateOfChange,
+                            value,
+                        });
+                    } else if state.rate_active && rate <= max_rate {
+                        state.rate_active = false;
+                        new_events.push(AlarmEvent::Cleared {
+                            input: input.clone(),
+                            kind: AlarmKind::RateOfChange,
+                            value,
+                        });
+                    }
+                }
+            }
+            state.previous = Some((value, at));
+        }
+
+        let count = new_events.len();
+        for event in new_events {
+            if let Some(callback) = self.callback.as_mut() {
+                callback(&event);
+            }
+            self.events.push_back(event);
+        }
+        count
+    }
+
+    /// Take the oldest queued event
+    pub fn next_event(&mut self) -> Option<AlarmEvent> {
+        self.events.pop_front()
+    }
+
+    /// Take all queued events, oldest first
+    pub fn drain_events(&mut self) -> impl Iterator<Item = AlarmEvent> + '_ {
+        self.events.drain(..)
+    }
+
+    /// True if any alarm of the given input is currently raised
+    pub fn is_active(&self, input: &InputSelection) -> bool {
+        self.alarms
+            .iter()
+            .any(|(a, s)| &a.input == input && (s.high_active || s.low_active || s.rate_active))
+    }
+}
+
+/// Where a value sits relative to a [WindowComparator]'s window
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowState {
+    Below,
+    Inside,
+    Above,
+}
+
+/// Emitted by a [WindowComparator] when its debounced state changes
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowEvent {
+    pub input: InputSelection,
+    pub state: WindowState,
+    /// The value that confirmed the new state
+    pub value: f32,
+}
+
+/// In-window/out-of-window classification of an input, like the window comparator mode of
+/// the ADS1115, with debouncing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowComparator {
+    pub input: InputSelection,
+    pub low: f32,
+    pub high: f32,
+    /// Number of consecutive values that must fall in a new state before it is reported
+    pub debounce: u32,
+    state: Option<WindowState>,
+    candidate: Option<(WindowState, u32)>,
+}
+
+impl WindowComparator {
+    /// A comparator for the window `[low, high]`, reporting every change (debounce of 1)
+    pub fn new(input: InputSelection, low: f32, high: f32) -> Self {
+        WindowComparator {
+            input,
+            low,
+            high,
+            debounce: 1,
+            state: None,
+            candidate: None,
+        }
+    }
+
+    pub fn with_debounce(mut self, debounce: u32) -> Self {
+        self.debounce = debounce.max(1);
+        self
+    }
+
+    /// The current debounced state, `None` until the first state is confirmed
+    pub fn state(&self) -> Option<WindowState> {
+        self.state
+    }
+
+    /// Classify a value without changing the comparator's state
+    pub fn classify(&self, value: f32) -> WindowState {
+        if value < self.low {
+            WindowState::Below
+        } else if value > self.high {
+            WindowState::Above
+        } else {
+            WindowState::Inside
+        }
+    }
+
+    /// Feed a new value. Returns an event when the debounced state changes.
+    pub fn update(&mut self, value: f32) -> Option<WindowEvent> {
+        let observed = self.classify(value);
+        if Some(observed) == self.state {
+            self.candidate = None;
+            return None;
+        }
+
+        let count = match self.candidate {
+            Some((state, count)) if state == observed => count + 1,
+            _ => 1,
+        };
+        if count < self.debounce {
+            self.candidate = Some((observed, count));
+            return None;
+        }
+
+        self.candidate = None;
+        self.state = Some(observed);
+        Some(WindowEvent {
+            input: self.input.clone(),
+            state: observed,
+            value,
+        })
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Read the comparator's input with [Ads1119::read_input_voltage] and feed it to the
+    /// comparator. Returns an event when its debounced state changes.
+    pub fn read_window(
+        &mut self,
+        comparator: &mut WindowComparator,
+    ) -> Result<Option<WindowEvent>, Ads1119Err<I2C::Error>> {
+        let volts = self.read_input_voltage(&comparator.input)?;
+        Ok(comparator.update(volts))
+    }
+
+    /// Read the input with [Ads1119::read_input_voltage] and check the value against
+    /// `alarms`, timed on the driver's clock (see [Ads1119::set_clock])
+    pub fn read_input_monitored(
+        &mut self,
+        input: &InputSelection,
+        alarms: &mut Alarms,
+    ) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let volts = self.read_input_voltage(input)?;
+        alarms.update_at(input, volts, self.now());
+        Ok(volts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const INPUT: InputSelection = InputSelection::AN0SingleEnded;
+
+    #[test]
+    fn high_alarm_with_hysteresis() {
+        let mut alarms = Alarms::new();
+        alarms.add(Alarm::new(INPUT).with_high(1.5).with_hysteresis(0.1));
+
+        assert_eq!(alarms.update(&INPUT, 1.4), 0);
+        assert_eq!(alarms.update(&INPUT, 1.6), 1);
+        assert!(alarms.is_active(&INPUT));
+        // still above the threshold, or back below it but within the hysteresis
+        assert_eq!(alarms.update(&INPUT, 1.7), 0);
+        assert_eq!(alarms.update(&INPUT, 1.45), 0);
+        assert_eq!(alarms.update(&INPUT, 1.39), 1);
+        assert!(!alarms.is_active(&INPUT));
+
+        assert_eq!(
+            alarms.next_event(),
+            Some(AlarmEvent::Raised {
+                input: INPUT,
+                kind: AlarmKind::High,
+                value: 1.6
+            })
+        );
+        assert_eq!(
+            alarms.next_event(),
+            Some(AlarmEvent::Cleared {
+                input: INPUT,
+                kind: AlarmKind::High,
+                value: 1.39
+            })
+        );
+        assert_eq!(alarms.next_event(), None);
+    }
+
+    #[test]
+    fn low_alarm_calls_back() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut alarms = Alarms::new();
+        alarms.add(Alarm::new(INPUT).with_low(0.5));
+        let callback_seen = seen.clone();
+        alarms.set_callback(move |event| callback_seen.lock().unwrap().push(event.clone()));
+
+        alarms.update(&InputSelection::AN1SingleEnded, 0.1);
+        alarms.update(&INPUT, 0.4);
+        alarms.update(&INPUT, 0.6);
+        assert_eq!(seen.lock().unwrap().len(), 2);
+        assert_eq!(alarms.drain_events().count(), 2);
+    }
+
+    #[test]
+    fn window_comparator_debounces() {
+        let mut comparator = WindowComparator::new(INPUT, 1.0, 2.0).with_debounce(2);
+        assert_eq!(comparator.update(1.5), None);
+        assert_eq!(comparator.state(), None);
+        assert_eq!(
+            comparator.update(1.6),
+            Some(WindowEvent {
+                input: INPUT,
+                state: WindowState::Inside,
+                value: 1.6
+            })
+        );
+        // a single glitch outside of the window is ignored
+        assert_eq!(comparator.update(2.5), None);
+        assert_eq!(comparator.update(1.5), None);
+        assert_eq!(comparator.update(0.5), None);
+        assert_eq!(
+            comparator.update(0.4).map(|event| event.state),
+            Some(WindowState::Below)
+        );
+        assert_eq!(comparator.state(), Some(WindowState::Below));
+    }
+
+    #[test]
+    fn rate_of_change_alarm() {
+        let mut alarms = Alarms::new();
+        alarms.add(Alarm::new(INPUT).with_max_rate(1.0));
+        let at = Duration::from_millis;
+
+        // the first value has nothing to compare to
+        assert_eq!(alarms.update_at(&INPUT, 1.0, at(0)), 0);
+        // 0.05 in 100ms is 0.5/s
+        assert_eq!(alarms.update_at(&INPUT, 1.05, at(100)), 0);
+        // 0.5 in 100ms is 5/s, e.g. a sensor disconnecting
+        assert_eq!(alarms.update_at(&INPUT, 0.55, at(200)), 1);
+        assert!(alarms.is_active(&INPUT));
+        assert_eq!(alarms.update_at(&INPUT, 0.55, at(300)), 1);
+        assert!(!alarms.is_active(&INPUT));
+
+        let kinds: Vec<_> = alarms
+            .drain_events()
+            .map(|event| match event {
+                AlarmEvent::Raised { kind, .. } | AlarmEvent::Cleared { kind, .. } => kind,
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![AlarmKind::RateOfChange, AlarmKind::RateOfChange]
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Async version of the driver, built on `embedded-hal-async`.
+//!
+//! Enabled with the `async` feature.
+
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+use futures_util::Stream;
+
+use core::time::Duration;
+
+use crate::registers::StatusRegister;
+use crate::sequence::Sequenced;
+use crate::wait::{AdaptivePoll, WaitStrategy};
+use crate::{
+    Ads1119Err, Command, Config, ConversionMode, DataRate, InputSelection, Register,
+    MIN_CHECK_TIME, READ_INPUT_TIMEOUT,
+};
+
+/// Async counterpart of [crate::Ads1119].
+///
+/// Waiting for conversions is done with the given [DelayNs] instead of blocking the thread,
+/// for the delays of the [WaitStrategy] ([AdaptivePoll] unless set with
+/// [Ads1119Async::with_wait_strategy]).
+pub struct Ads1119Async<I2C, D, W = AdaptivePoll> {
+    i2c: I2C,
+    // I2C address
+    address: u8,
+    delay: D,
+    wait: W,
+    // the last value written to the config register, if known
+    config: Option<u8>,
+}
+
+impl<I2C, D> Ads1119Async<I2C, D>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    pub fn new(i2c: I2C, i2c_address: u8, delay: D) -> Self {
+        Ads1119Async {
+            i2c,
+            address: i2c_address,
+            delay,
+            wait: AdaptivePoll::default(),
+            config: None,
+        }
+    }
+}
+
+impl<I2C, D, W> Ads1119Async<I2C, D, W>
+where
+    I2C: I2c,
+    D: DelayNs,
+    W: WaitStrategy,
+{
+    /// Wait for conversions with the given strategy, see [crate::Ads1119::set_wait_strategy].
+    /// Its delays are waited on the timer; [WaitStrategy::wait] isn't used.
+    pub fn with_wait_strategy<W2: WaitStrategy>(self, wait: W2) -> Ads1119Async<I2C, D, W2> {
+        Ads1119Async {
+            i2c: self.i2c,
+            address: self.address,
+            delay: self.delay,
+            wait,
+            config: self.config,
+        }
+    }
+
+    /// Destroy the `Ads1119Async` instance and return its I2C and delay instances
+    pub fn destroy(self) -> (I2C, D) {
+        (self.i2c, self.delay)
+    }
+
+    /// See [crate::Ads1119::read_config]
+    pub async fn read_config(&mut self) -> Result<u8, I2C::Error> {
+        let mut read_buffer = [0];
+        self.i2c
+            .write_read(
+                self.address,
+                &[Command::ReadRegister(Register::Config).encode()],
+                &mut read_buffer,
+            )
+            .await
+            .and(Ok(read_buffer[0]))
+    }
+
+    /// See [crate::Ads1119::write_config]
+    pub async fn write_config(&mut self, value: u8) -> Result<(), I2C::Error> {
+        self.config = None;
+        self.i2c
+            .write(
+                self.address,
+                &[Command::WriteRegister(Register::Config).encode(), value],
+            )
+            .await?;
+        self.config = Some(value);
+        Ok(())
+    }
+
+    /// See [crate::Ads1119::read_status]
+    pub async fn read_status(&mut self) -> Result<u8, I2C::Error> {
+        let mut read_buffer = [0];
+        self.i2c
+            .write_read(
+                self.address,
+                &[Command::ReadRegister(Register::Status).encode()],
+                &mut read_buffer,
+            )
+            .await
+            .and(Ok(read_buffer[0]))
+    }
+
+    /// See [crate::Ads1119::is_conversion_ready]
+    pub async fn is_conversion_ready(&mut self) -> Result<bool, I2C::Error> {
+        Ok(StatusRegister::from_bits(self.read_status().await?).conversion_ready())
+    }
+
+    /// See [crate::Ads1119::start_sync]
+    pub async fn start_sync(&mut self) -> Result<(), I2C::Error> {
+        self.i2c
+            .write(self.address, &[Command::StartSync.encode()])
+            .await
+    }
+
+    /// See [crate::Ads1119::reset]
+    pub async fn reset(&mut self) -> Result<(), I2C::Error> {
+        self.config = None;
+        self.i2c
+            .write(self.address, &[Command::Reset.encode()])
+            .await?;
+        self.config = Some(0);
+        Ok(())
+    }
+
+    /// See [crate::Ads1119::power_down]
+    pub async fn power_down(&mut self) -> Result<(), I2C::Error> {
+        self.i2c
+            .write(self.address, &[Command::PowerDown.encode()])
+            .await
+    }
+
+    /// See [crate::Ads1119::read_data]
+    pub async fn read_data(&mut self) -> Result<i16, I2C::Error> {
+        let mut read_buffer = [0u8, 0u8];
+        self.i2c
+            .write_read(
+                self.address,
+                &[Command::ReadData.encode()],
+                &mut read_buffer,
+            )
+            .await
+            .and(Ok(i16::from_be_bytes(read_buffer)))
+    }
+
+    /// See [crate::Ads1119::read_input_oneshot]. The same exclusive-access precondition applies.
+    pub async fn read_input_oneshot(
+        &mut self,
+        input: &InputSelection,
+    ) -> Result<i16, Ads1119Err<I2C::Error>> {
+        self.write_config(input.bits()).await?;
+        self.start_sync().await?;
+        self.wait_conversion_ready().await?;
+        Ok(self.read_data().await?)
+    }
+
+    /// See [crate::Ads1119::start_continuous]
+    pub async fn start_continuous(&mut self, config: &Config) -> Result<(), I2C::Error> {
+        let config = config
+            .clone()
+            .with_conversion_mode(ConversionMode::Continuous);
+        self.write_config(config.bits()).await?;
+        self.start_sync().await
+    }
+
+    /// Stream of the conversions of a device running in continuous mode
+    /// (see [Ads1119Async::start_continuous]).
+    ///
+    /// Like [crate::Ads1119::samples], the stream never ends on its own and errors are yielded
+    /// to the caller.
+    pub fn sample_stream(
+        &mut self,
+    ) -> impl Stream<Item = Result<i16, Ads1119Err<I2C::Error>>> + '_ {
+        futures_util::stream::unfold(self, |device| async move {
+            let sample = device.next_sample().await;
+            Some((sample, device))
+        })
+    }
+
+    /// Same as [Ads1119Async::sample_stream], with each sample numbered in the order it
+    /// was read, starting at 0 (see [crate::sequence]). Errors don't use up a number.
+    ///
+    /// Without a clock the stream can't tell when conversions were missed, so the numbers
+    /// only reveal the samples lost after the stream, e.g. in a bounded channel.
+    pub fn sequenced_sample_stream(
+        &mut self,
+    ) -> impl Stream<Item = Result<Sequenced<i16>, Ads1119Err<I2C::Error>>> + '_ {
+        futures_util::stream::unfold((self, 0u64), |(device, sequence)| async move {
+            match device.next_sample().await {
+                Ok(value) => Some((Ok(Sequenced { sequence, value }), (device, sequence + 1))),
+                Err(e) => Some((Err(e), (device, sequence))),
+            }
+        })
+    }
+
+    async fn next_sample(&mut self) -> Result<i16, Ads1119Err<I2C::Error>> {
+        self.wait_conversion_ready().await?;
+        Ok(self.read_data().await?)
+    }
+
+    async fn wait_conversion_ready(&mut self) -> Result<(), Ads1119Err<I2C::Error>> {
+        self.wait_for_conversion(READ_INPUT_TIMEOUT).await
+    }
+
+    /// Poll the status register until a new conversion is ready, failing with
+    /// [Ads1119Err::ConversionTimeout] after `timeout`. See [crate::Ads1119::wait_for_conversion].
+    ///
+    /// There is no clock available here, so the timeout is approximated by adding up the
+    /// delays, counting each status check as at least [MIN_CHECK_TIME].
+    pub async fn wait_for_conversion(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Ads1119Err<I2C::Error>> {
+        let conversion_time = self
+            .config
+            .map(|config| DataRate::from_bits(config).conversion_time());
+        let mut delay = self.wait.first_delay(conversion_time);
+        let mut elapsed = Duration::ZERO;
+        let mut checks = 0;
+        loop {
+            // the waits end at the timeout, whatever the strategy asks for
+            delay = delay.min(timeout.saturating_sub(elapsed));
+            self.delay.delay_us(delay.as_micros() as u32).await;
+            elapsed += delay.max(MIN_CHECK_TIME);
+
+            if self.is_conversion_ready().await? {
+                return Ok(());
+            }
+            checks += 1;
+            if elapsed >= timeout {
+                return Err(Ads1119Err::ConversionTimeout(timeout.as_millis()));
+            }
+            delay = self.wait.next_delay(checks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal::i2c::{ErrorType, I2c as _, Operation};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use futures_executor::block_on;
+    use futures_util::StreamExt;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+    const NOT_READY_STATUS: u8 = !0b1000_0000;
+
+    // embedded-hal-mock only provides a blocking I2C mock, so forward the async calls to it
+    struct AsyncI2cMock(I2cMock);
+
+    impl ErrorType for AsyncI2cMock {
+        type Error = <I2cMock as ErrorType>::Error;
+    }
+
+    impl I2c for AsyncI2cMock {
+        async fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            self.0.transaction(address, operations)
+        }
+
+        async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+            self.0.write(address, write)
+        }
+
+        async fn write_read(
+            &mut self,
+            address: u8,
+            write: &[u8],
+            read: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            self.0.write_read(address, write, read)
+        }
+    }
+
+    struct NoopDelay;
+
+    impl DelayNs for NoopDelay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn status_read(status: u8) -> I2cTransaction {
+        I2cTransaction::write_read(
+            DEVICE_ADDRESS,
+            vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+            vec![status],
+        )
+    }
+
+    fn data_read(value: i16) -> I2cTransaction {
+        I2cTransaction::write_read(
+            DEVICE_ADDRESS,
+            vec![CmdFlags::RDATA],
+            value.to_be_bytes().to_vec(),
+        )
+    }
+
+    #[test]
+    fn can_read_input_oneshot() {
+        let input = InputSelection::AN2SingleEnded;
+        let mut device = Ads1119Async::new(
+            AsyncI2cMock(I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                status_read(NOT_READY_STATUS),
+                status_read(STATUS_CONV_RDY),
+                data_read(777),
+            ])),
+            DEVICE_ADDRESS,
+            NoopDelay,
+        );
+        assert_eq!(block_on(device.read_input_oneshot(&input)).unwrap(), 777);
+        device.destroy().0 .0.done();
+    }
+
+    #[test]
+    fn waits_with_the_strategy_delays() {
+        struct RecordingDelay(u64);
+
+        impl DelayNs for RecordingDelay {
+            async fn delay_ns(&mut self, ns: u32) {
+                self.0 += ns as u64;
+            }
+        }
+
+        let input = InputSelection::AN1SingleEnded;
+        let mut device = Ads1119Async::new(
+            AsyncI2cMock(I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                status_read(STATUS_CONV_RDY),
+                data_read(-5),
+            ])),
+            DEVICE_ADDRESS,
+            RecordingDelay(0),
+        )
+        .with_wait_strategy(crate::wait::ComputedWait);
+        assert_eq!(block_on(device.read_input_oneshot(&input)).unwrap(), -5);
+        let (mut i2c, delay) = device.destroy();
+        i2c.0.done();
+        // 50 ms at the default 20 SPS, plus 10%
+        assert_eq!(delay.0, 55_000_000);
+    }
+
+    #[test]
+    fn can_stream_samples() {
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        let mut device = Ads1119Async::new(
+            AsyncI2cMock(I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, 0b0110_0010],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                status_read(STATUS_CONV_RDY),
+                data_read(1),
+                status_read(NOT_READY_STATUS),
+                status_read(STATUS_CONV_RDY),
+                data_read(2),
+            ])),
+            DEVICE_ADDRESS,
+            NoopDelay,
+        );
+        let samples: Vec<i16> = block_on(async {
+            device.start_continuous(&config).await.unwrap();
+            device
+                .sample_stream()
+                .take(2)
+                .map(Result::unwrap)
+                .collect()
+                .await
+        });
+        assert_eq!(samples, vec![1, 2]);
+        device.destroy().0 .0.done();
+    }
+
+    #[test]
+    fn sequenced_stream_numbers_the_samples() {
+        let mut device = Ads1119Async::new(
+            AsyncI2cMock(I2cMock::new(&[
+                status_read(STATUS_CONV_RDY),
+                data_read(7),
+                status_read(STATUS_CONV_RDY),
+                data_read(8),
+            ])),
+            DEVICE_ADDRESS,
+            NoopDelay,
+        );
+        let samples: Vec<_> = block_on(
+            device
+                .sequenced_sample_stream()
+                .take(2)
+                .map(Result::unwrap)
+                .collect(),
+        );
+        assert_eq!(
+            samples,
+            vec![
+                Sequenced {
+                    sequence: 0,
+                    value: 7
+                },
+                Sequenced {
+                    sequence: 1,
+                    value: 8
+                }
+            ]
+        );
+        device.destroy().0 .0.done();
+    }
+}
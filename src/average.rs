@@ -0,0 +1,147 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, Ads1119Err, InputSelection};
+
+/// Result of [Ads1119::read_input_averaged], in raw ADC codes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AveragedReading {
+    pub mean: f32,
+    /// Population standard deviation of the samples
+    pub std_dev: f32,
+    /// Number of samples that were averaged
+    pub count: usize,
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Take `n` single-shot conversions of the given input and average them.
+    ///
+    /// The config is written once, then each conversion is started and read in turn.
+    /// The same exclusive-access precondition as [Ads1119::read_input_oneshot] applies.
+    /// Returns [Ads1119Err::NoSamples] if `n` is 0.
+    pub fn read_input_averaged(
+        &mut self,
+        input: &InputSelection,
+        n: usize,
+    ) -> Result<AveragedReading, Ads1119Err<I2C::Error>> {
+        if n == 0 {
+            return Err(Ads1119Err::NoSamples);
+        }
+
+        self.write_config(input.bits())?;
+
+        // Welford's algorithm, so the variance doesn't need the samples kept around
+        let mut mean = 0.0f64;
+        let mut m2 = 0.0f64;
+        for count in 1..=n {
+            let sample = self.convert_oneshot()? as f64;
+            let delta = sample - mean;
+            mean += delta / count as f64;
+            m2 += delta * (sample - mean);
+        }
+
+        Ok(AveragedReading {
+            mean: mean as f32,
+            std_dev: (m2 / n as f64).sqrt() as f32,
+            count: n,
+        })
+    }
+
+    /// Take `n` single-shot conversions of the given input and return their median.
+    ///
+    /// Unlike [Ads1119::read_input_averaged], a single glitch sample (e.g. from mux switching or
+    /// an EMI burst) can't skew the result. For an even `n` the two middle samples are averaged.
+    /// Returns [Ads1119Err::NoSamples] if `n` is 0.
+    pub fn read_input_median(
+        &mut self,
+        input: &InputSelection,
+        n: usize,
+    ) -> Result<i16, Ads1119Err<I2C::Error>> {
+        let mut samples = self.read_samples(input.bits(), n)?;
+        samples.sort_unstable();
+
+        let mid = n / 2;
+        if n % 2 == 1 {
+            Ok(samples[mid])
+        } else {
+            Ok(((samples[mid - 1] as i32 + samples[mid] as i32) / 2) as i16)
+        }
+    }
+
+    /// Write the given config register value once, then take `n` single-shot conversions.
+    /// Returns [Ads1119Err::NoSamples] if `n` is 0.
+    pub(crate) fn read_samples(
+        &mut self,
+        config: u8,
+        n: usize,
+    ) -> Result<Vec<i16>, Ads1119Err<I2C::Error>> {
+        if n == 0 {
+            return Err(Ads1119Err::NoSamples);
+        }
+
+        self.write_config(config)?;
+
+        let mut samples = Vec::with_capacity(n);
+        for _ in 0..n {
+            samples.push(self.convert_oneshot()?);
+        }
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_repeated_oneshot_reads;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn can_read_input_averaged() {
+        let input = InputSelection::AN1SingleEnded;
+        let transactions =
+            expect_repeated_oneshot_reads(DEVICE_ADDRESS, input.bits(), &[2, 4, 4, 4, 5, 5, 7, 9]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        let reading = device.read_input_averaged(&input, 8).unwrap();
+        assert_eq!(reading.count, 8);
+        assert!((reading.mean - 5.0).abs() < 0.0001);
+        assert!((reading.std_dev - 2.0).abs() < 0.0001);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn read_input_averaged_needs_samples() {
+        let mut device = Ads1119::new(I2cMock::new(&[]), DEVICE_ADDRESS);
+        assert!(matches!(
+            device.read_input_averaged(&InputSelection::AN0SingleEnded, 0),
+            Err(Ads1119Err::NoSamples)
+        ));
+        device.destroy().done();
+    }
+
+    #[test]
+    fn read_input_median_rejects_glitch() {
+        let input = InputSelection::AN3SingleEnded;
+        let transactions = expect_repeated_oneshot_reads(
+            DEVICE_ADDRESS,
+            input.bits(),
+            &[100, 101, 32767, 99, 100],
+        );
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        assert_eq!(device.read_input_median(&input, 5).unwrap(), 100);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn read_input_median_even_count() {
+        let input = InputSelection::AN3SingleEnded;
+        let transactions =
+            expect_repeated_oneshot_reads(DEVICE_ADDRESS, input.bits(), &[10, -20, 30, 12]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        assert_eq!(device.read_input_median(&input, 4).unwrap(), 11);
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,164 @@
+//! Battery voltage and state-of-charge monitoring.
+
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    divider::VoltageDivider, single_ended_code_to_voltage, Ads1119, Ads1119Err, InputSelection,
+};
+
+/// A battery read through a voltage divider on one input
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatteryMonitor {
+    pub input: InputSelection,
+    pub divider: VoltageDivider,
+    /// Number of conversions averaged for each reading
+    pub samples: usize,
+    /// Open-circuit voltage to state-of-charge curve, as `(volts, percent)` points sorted by
+    /// increasing voltage. The state of charge is interpolated linearly between points and
+    /// clamped to the first and last points outside of them.
+    pub curve: Vec<(f32, f32)>,
+}
+
+/// A reading from a [BatteryMonitor]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BatteryReading {
+    /// Battery voltage, in volts
+    pub volts: f32,
+    /// Estimated state of charge, in percent
+    pub state_of_charge: f32,
+}
+
+impl BatteryMonitor {
+    /// A monitor averaging 8 conversions, with a linear curve from `empty` (0%) to `full` (100%)
+    pub fn new(input: InputSelection, divider: VoltageDivider, empty: f32, full: f32) -> Self {
+        BatteryMonitor {
+            input,
+            divider,
+            samples: 8,
+            curve: vec![(empty, 0.0), (full, 100.0)],
+        }
+    }
+
+    /// A monitor for a single Li-ion/LiPo cell, using a typical resting-voltage curve
+    pub fn li_ion_1s(input: InputSelection, divider: VoltageDivider) -> Self {
+        BatteryMonitor {
+            curve: vec![
+                (3.27, 0.0),
+                (3.61, 5.0),
+                (3.69, 10.0),
+                (3.71, 15.0),
+                (3.73, 20.0),
+                (3.75, 25.0),
+                (3.77, 30.0),
+                (3.79, 35.0),
+                (3.80, 40.0),
+                (3.82, 45.0),
+                (3.84, 50.0),
+                (3.85, 55.0),
+                (3.87, 60.0),
+                (3.91, 65.0),
+                (3.95, 70.0),
+                (3.98, 75.0),
+                (4.02, 80.0),
+                (4.08, 85.0),
+                (4.11, 90.0),
+                (4.15, 95.0),
+                (4.20, 100.0),
+            ],
+            ..BatteryMonitor::new(input, divider, 3.27, 4.2)
+        }
+    }
+
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    pub fn with_curve(mut self, curve: Vec<(f32, f32)>) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Estimate the state of charge, in percent, for the given battery voltage
+    pub fn state_of_charge(&self, volts: f32) -> f32 {
+        let (Some(&(first_volts, first_soc)), Some(&(last_volts, last_soc))) =
+            (self.curve.first(), self.curve.last())
+        else {
+            return 0.0;
+        };
+        if volts <= first_volts {
+            return first_soc;
+        }
+        if volts >= last_volts {
+            return last_soc;
+        }
+
+        self.curve
+            .windows(2)
+            .find(|points| volts <= points[1].0)
+            .map_or(last_soc, |points| {
+                let ((v0, soc0), (v1, soc1)) = (points[0], points[1]);
+                soc0 + (volts - v0) / (v1 - v0) * (soc1 - soc0)
+            })
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Read the battery voltage (averaged, calibrated and scaled by the monitor's divider)
+    /// and estimate its state of charge.
+    ///
+    /// The divider registered on the input with [Ads1119::set_divider] is not used; the
+    /// monitor's own divider is.
+    pub fn read_battery(
+        &mut self,
+        monitor: &BatteryMonitor,
+    ) -> Result<BatteryReading, Ads1119Err<I2C::Error>> {
+        let mean = self
+            .read_input_averaged(&monitor.input, monitor.samples)?
+            .mean;
+        let volts = monitor
+            .divider
+            .apply(self.apply_calibration(&monitor.input, single_ended_code_to_voltage(mean)));
+        Ok(BatteryReading {
+            volts,
+            state_of_charge: monitor.state_of_charge(volts),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EPS: f32 = 0.0001;
+
+    fn divider() -> VoltageDivider {
+        VoltageDivider::new(30_000.0, 10_000.0)
+    }
+
+    #[test]
+    fn linear_state_of_charge() {
+        let monitor = BatteryMonitor::new(InputSelection::AN0SingleEnded, divider(), 3.0, 4.0);
+        assert_eq!(monitor.state_of_charge(2.5), 0.0);
+        assert!((monitor.state_of_charge(3.25) - 25.0).abs() < EPS);
+        assert_eq!(monitor.state_of_charge(4.5), 100.0);
+    }
+
+    #[test]
+    fn curve_state_of_charge() {
+        let monitor = BatteryMonitor::li_ion_1s(InputSelection::AN0SingleEnded, divider());
+        assert!((monitor.state_of_charge(3.84) - 50.0).abs() < EPS);
+        assert!((monitor.state_of_charge(3.83) - 47.5).abs() < 0.01);
+        assert_eq!(monitor.state_of_charge(4.3), 100.0);
+    }
+
+    #[test]
+    fn empty_curve() {
+        let monitor = BatteryMonitor::new(InputSelection::AN0SingleEnded, divider(), 3.0, 4.0)
+            .with_curve(vec![]);
+        assert_eq!(monitor.state_of_charge(3.5), 0.0);
+    }
+}
@@ -0,0 +1,266 @@
+//! Command line tool for bringing up and poking at an ADS1119 on Linux.
+//!
+//! Built with the `cli` feature: `cargo run --features cli -- --help`
+
+use std::{
+    error::Error,
+    fs, io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use linux_embedded_hal::I2cdev;
+
+use ads1119::{
+    json::JsonRecord,
+    logger::{Column, CsvLogger},
+    single_ended_rdata_to_scaled_voltage, Ads1119, Config, InputSelection, ADDRESSES,
+};
+
+#[derive(Parser)]
+#[command(name = "ads1119-cli", version, about)]
+struct Cli {
+    /// I2C bus device
+    #[arg(short, long, default_value = "/dev/i2c-7")]
+    bus: String,
+    /// I2C address of the ADS1119, e.g. 0x40
+    #[arg(short, long, default_value = "0x40", value_parser = parse_u8)]
+    address: u8,
+    /// GPIO line of the DRDY pin, e.g. /dev/gpiochip0:17, to wait for its edges instead of
+    /// polling the status register
+    #[cfg(feature = "gpio-cdev")]
+    #[arg(long, value_parser = parse_gpio_line)]
+    drdy: Option<(String, u32)>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Take a single-shot reading of one input
+    Read {
+        /// 0-3 for single-ended inputs, 0-1, 2-3 or 1-2 for differential ones,
+        /// or mid for the inputs shorted to mid-supply
+        #[arg(value_parser = parse_input)]
+        input: InputSelection,
+    },
+    /// Read each single-ended input once
+    Scan,
+    /// Repeatedly read inputs until interrupted with Ctrl-C, then reset the device
+    Monitor {
+        /// Comma separated inputs, see `read`
+        #[arg(short, long, value_delimiter = ',', default_value = "0", value_parser = parse_input)]
+        channels: Vec<InputSelection>,
+        /// Rounds of readings per second
+        #[arg(short, long, default_value_t = 1.0)]
+        rate: f32,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Read or write the config register
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Reset the device to its default state
+    Reset,
+    /// Probe every /dev/i2c-* bus for devices at the ADS1119 addresses.
+    /// The bus and address options are ignored.
+    Detect,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    Get,
+    Set {
+        /// Register value, e.g. 0x60 or 0b0110_0000
+        #[arg(value_parser = parse_u8)]
+        value: u8,
+    },
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    if let Command::Detect = cli.command {
+        return detect();
+    }
+    let dev = I2cdev::new(&cli.bus)?;
+    let mut driver = Ads1119::new(dev, cli.address);
+    #[cfg(feature = "gpio-cdev")]
+    if let Some((chip, line)) = &cli.drdy {
+        driver.set_wait_strategy(ads1119::gpio_cdev::CdevDrdy::new(chip, *line)?);
+    }
+
+    match cli.command {
+        Command::Read { input } => print_reading(&mut driver, &input)?,
+        Command::Scan => {
+            for input in [
+                InputSelection::AN0SingleEnded,
+                InputSelection::AN1SingleEnded,
+                InputSelection::AN2SingleEnded,
+                InputSelection::AN3SingleEnded,
+            ] {
+                print_reading(&mut driver, &input)?;
+            }
+        }
+        Command::Monitor {
+            channels,
+            rate,
+            format,
+        } => monitor(&mut driver, &channels, rate, format)?,
+        Command::Config { action } => match action {
+            ConfigAction::Get => {
+                let config = driver.read_config()?;
+                println!("{:#04x} ({:#010b})", config, config);
+            }
+            ConfigAction::Set { value } => driver.write_config(value)?,
+        },
+        Command::Reset => driver.reset()?,
+        Command::Detect => unreachable!("handled before opening the bus"),
+    }
+    Ok(())
+}
+
+fn print_reading(
+    driver: &mut Ads1119<I2cdev>,
+    input: &InputSelection,
+) -> Result<(), Box<dyn Error>> {
+    let raw = driver.read_input_oneshot(input)?;
+    println!(
+        "{:?}: {} ({:.5}V)",
+        input,
+        raw,
+        single_ended_rdata_to_scaled_voltage(raw)
+    );
+    Ok(())
+}
+
+fn monitor(
+    driver: &mut Ads1119<I2cdev>,
+    channels: &[InputSelection],
+    rate: f32,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err("the rate must be positive".into());
+    }
+    let period = Duration::from_secs_f32(1.0 / rate);
+    let running = Arc::new(AtomicBool::new(true));
+    let handler_running = running.clone();
+    ctrlc::set_handler(move || handler_running.store(false, Ordering::SeqCst))?;
+
+    let mut csv = CsvLogger::new(io::stdout(), &[Column::Raw, Column::Volts]);
+    // keep going after a failed read so the device still gets reset below
+    let result = (|| -> Result<(), Box<dyn Error>> {
+        while running.load(Ordering::SeqCst) {
+            let started = Instant::now();
+            for input in channels {
+                match format {
+                    Format::Text => print_reading(driver, input)?,
+                    Format::Csv => csv.log_input(driver, input)?,
+                    Format::Json => {
+                        let raw = driver.read_input_oneshot(input)?;
+                        JsonRecord::new(&Config::new(input.clone()), raw, SystemTime::now())
+                            .write_line(io::stdout().lock())?;
+                    }
+                }
+            }
+            // sleep in short steps so Ctrl-C is handled promptly at low rates
+            while running.load(Ordering::SeqCst) && started.elapsed() < period {
+                thread::sleep((period - started.elapsed()).min(Duration::from_millis(100)));
+            }
+        }
+        Ok(())
+    })();
+
+    driver.reset()?;
+    result
+}
+
+fn detect() -> Result<(), Box<dyn Error>> {
+    let mut buses: Vec<String> = fs::read_dir("/dev")?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.starts_with("i2c-"))
+        .map(|name| format!("/dev/{}", name))
+        .collect();
+    // numeric order, so i2c-10 comes after i2c-9
+    buses.sort_by_key(|bus| {
+        bus.trim_start_matches("/dev/i2c-")
+            .parse::<u32>()
+            .unwrap_or(u32::MAX)
+    });
+
+    let mut found = 0;
+    for bus in buses {
+        let mut dev = match I2cdev::new(&bus) {
+            Ok(dev) => dev,
+            Err(e) => {
+                eprintln!("{}: {}", bus, e);
+                continue;
+            }
+        };
+        for address in ADDRESSES {
+            // reading the config register doesn't change the device's state
+            let mut driver = Ads1119::new(dev, address);
+            if let Ok(config) = driver.read_config() {
+                println!("{} {:#04x}: config {:#04x}", bus, address, config);
+                found += 1;
+            }
+            dev = driver.destroy();
+        }
+    }
+    if found == 0 {
+        println!("no devices found");
+    }
+    Ok(())
+}
+
+fn parse_input(s: &str) -> Result<InputSelection, String> {
+    match s {
+        "0" => Ok(InputSelection::AN0SingleEnded),
+        "1" => Ok(InputSelection::AN1SingleEnded),
+        "2" => Ok(InputSelection::AN2SingleEnded),
+        "3" => Ok(InputSelection::AN3SingleEnded),
+        "0-1" => Ok(InputSelection::AN0AN1Differential),
+        "2-3" => Ok(InputSelection::AN2AN3Differential),
+        "1-2" => Ok(InputSelection::AN1AN2Differential),
+        "mid" => Ok(InputSelection::ShortedToMidSupply),
+        _ => Err(format!("unknown input {}", s)),
+    }
+}
+
+/// Parse `<chip>:<line offset>`
+#[cfg(feature = "gpio-cdev")]
+fn parse_gpio_line(s: &str) -> Result<(String, u32), String> {
+    let (chip, line) = s
+        .rsplit_once(':')
+        .ok_or_else(|| format!("{}: expected <chip>:<line>", s))?;
+    let line = line.parse().map_err(|e| format!("{}: {}", s, e))?;
+    Ok((chip.to_string(), line))
+}
+
+/// Parse a decimal, `0x` hex or `0b` binary byte
+fn parse_u8(s: &str) -> Result<u8, String> {
+    let s = s.replace('_', "");
+    let parsed = if let Some(hex) = s.strip_prefix("0x") {
+        u8::from_str_radix(hex, 16)
+    } else if let Some(binary) = s.strip_prefix("0b") {
+        u8::from_str_radix(binary, 2)
+    } else {
+        s.parse()
+    };
+    parsed.map_err(|e| format!("{}: {}", s, e))
+}
@@ -0,0 +1,410 @@
+//! Block capture: a run of back-to-back continuous-mode samples into a caller's buffer,
+//! e.g. for a waveform snapshot or a spectrum, optionally started by a [Trigger].
+
+use embedded_hal::i2c::I2c;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::{Ads1119, Ads1119Err, Config, ConversionMode, InputSelection};
+
+/// How closely a [Ads1119::capture] kept to the data rate, from the times the samples
+/// were read on the driver's clock
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CaptureTiming {
+    /// Number of samples captured
+    pub samples: usize,
+    /// Time from the first sample to the last
+    pub duration: Duration,
+    /// The conversion time of the data rate
+    pub nominal_interval: Duration,
+    /// Shortest time between two samples, zero with fewer than two
+    pub min_interval: Duration,
+    /// Longest time between two samples, zero with fewer than two
+    pub max_interval: Duration,
+    /// Conversions lost between samples, see [crate::continuous::Samples::overrun]
+    pub missed: u64,
+}
+
+impl CaptureTiming {
+    /// Mean time between two samples, zero with fewer than two
+    pub fn mean_interval(&self) -> Duration {
+        match self.samples {
+            0 | 1 => Duration::ZERO,
+            n => self.duration / (n - 1) as u32,
+        }
+    }
+
+    /// Samples per second actually captured, None with fewer than two samples
+    pub fn achieved_rate(&self) -> Option<f32> {
+        let interval = self.mean_interval();
+        (!interval.is_zero()).then(|| 1.0 / interval.as_secs_f32())
+    }
+}
+
+// accumulates the times of the samples of a capture
+#[derive(Default)]
+struct TimingTracker {
+    samples: usize,
+    first: Duration,
+    last: Duration,
+    min_interval: Option<Duration>,
+    max_interval: Duration,
+}
+
+impl TimingTracker {
+    fn add(&mut self, timestamp: Duration) {
+        if self.samples == 0 {
+            self.first = timestamp;
+        } else {
+            let interval = timestamp.saturating_sub(self.last);
+            self.min_interval = Some(self.min_interval.map_or(interval, |min| min.min(interval)));
+            self.max_interval = self.max_interval.max(interval);
+        }
+        self.last = timestamp;
+        self.samples += 1;
+    }
+
+    fn finish(self, config: &Config, missed: u64) -> CaptureTiming {
+        CaptureTiming {
+            samples: self.samples,
+            duration: self.last.saturating_sub(self.first),
+            nominal_interval: config.data_rate.conversion_time(),
+            min_interval: self.min_interval.unwrap_or_default(),
+            max_interval: self.max_interval,
+            missed,
+        }
+    }
+}
+
+/// Direction of the crossing that fires a [Trigger]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TriggerEdge {
+    /// From below the level to at or above it
+    Rising,
+    /// From above the level to at or below it
+    Falling,
+}
+
+/// When [Ads1119::capture_triggered] starts recording
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trigger {
+    /// Raw ADC code to cross
+    pub level: i16,
+    pub edge: TriggerEdge,
+    /// Number of samples from before the crossing to keep
+    pub pre_trigger: usize,
+    /// How long to wait for the crossing, forever if None
+    pub timeout: Option<Duration>,
+}
+
+impl Trigger {
+    /// Trigger on a crossing of `level` in the direction of `edge`, without pre-trigger
+    /// samples or timeout
+    pub fn new(level: i16, edge: TriggerEdge) -> Self {
+        Trigger {
+            level,
+            edge,
+            pre_trigger: 0,
+            timeout: None,
+        }
+    }
+
+    pub fn with_pre_trigger(mut self, pre_trigger: usize) -> Self {
+        self.pre_trigger = pre_trigger;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Whether going from `previous` to `sample` fires the trigger
+    pub fn crosses(&self, previous: i16, sample: i16) -> bool {
+        match self.edge {
+            TriggerEdge::Rising => previous < self.level && sample >= self.level,
+            TriggerEdge::Falling => previous > self.level && sample <= self.level,
+        }
+    }
+}
+
+/// Result of [Ads1119::capture_triggered]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TriggeredCapture {
+    /// Index in the buffer of the first sample past the level; the ones before it are the
+    /// pre-trigger samples
+    pub trigger_index: usize,
+    pub timing: CaptureTiming,
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Capture `n` back-to-back continuous-mode samples of `channel` into `buffer[..n]`,
+    /// with the gain, data rate and reference of the last config written by the driver
+    /// (the defaults if it isn't known).
+    ///
+    /// The config is written back in single-shot mode afterwards, which stops the
+    /// conversions. Returns [Ads1119Err::NoSamples] if `n` is 0; panics if `buffer` is
+    /// shorter than `n`. The samples are only valid if the capture succeeded.
+    pub fn capture(
+        &mut self,
+        channel: &InputSelection,
+        n: usize,
+        buffer: &mut [i16],
+    ) -> Result<CaptureTiming, Ads1119Err<I2C::Error>> {
+        if n == 0 {
+            return Err(Ads1119Err::NoSamples);
+        }
+        let config = self.capture_config(channel);
+        self.start_continuous(&config)?;
+
+        let result = self.record(&mut buffer[..n]);
+        // stop the conversions even if the capture failed
+        let stopped = self.write_config(config.bits());
+        let (timing, missed) = result?;
+        stopped?;
+        Ok(timing.finish(&config, missed))
+    }
+
+    fn record(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(TimingTracker, u64), Ads1119Err<I2C::Error>> {
+        let mut samples = self.samples();
+        let mut timing = TimingTracker::default();
+        for slot in buffer.iter_mut() {
+            // the iterator only ends when cancelled
+            *slot = samples.next().ok_or(Ads1119Err::Cancelled)??;
+            timing.add(samples.read_at());
+        }
+        Ok((timing, samples.missed_conversions()))
+    }
+
+    /// Wait for `channel` to cross `trigger.level` in the direction of `trigger.edge`, then
+    /// fill `buffer` with up to `trigger.pre_trigger` samples from before the crossing, the
+    /// sample past the level, and the samples after it. Like [Ads1119::capture], with the
+    /// same config and in continuous mode, stopped afterwards.
+    ///
+    /// Fails with [Ads1119Err::TriggerTimeout] if there's no crossing within
+    /// `trigger.timeout`. Panics unless `trigger.pre_trigger` is shorter than `buffer`.
+    pub fn capture_triggered(
+        &mut self,
+        channel: &InputSelection,
+        trigger: &Trigger,
+        buffer: &mut [i16],
+    ) -> Result<TriggeredCapture, Ads1119Err<I2C::Error>> {
+        assert!(
+            trigger.pre_trigger < buffer.len(),
+            "the buffer must hold the pre-trigger samples and the trigger"
+        );
+        let config = self.capture_config(channel);
+        self.start_continuous(&config)?;
+
+        let result = self.record_triggered(trigger, buffer);
+        // stop the conversions even if the capture failed
+        let stopped = self.write_config(config.bits());
+        let (trigger_index, timing, missed) = result?;
+        stopped?;
+        Ok(TriggeredCapture {
+            trigger_index,
+            timing: timing.finish(&config, missed),
+        })
+    }
+
+    fn record_triggered(
+        &mut self,
+        trigger: &Trigger,
+        buffer: &mut [i16],
+    ) -> Result<(usize, TimingTracker, u64), Ads1119Err<I2C::Error>> {
+        let mut samples = self.samples();
+        // the samples and when they were read on the clock
+        let mut history: VecDeque<(i16, Duration)> = VecDeque::with_capacity(trigger.pre_trigger);
+        let mut armed_at = None;
+        let mut previous = None;
+        let crossing = loop {
+            // the iterator only ends when cancelled
+            let value = samples.next().ok_or(Ads1119Err::Cancelled)??;
+            let sample = (value, samples.read_at());
+            let armed_at = *armed_at.get_or_insert(sample.1);
+            if previous.is_some_and(|previous| trigger.crosses(previous, value)) {
+                break sample;
+            }
+            if let Some(timeout) = trigger.timeout {
+                let waited = sample.1.saturating_sub(armed_at);
+                if waited > timeout {
+                    return Err(Ads1119Err::TriggerTimeout(waited.as_millis()));
+                }
+            }
+            previous = Some(value);
+            if trigger.pre_trigger > 0 {
+                if history.len() == trigger.pre_trigger {
+                    history.pop_front();
+                }
+                history.push_back(sample);
+            }
+        };
+
+        let mut timing = TimingTracker::default();
+        let trigger_index = history.len();
+        let (recorded, rest) = buffer.split_at_mut(trigger_index + 1);
+        for (slot, (value, read_at)) in recorded.iter_mut().zip(history.iter().chain([&crossing])) {
+            *slot = *value;
+            timing.add(*read_at);
+        }
+        for slot in rest.iter_mut() {
+            *slot = samples.next().ok_or(Ads1119Err::Cancelled)??;
+            timing.add(samples.read_at());
+        }
+        Ok((trigger_index, timing, samples.missed_conversions()))
+    }
+
+    /// The config of the captures, see [Ads1119::capture]
+    fn capture_config(&self, channel: &InputSelection) -> Config {
+        // 0 is the config register's reset value
+        Config {
+            input: channel.clone(),
+            conversion_mode: ConversionMode::SingleShot,
+            ..Config::from_bits(self.config.unwrap_or(0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{
+        expect_read_data, expect_read_status, expect_start_sync, expect_write_config, SteppingClock,
+    };
+    use crate::{DataRate, STATUS_CONV_RDY};
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn captures_into_the_buffer_with_timing() {
+        static CLOCK: SteppingClock = SteppingClock::new(Duration::from_millis(1));
+
+        let config = Config::new(InputSelection::AN3SingleEnded).with_data_rate(DataRate::Sps1000);
+        let continuous = config
+            .clone()
+            .with_conversion_mode(ConversionMode::Continuous);
+        let mut transactions = vec![
+            expect_write_config(DEVICE_ADDRESS, DataRate::Sps1000.bits()),
+            expect_write_config(DEVICE_ADDRESS, continuous.bits()),
+            expect_start_sync(DEVICE_ADDRESS),
+        ];
+        for value in [1, 2, 3] {
+            transactions.push(expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY));
+            transactions.push(expect_read_data(DEVICE_ADDRESS, value));
+        }
+        transactions.push(expect_write_config(DEVICE_ADDRESS, config.bits()));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.set_clock(&CLOCK);
+        device.write_config(DataRate::Sps1000.bits()).unwrap();
+
+        let mut buffer = [0; 4];
+        let timing = device
+            .capture(&InputSelection::AN3SingleEnded, 3, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, [1, 2, 3, 0]);
+        assert_eq!(timing.samples, 3);
+        assert_eq!(timing.nominal_interval, Duration::from_millis(1));
+        // the clock is read three times per sample, so they're 3 ms apart
+        assert_eq!(timing.duration, Duration::from_millis(6));
+        assert_eq!(timing.min_interval, Duration::from_millis(3));
+        assert_eq!(timing.max_interval, Duration::from_millis(3));
+        assert_eq!(timing.missed, 4);
+        device.destroy().done();
+    }
+
+    fn continuous_transactions(config: &Config, values: &[i16]) -> Vec<I2cTransaction> {
+        let continuous = config
+            .clone()
+            .with_conversion_mode(ConversionMode::Continuous);
+        let mut transactions = vec![
+            expect_write_config(DEVICE_ADDRESS, continuous.bits()),
+            expect_start_sync(DEVICE_ADDRESS),
+        ];
+        for value in values {
+            transactions.push(expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY));
+            transactions.push(expect_read_data(DEVICE_ADDRESS, *value));
+        }
+        transactions.push(expect_write_config(DEVICE_ADDRESS, config.bits()));
+        transactions
+    }
+
+    #[test]
+    fn failed_capture_stops_the_conversions() {
+        let config = Config::new(InputSelection::AN1SingleEnded);
+        let continuous = config
+            .clone()
+            .with_conversion_mode(ConversionMode::Continuous);
+        let transactions = [
+            expect_write_config(DEVICE_ADDRESS, continuous.bits()),
+            expect_start_sync(DEVICE_ADDRESS),
+            expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            expect_read_data(DEVICE_ADDRESS, 1),
+            expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            expect_read_data(DEVICE_ADDRESS, 2).with_error(ErrorKind::Other),
+            // written back even though the capture failed
+            expect_write_config(DEVICE_ADDRESS, config.bits()),
+        ];
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let mut buffer = [0; 3];
+        assert!(matches!(
+            device.capture(&InputSelection::AN1SingleEnded, 3, &mut buffer),
+            Err(Ads1119Err::I2CError { .. })
+        ));
+        device.destroy().done();
+    }
+
+    #[test]
+    fn trigger_crossings() {
+        let rising = Trigger::new(100, TriggerEdge::Rising);
+        assert!(rising.crosses(99, 100));
+        assert!(!rising.crosses(100, 101));
+        assert!(!rising.crosses(101, 99));
+        let falling = Trigger::new(100, TriggerEdge::Falling);
+        assert!(falling.crosses(101, 100));
+        assert!(!falling.crosses(99, 100));
+    }
+
+    #[test]
+    fn triggered_capture_keeps_the_pre_trigger_samples() {
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        let transactions = continuous_transactions(&config, &[10, 20, 30, 40, 150, 160, 170]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let trigger = Trigger::new(100, TriggerEdge::Rising).with_pre_trigger(2);
+        let mut buffer = [0; 5];
+        let capture = device
+            .capture_triggered(&InputSelection::AN0SingleEnded, &trigger, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, [30, 40, 150, 160, 170]);
+        assert_eq!(capture.trigger_index, 2);
+        assert_eq!(capture.timing.samples, 5);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn triggered_capture_times_out() {
+        static CLOCK: SteppingClock = SteppingClock::new(Duration::from_millis(10));
+
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        let transactions = continuous_transactions(&config, &[10, 20]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.set_clock(&CLOCK);
+
+        let trigger = Trigger::new(100, TriggerEdge::Rising).with_timeout(Duration::from_millis(1));
+        let mut buffer = [0; 2];
+        assert!(matches!(
+            device.capture_triggered(&InputSelection::AN0SingleEnded, &trigger, &mut buffer),
+            Err(Ads1119Err::TriggerTimeout(_))
+        ));
+        device.destroy().done();
+    }
+}
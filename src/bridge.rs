@@ -0,0 +1,130 @@
+//! Strain-gauge bridges (load cells) read ratiometrically.
+//!
+//! The bridge excitation also drives REFP/REFN, and its output is read differentially at gain 4,
+//! so the result is directly in mV/V and independent of the excitation voltage.
+
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    ratiometric::rdata_to_ratio, Ads1119, Ads1119Err, Config, ConversionMode, Gain, InputSelection,
+    VoltageReference,
+};
+
+/// A load cell (or other full bridge) on a differential input
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadCell {
+    pub input: InputSelection,
+    /// Rated output at full capacity, in mV/V (typically 1 to 3)
+    pub sensitivity: f32,
+    /// Load at which the cell outputs its rated sensitivity, in the user's units (e.g. kg)
+    pub capacity: f32,
+    /// Bridge output with no load, in mV/V. Set by [Ads1119::tare_load_cell].
+    pub zero: f32,
+    /// Number of conversions averaged for each reading
+    pub samples: usize,
+}
+
+impl LoadCell {
+    pub fn new(input: InputSelection, sensitivity: f32, capacity: f32) -> Self {
+        LoadCell {
+            input,
+            sensitivity,
+            capacity,
+            zero: 0.0,
+            samples: 4,
+        }
+    }
+
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Bridge preset: the cell's input, gain 4 and the external (ratiometric) reference
+    pub fn config(&self) -> Config {
+        Config::new(self.input.clone())
+            .with_gain(Gain::Four)
+            .with_voltage_reference(VoltageReference::External)
+            .with_conversion_mode(ConversionMode::SingleShot)
+    }
+
+    /// Load, in the cell's units, for the given bridge output in mV/V
+    pub fn load(&self, millivolts_per_volt: f32) -> f32 {
+        (millivolts_per_volt - self.zero) / self.sensitivity * self.capacity
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Average `samples` conversions of the bridge and return its output, in mV/V
+    pub fn read_bridge(
+        &mut self,
+        config: &Config,
+        samples: usize,
+    ) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let readings = self.read_samples(config.bits(), samples)?;
+        let mean_ratio = readings
+            .iter()
+            .map(|&raw| rdata_to_ratio(raw, config.gain))
+            .sum::<f32>()
+            / readings.len() as f32;
+        Ok(mean_ratio * 1000.0)
+    }
+
+    /// Measure the unloaded bridge output and store it as the cell's zero
+    pub fn tare_load_cell(
+        &mut self,
+        load_cell: &mut LoadCell,
+    ) -> Result<(), Ads1119Err<I2C::Error>> {
+        load_cell.zero = self.read_bridge(&load_cell.config(), load_cell.samples)?;
+        Ok(())
+    }
+
+    /// Read the load on the cell, in the cell's units
+    pub fn read_load_cell(&mut self, load_cell: &LoadCell) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let millivolts_per_volt = self.read_bridge(&load_cell.config(), load_cell.samples)?;
+        Ok(load_cell.load(millivolts_per_volt))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_oneshot_read_config;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    fn bridge_read_transactions(value: i16) -> Vec<I2cTransaction> {
+        // AIN0-AIN1, gain 4, external reference
+        expect_oneshot_read_config(DEVICE_ADDRESS, 0b0001_0001, value)
+    }
+
+    #[test]
+    fn load_from_bridge_output() {
+        let mut load_cell = LoadCell::new(InputSelection::AN0AN1Differential, 2.0, 50.0);
+        assert!((load_cell.load(1.0) - 25.0).abs() < 0.0001);
+        load_cell.zero = 0.1;
+        assert!((load_cell.load(2.1) - 50.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn can_tare_and_read() {
+        // 0.5 mV/V and 1.5 mV/V, as codes at gain 4
+        let zero = 0.0005 * 4.0 * 0x7FFF as f32;
+        let loaded = 0.0015 * 4.0 * 0x7FFF as f32;
+        let mut transactions = bridge_read_transactions(zero as i16);
+        transactions.extend(bridge_read_transactions(loaded as i16));
+
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        let mut load_cell =
+            LoadCell::new(InputSelection::AN0AN1Differential, 2.0, 10.0).with_samples(1);
+        device.tare_load_cell(&mut load_cell).unwrap();
+        assert!((load_cell.zero - 0.5).abs() < 0.01);
+        // 1 mV/V above zero is half of the 10kg capacity
+        assert!((device.read_load_cell(&load_cell).unwrap() - 5.0).abs() < 0.05);
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,309 @@
+use embedded_hal::i2c::I2c;
+
+#[cfg(feature = "std")]
+use crate::{single_ended_code_to_voltage, single_ended_rdata_to_scaled_voltage, Ads1119Err};
+use crate::{Ads1119, InputSelection, MUX_COUNT};
+
+/// Number of conversions averaged for each point of [Ads1119::calibrate_two_point]
+pub const CALIBRATION_SAMPLES: usize = 16;
+
+/// Offset and scale correction for one input, applied to the measured voltage:
+///
+/// `corrected = (measured - offset) * scale`
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibration {
+    /// Offset, in volts
+    pub offset: f32,
+    pub scale: f32,
+}
+
+impl Calibration {
+    pub fn new(offset: f32, scale: f32) -> Self {
+        Calibration { offset, scale }
+    }
+
+    /// Apply the correction to a measured voltage
+    pub fn apply(&self, volts: f32) -> f32 {
+        (volts - self.offset) * self.scale
+    }
+
+    /// Compute the calibration that maps the two measured voltages onto their known references.
+    ///
+    /// Returns `None` if the measurements or the references are equal, as no slope can be derived.
+    pub fn from_two_points(
+        measured_low: f32,
+        low_ref: f32,
+        measured_high: f32,
+        high_ref: f32,
+    ) -> Option<Self> {
+        if measured_high == measured_low || high_ref == low_ref {
+            return None;
+        }
+        let scale = (high_ref - low_ref) / (measured_high - measured_low);
+        Some(Calibration::new(measured_low - low_ref / scale, scale))
+    }
+}
+
+impl Default for Calibration {
+    /// No correction
+    fn default() -> Self {
+        Calibration::new(0.0, 1.0)
+    }
+}
+
+/// The calibrations of all inputs of a device.
+///
+/// With the `serde` feature, the table can be persisted (e.g. to flash or disk) and loaded back
+/// into a driver with [Ads1119::load_calibration_table].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalibrationTable {
+    // indexed by InputSelection::mux_index
+    entries: [Option<Calibration>; MUX_COUNT],
+}
+
+impl CalibrationTable {
+    pub fn new() -> Self {
+        CalibrationTable::default()
+    }
+
+    /// The calibration of the given input, if any
+    pub fn get(&self, input: &InputSelection) -> Option<Calibration> {
+        self.entries[input.mux_index()]
+    }
+
+    /// Set the calibration of the given input, replacing any previous one
+    pub fn set(&mut self, input: &InputSelection, calibration: Calibration) {
+        self.entries[input.mux_index()] = Some(calibration);
+    }
+
+    /// Remove the calibration of the given input
+    pub fn clear(&mut self, input: &InputSelection) {
+        self.entries[input.mux_index()] = None;
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Register the calibration for the given input. It replaces any previous calibration and is
+    /// applied by [Ads1119::read_input_voltage].
+    pub fn set_calibration(&mut self, input: &InputSelection, offset: f32, scale: f32) {
+        self.calibrations
+            .set(input, Calibration::new(offset, scale));
+    }
+
+    /// Remove the calibration of the given input
+    pub fn clear_calibration(&mut self, input: &InputSelection) {
+        self.calibrations.clear(input);
+    }
+
+    /// The calibration registered for the given input, if any
+    pub fn calibration(&self, input: &InputSelection) -> Option<Calibration> {
+        self.calibrations.get(input)
+    }
+
+    /// The calibrations of all inputs
+    pub fn calibration_table(&self) -> &CalibrationTable {
+        &self.calibrations
+    }
+
+    /// Replace the calibrations of all inputs, e.g. with a table persisted by an earlier run
+    pub fn load_calibration_table(&mut self, table: CalibrationTable) {
+        self.calibrations = table;
+    }
+
+    /// Apply the calibration of the given input to a voltage. Voltages of uncalibrated inputs
+    /// are returned unchanged.
+    pub fn apply_calibration(&self, input: &InputSelection, volts: f32) -> f32 {
+        self.calibration(input)
+            .map_or(volts, |calibration| calibration.apply(volts))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Read the given input with [Ads1119::read_input_oneshot], convert it to a voltage
+    /// and apply the input's calibration, then its voltage divider, then its smoothing, if
+    /// they are registered (see [Ads1119::set_divider] and [Ads1119::set_smoothing]).
+    ///
+    /// The conversion assumes the default configuration, see [single_ended_rdata_to_scaled_voltage].
+    pub fn read_input_voltage(
+        &mut self,
+        input: &InputSelection,
+    ) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let raw = self.read_input_oneshot(input)?;
+        let volts = self.apply_calibration(input, single_ended_rdata_to_scaled_voltage(raw));
+        let volts = self.apply_divider(input, volts);
+        Ok(self.apply_smoothing(input, volts))
+    }
+
+    /// Guided two-point calibration of the given input.
+    ///
+    /// `apply_reference` is called with `low_ref`, then with `high_ref` (both in volts). It must
+    /// return once that voltage is applied to the input, e.g. after setting a bench supply or
+    /// prompting an operator. Each point is measured as the mean of [CALIBRATION_SAMPLES]
+    /// conversions.
+    ///
+    /// If the input has a voltage divider, the references are the voltages at the divider's
+    /// input, like the values returned by [Ads1119::read_input_voltage].
+    ///
+    /// The resulting calibration replaces the input's previous one and is returned.
+    pub fn calibrate_two_point<F>(
+        &mut self,
+        input: &InputSelection,
+        low_ref: f32,
+        high_ref: f32,
+        mut apply_reference: F,
+    ) -> Result<Calibration, Ads1119Err<I2C::Error>>
+    where
+        F: FnMut(f32),
+    {
+        apply_reference(low_ref);
+        let measured_low = self.read_input_averaged(input, CALIBRATION_SAMPLES)?.mean;
+        apply_reference(high_ref);
+        let measured_high = self.read_input_averaged(input, CALIBRATION_SAMPLES)?.mean;
+
+        // the calibration applies before the divider, so refer the references to the ADC input
+        let divider_factor = self.divider(input).map_or(1.0, |divider| divider.factor());
+        let calibration = Calibration::from_two_points(
+            single_ended_code_to_voltage(measured_low),
+            low_ref / divider_factor,
+            single_ended_code_to_voltage(measured_high),
+            high_ref / divider_factor,
+        )
+        .ok_or(Ads1119Err::DegenerateCalibration)?;
+        self.calibrations.set(input, calibration);
+        Ok(calibration)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+    const EPS: f32 = 0.0001;
+
+    #[test]
+    fn calibration_is_per_input() {
+        let mut device = Ads1119::new(I2cMock::new(&[]), DEVICE_ADDRESS);
+        device.set_calibration(&InputSelection::AN1SingleEnded, 0.1, 2.0);
+        assert_eq!(device.calibration(&InputSelection::AN0SingleEnded), None);
+        assert!((device.apply_calibration(&InputSelection::AN1SingleEnded, 1.1) - 2.0).abs() < EPS);
+        assert_eq!(
+            device.apply_calibration(&InputSelection::AN2SingleEnded, 1.1),
+            1.1
+        );
+        device.clear_calibration(&InputSelection::AN1SingleEnded);
+        assert_eq!(device.calibration(&InputSelection::AN1SingleEnded), None);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn read_input_voltage_applies_calibration() {
+        let input = InputSelection::AN0SingleEnded;
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                // half scale, 1.024V
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0x40, 0x00]),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        device.set_calibration(&input, 0.024, 0.5);
+        assert!((device.read_input_voltage(&input).unwrap() - 0.5).abs() < EPS);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn two_point_calibration_maps_references() {
+        let calibration = Calibration::from_two_points(0.02, 0.0, 1.52, 1.5).unwrap();
+        assert!(calibration.apply(0.02).abs() < EPS);
+        assert!((calibration.apply(1.52) - 1.5).abs() < EPS);
+        assert_eq!(Calibration::from_two_points(1.0, 0.0, 1.0, 1.5), None);
+    }
+
+    #[test]
+    fn can_calibrate_two_point() {
+        let input = InputSelection::AN2SingleEnded;
+        let mut transactions = vec![];
+        // 0x0200 reads as 32mV, 0x4000 as 1.024V
+        for value in [0x0200i16, 0x4000] {
+            transactions.push(I2cTransaction::write(
+                DEVICE_ADDRESS,
+                vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+            ));
+            for _ in 0..CALIBRATION_SAMPLES {
+                transactions.extend([
+                    I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                    I2cTransaction::write_read(
+                        DEVICE_ADDRESS,
+                        vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                        vec![STATUS_CONV_RDY],
+                    ),
+                    I2cTransaction::write_read(
+                        DEVICE_ADDRESS,
+                        vec![CmdFlags::RDATA],
+                        value.to_be_bytes().to_vec(),
+                    ),
+                ]);
+            }
+        }
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        let mut references = vec![];
+        let calibration = device
+            .calibrate_two_point(&input, 0.0, 1.0, |reference| references.push(reference))
+            .unwrap();
+        assert_eq!(references, vec![0.0, 1.0]);
+        assert_eq!(device.calibration(&input), Some(calibration));
+        assert!(device.apply_calibration(&input, 0.032).abs() < EPS);
+        assert!((device.apply_calibration(&input, 1.024) - 1.0).abs() < EPS);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn can_load_calibration_table() {
+        let mut table = CalibrationTable::new();
+        table.set(&InputSelection::AN3SingleEnded, Calibration::new(0.5, 2.0));
+        let mut device = Ads1119::new(I2cMock::new(&[]), DEVICE_ADDRESS);
+        device.load_calibration_table(table.clone());
+        assert_eq!(device.calibration_table(), &table);
+        assert_eq!(
+            device.calibration(&InputSelection::AN3SingleEnded),
+            Some(Calibration::new(0.5, 2.0))
+        );
+        device.destroy().done();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn calibration_table_round_trips_through_serde() {
+        let mut table = CalibrationTable::new();
+        table.set(
+            &InputSelection::AN0SingleEnded,
+            Calibration::new(-0.001, 1.01),
+        );
+        let json = serde_json::to_string(&table).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CalibrationTable>(&json).unwrap(),
+            table
+        );
+    }
+}
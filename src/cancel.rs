@@ -0,0 +1,45 @@
+//! Cancelling the blocking reads from another thread, so a service can shut down without
+//! waiting out a conversion timeout. See [Ads1119::set_cancellation_token].
+//!
+//! [Ads1119::set_cancellation_token]: crate::Ads1119::set_cancellation_token
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag checked by the driver each time it waits for a conversion.
+///
+/// Clones share the flag; once cancelled, a token stays cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make the reads of the devices using this token fail with
+    /// [crate::Ads1119Err::Cancelled], and end their sample iterators
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clones_share_the_flag() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+}
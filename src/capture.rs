@@ -0,0 +1,246 @@
+//! Compact binary format for storing continuous captures and reading them back.
+//!
+//! Enabled with the `std` feature.
+//!
+//! A capture starts with a header: the [MAGIC] bytes, the format [VERSION] and the config
+//! register value the capture was taken with. Each sample follows as a record of the time since
+//! the start of the capture, in microseconds (`u64`), and the raw ADC code (`i16`).
+//! All values are little-endian.
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use embedded_hal::i2c::I2c;
+
+use crate::logger::LogError;
+use crate::{Ads1119, Ads1119Err, Config};
+
+/// Bytes every capture starts with
+pub const MAGIC: [u8; 4] = *b"A119";
+/// Version of the format written by [CaptureWriter]
+pub const VERSION: u8 = 1;
+
+const RECORD_LEN: usize = 10;
+
+/// A sample of a capture
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CaptureSample {
+    /// Time since the start of the capture
+    pub timestamp: Duration,
+    pub raw: i16,
+}
+
+/// Writes a capture, see the [module](self) documentation for the format.
+pub struct CaptureWriter<W> {
+    writer: W,
+    // when the first capture started, on the driver's clock
+    started: Option<Duration>,
+}
+
+impl<W> CaptureWriter<W>
+where
+    W: Write,
+{
+    /// Write the header for a capture taken with the given config register value.
+    /// Sample timestamps of [CaptureWriter::capture] are relative to the start of the first
+    /// capture, on the driver's clock (see [Ads1119::set_clock]).
+    pub fn new(mut writer: W, config: u8) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION, config])?;
+        Ok(CaptureWriter {
+            writer,
+            started: None,
+        })
+    }
+
+    /// Destroy the writer and return the underlying one
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    pub fn write_sample(&mut self, sample: &CaptureSample) -> io::Result<()> {
+        let mut record = [0u8; RECORD_LEN];
+        record[..8].copy_from_slice(&(sample.timestamp.as_micros() as u64).to_le_bytes());
+        record[8..].copy_from_slice(&sample.raw.to_le_bytes());
+        self.writer.write_all(&record)
+    }
+
+    /// Start continuous conversions with the given config and record `n` samples.
+    ///
+    /// The config should be the one passed to [CaptureWriter::new]. The device is left
+    /// converting, see [Ads1119::start_continuous].
+    pub fn capture<I2C>(
+        &mut self,
+        device: &mut Ads1119<I2C>,
+        config: &Config,
+        n: usize,
+    ) -> Result<(), LogError<I2C::Error>>
+    where
+        I2C: I2c,
+    {
+        let started = *self.started.get_or_insert_with(|| device.now());
+        device.start_continuous(config).map_err(Ads1119Err::from)?;
+        let mut samples = device.samples();
+        for _ in 0..n {
+            let Some(sample) = samples.next() else {
+                break;
+            };
+            let raw = sample?;
+            self.write_sample(&CaptureSample {
+                timestamp: samples.read_at().saturating_sub(started),
+                raw,
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reads a capture written by [CaptureWriter], iterating over its samples.
+pub struct CaptureReader<R> {
+    reader: R,
+    config: u8,
+}
+
+impl<R> CaptureReader<R>
+where
+    R: Read,
+{
+    /// Read and check the header of a capture.
+    ///
+    /// Returns an [io::ErrorKind::InvalidData] error if it isn't a capture in a known version.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 6];
+        reader.read_exact(&mut header)?;
+        if header[..4] != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a capture"));
+        }
+        if header[4] != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported capture version {}", header[4]),
+            ));
+        }
+        Ok(CaptureReader {
+            reader,
+            config: header[5],
+        })
+    }
+
+    /// The config register value the capture was taken with
+    pub fn config(&self) -> u8 {
+        self.config
+    }
+}
+
+impl<R> Iterator for CaptureReader<R>
+where
+    R: Read,
+{
+    type Item = io::Result<CaptureSample>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = [0u8; RECORD_LEN];
+        // a clean end of the capture is at a record boundary
+        match self.reader.read(&mut record[..1]) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        if let Err(e) = self.reader.read_exact(&mut record[1..]) {
+            return Some(Err(e));
+        }
+        let mut timestamp = [0u8; 8];
+        timestamp.copy_from_slice(&record[..8]);
+        Some(Ok(CaptureSample {
+            timestamp: Duration::from_micros(u64::from_le_bytes(timestamp)),
+            raw: i16::from_le_bytes([record[8], record[9]]),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, InputSelection, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn capture_round_trips() {
+        let samples = [
+            CaptureSample {
+                timestamp: Duration::from_micros(0),
+                raw: -32768,
+            },
+            CaptureSample {
+                timestamp: Duration::from_micros(1_100),
+                raw: 1234,
+            },
+        ];
+        let mut writer = CaptureWriter::new(Vec::new(), 0b0110_0010).unwrap();
+        for sample in &samples {
+            writer.write_sample(sample).unwrap();
+        }
+        let bytes = writer.into_inner();
+        assert_eq!(bytes.len(), 6 + 2 * RECORD_LEN);
+
+        let reader = CaptureReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.config(), 0b0110_0010);
+        let read: Vec<CaptureSample> = reader.map(Result::unwrap).collect();
+        assert_eq!(read, samples);
+    }
+
+    #[test]
+    fn reader_rejects_bad_input() {
+        let not_a_capture = CaptureReader::new(&b"RIFF\x01\x00"[..]);
+        assert_eq!(
+            not_a_capture.err().unwrap().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        // a truncated record is an error, not the end of the capture
+        let mut bytes = CaptureWriter::new(Vec::new(), 0).unwrap().into_inner();
+        bytes.extend([1, 2, 3]);
+        let mut reader = CaptureReader::new(bytes.as_slice()).unwrap();
+        assert!(reader.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn can_capture_continuous() {
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        let status_read = I2cTransaction::write_read(
+            DEVICE_ADDRESS,
+            vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+            vec![STATUS_CONV_RDY],
+        );
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, 0b0110_0010],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                status_read.clone(),
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0, 7]),
+                status_read,
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0, 8]),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        let mut writer = CaptureWriter::new(Vec::new(), config.bits()).unwrap();
+        writer.capture(&mut device, &config, 2).unwrap();
+
+        let bytes = writer.into_inner();
+        let raw: Vec<i16> = CaptureReader::new(bytes.as_slice())
+            .unwrap()
+            .map(|sample| sample.unwrap().raw)
+            .collect();
+        assert_eq!(raw, vec![7, 8]);
+        device.destroy().done();
+    }
+}
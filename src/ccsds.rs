@@ -0,0 +1,218 @@
+//! Telemetry packets in the CCSDS Space Packet layout, for spacecraft and UAV downlink
+//! pipelines. See CCSDS 133.0-B Space Packet Protocol.
+//!
+//! Enabled with the `ccsds` feature.
+//!
+//! Each packet is a telemetry packet with a secondary header and no segmentation, all
+//! fields big-endian:
+//! - the 6-byte primary header: version 0, type 0 (telemetry), secondary header flag 1,
+//!   the 11-bit APID, sequence flags `0b11` (unsegmented), the 14-bit sequence count and
+//!   the length of the data field minus one
+//! - the secondary header: a 6-byte timestamp of 4 bytes of seconds and 2 bytes of
+//!   1/65536 seconds (a CUC time code without P-field), from the driver's timestamps
+//!   (see [crate::clock::TimestampSource])
+//! - one 16-bit word per channel: the raw ADC code, in the order of the scan
+
+use std::time::Duration;
+
+use crate::scan::ScanReading;
+
+/// Largest APID, which has 11 bits
+pub const MAX_APID: u16 = 0x7ff;
+/// Length of the primary header
+pub const PRIMARY_HEADER_LEN: usize = 6;
+/// Length of the timestamp in the secondary header
+pub const TIMESTAMP_LEN: usize = 6;
+
+// the sequence count has 14 bits
+const SEQUENCE_COUNT_MASK: u16 = 0x3fff;
+// unsegmented user data
+const SEQUENCE_FLAGS: u16 = 0b11 << 14;
+const SECONDARY_HEADER_FLAG: u16 = 1 << 11;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum PacketError {
+    #[error("{0} bytes is too short for a packet")]
+    TooShort(usize),
+    #[error("the packet length field says {expected} bytes, but there are {actual}")]
+    LengthMismatch { expected: usize, actual: usize },
+    #[error("not a telemetry packet with a secondary header")]
+    UnexpectedHeader,
+    #[error("the channel words don't fill the data field")]
+    OddLength,
+}
+
+/// Packs scans into telemetry packets under one APID, counting their sequence
+#[derive(Clone, Debug, PartialEq)]
+pub struct PacketEncoder {
+    apid: u16,
+    sequence_count: u16,
+}
+
+impl PacketEncoder {
+    /// Encode packets with the given APID, the sequence count starting at 0. Panics if
+    /// `apid` is above [MAX_APID].
+    pub fn new(apid: u16) -> Self {
+        assert!(apid <= MAX_APID, "the APID has 11 bits");
+        PacketEncoder {
+            apid,
+            sequence_count: 0,
+        }
+    }
+
+    pub fn apid(&self) -> u16 {
+        self.apid
+    }
+
+    /// Sequence count of the next packet
+    pub fn sequence_count(&self) -> u16 {
+        self.sequence_count
+    }
+
+    /// Length of a packet with `channels` words
+    pub fn packet_len(channels: usize) -> usize {
+        PRIMARY_HEADER_LEN + TIMESTAMP_LEN + 2 * channels
+    }
+
+    /// Encode the readings of a scan, e.g. from [crate::Ads1119::run_scan], stamped with the
+    /// time of the first reading
+    pub fn encode_scan(&mut self, readings: &[ScanReading]) -> Vec<u8> {
+        let timestamp = readings
+            .first()
+            .map_or(Duration::ZERO, |reading| reading.timestamp);
+        let words: Vec<i16> = readings.iter().map(|reading| reading.raw).collect();
+        self.encode(timestamp, &words)
+    }
+
+    /// Encode a packet of the given channel words. Panics if they don't fit the 16-bit
+    /// length field.
+    pub fn encode(&mut self, timestamp: Duration, words: &[i16]) -> Vec<u8> {
+        let len = PacketEncoder::packet_len(words.len());
+        let data_len =
+            u16::try_from(len - PRIMARY_HEADER_LEN - 1).expect("too many channels for one packet");
+
+        let mut packet = Vec::with_capacity(len);
+        packet.extend_from_slice(&(SECONDARY_HEADER_FLAG | self.apid).to_be_bytes());
+        packet.extend_from_slice(&(SEQUENCE_FLAGS | self.sequence_count).to_be_bytes());
+        packet.extend_from_slice(&data_len.to_be_bytes());
+        packet.extend_from_slice(&encode_timestamp(timestamp));
+        for word in words {
+            packet.extend_from_slice(&word.to_be_bytes());
+        }
+        self.sequence_count = (self.sequence_count + 1) & SEQUENCE_COUNT_MASK;
+        packet
+    }
+}
+
+fn encode_timestamp(timestamp: Duration) -> [u8; TIMESTAMP_LEN] {
+    // the seconds wrap after 136 years
+    let seconds = timestamp.as_secs() as u32;
+    let fraction = ((u64::from(timestamp.subsec_nanos()) << 16) / 1_000_000_000) as u16;
+    let mut bytes = [0; TIMESTAMP_LEN];
+    bytes[..4].copy_from_slice(&seconds.to_be_bytes());
+    bytes[4..].copy_from_slice(&fraction.to_be_bytes());
+    bytes
+}
+
+/// A packet decoded by [TelemetryPacket::decode], e.g. on the ground side
+#[derive(Clone, Debug, PartialEq)]
+pub struct TelemetryPacket {
+    pub apid: u16,
+    pub sequence_count: u16,
+    /// To 1/65536 s
+    pub timestamp: Duration,
+    pub words: Vec<i16>,
+}
+
+impl TelemetryPacket {
+    /// Decode a packet encoded by [PacketEncoder]
+    pub fn decode(bytes: &[u8]) -> Result<Self, PacketError> {
+        if bytes.len() < PacketEncoder::packet_len(0) {
+            return Err(PacketError::TooShort(bytes.len()));
+        }
+        let field = |index: usize| u16::from_be_bytes([bytes[index], bytes[index + 1]]);
+        let identification = field(0);
+        // version 0, telemetry, with a secondary header
+        if identification & !MAX_APID != SECONDARY_HEADER_FLAG {
+            return Err(PacketError::UnexpectedHeader);
+        }
+        let expected = PRIMARY_HEADER_LEN + usize::from(field(4)) + 1;
+        if expected != bytes.len() {
+            return Err(PacketError::LengthMismatch {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        let words = bytes[PRIMARY_HEADER_LEN + TIMESTAMP_LEN..].chunks_exact(2);
+        if !words.remainder().is_empty() {
+            return Err(PacketError::OddLength);
+        }
+
+        let seconds = u32::from_be_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]);
+        let fraction = field(10);
+        let nanos = (u64::from(fraction) * 1_000_000_000) >> 16;
+        Ok(TelemetryPacket {
+            apid: identification & MAX_APID,
+            sequence_count: field(2) & SEQUENCE_COUNT_MASK,
+            timestamp: Duration::new(seconds.into(), nanos as u32),
+            words: words
+                .map(|word| i16::from_be_bytes([word[0], word[1]]))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encodes_the_header_and_words() {
+        let mut encoder = PacketEncoder::new(0x123);
+        let packet = encoder.encode(Duration::from_millis(1500), &[1, -2]);
+        assert_eq!(
+            packet,
+            [
+                0x09, 0x23, // secondary header flag and APID
+                0xc0, 0x00, // unsegmented, sequence count 0
+                0x00, 0x09, // 10 bytes of data field
+                0x00, 0x00, 0x00, 0x01, 0x80, 0x00, // 1.5 s
+                0x00, 0x01, 0xff, 0xfe, // the words
+            ]
+        );
+        assert_eq!(packet.len(), PacketEncoder::packet_len(2));
+        assert_eq!(encoder.sequence_count(), 1);
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut encoder = PacketEncoder::new(MAX_APID);
+        encoder.sequence_count = SEQUENCE_COUNT_MASK;
+        let timestamp = Duration::from_micros(12_345_678);
+        let packet = encoder.encode(timestamp, &[i16::MIN, 0, i16::MAX]);
+        // the sequence count wraps
+        assert_eq!(encoder.sequence_count(), 0);
+
+        let decoded = TelemetryPacket::decode(&packet).unwrap();
+        assert_eq!(decoded.apid, MAX_APID);
+        assert_eq!(decoded.sequence_count, SEQUENCE_COUNT_MASK);
+        assert!(timestamp - decoded.timestamp < Duration::from_micros(16));
+        assert_eq!(decoded.words, [i16::MIN, 0, i16::MAX]);
+    }
+
+    #[test]
+    fn rejects_truncated_packets() {
+        let packet = PacketEncoder::new(1).encode(Duration::ZERO, &[7]);
+        assert_eq!(
+            TelemetryPacket::decode(&packet[..13]),
+            Err(PacketError::LengthMismatch {
+                expected: 14,
+                actual: 13
+            })
+        );
+        assert_eq!(
+            TelemetryPacket::decode(&packet[..4]),
+            Err(PacketError::TooShort(4))
+        );
+    }
+}
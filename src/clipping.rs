@@ -0,0 +1,139 @@
+//! Detection of readings at or near the ends of the ADC's range, whose value can't be
+//! trusted: the input may be beyond full scale, so the gain should be lowered or the
+//! wiring checked.
+
+use embedded_hal::i2c::I2c;
+
+use crate::Ads1119;
+#[cfg(feature = "std")]
+use crate::{reading::Reading, Ads1119Err, Config, Gain};
+
+/// Default margin of [Clipping::of]: within 1% of full scale is near clipping
+pub const DEFAULT_CLIPPING_MARGIN: f32 = 0.01;
+
+/// How close a reading is to the ends of the range
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Clipping {
+    #[default]
+    InRange,
+    /// Within the margin of full scale; the input may be clipped by the next reading
+    NearClipping,
+    /// At positive or negative full scale: the input is likely beyond the range
+    Clipped,
+}
+
+impl Clipping {
+    /// Classify a raw conversion result. `margin` is a fraction of full scale, e.g. 0.01
+    /// flags readings within 1% of either end as [Clipping::NearClipping].
+    pub fn of(raw_data: i16, margin: f32) -> Self {
+        // the device outputs 0x7FFF and 0x8000 for inputs beyond full scale
+        if raw_data == i16::MAX || raw_data == i16::MIN {
+            return Clipping::Clipped;
+        }
+        let threshold = (1.0 - margin) * i16::MAX as f32;
+        if raw_data.unsigned_abs() as f32 >= threshold {
+            Clipping::NearClipping
+        } else {
+            Clipping::InRange
+        }
+    }
+
+    /// True unless the reading is in range
+    pub fn is_suspect(&self) -> bool {
+        *self != Clipping::InRange
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Margin used to flag the readings returned by the driver, see [Clipping::of].
+    /// The default is [DEFAULT_CLIPPING_MARGIN].
+    pub fn set_clipping_margin(&mut self, margin: f32) {
+        self.clipping_margin = margin;
+    }
+
+    /// Classify a raw conversion result with the driver's margin
+    pub fn clipping(&self, raw_data: i16) -> Clipping {
+        Clipping::of(raw_data, self.clipping_margin)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Read `config` with auto-ranging: at gain 4 first, then at gain 1 if that result is
+    /// [Clipping::Clipped]. The gain of the returned reading is the one that produced it.
+    ///
+    /// The gain of `config` is ignored. See [Ads1119::read].
+    pub fn read_auto_range(&mut self, config: &Config) -> Result<Reading, Ads1119Err<I2C::Error>> {
+        let reading = self.read(&config.clone().with_gain(Gain::Four))?;
+        if reading.clipping != Clipping::Clipped {
+            return Ok(reading);
+        }
+        self.read(&config.clone().with_gain(Gain::One))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_oneshot_read_config;
+    use crate::InputSelection;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn auto_range_falls_back_to_gain_one() {
+        let config = Config::new(InputSelection::AN3SingleEnded);
+        let gain_four = config.clone().with_gain(Gain::Four).bits();
+        let mut transactions = expect_oneshot_read_config(DEVICE_ADDRESS, gain_four, 1000);
+        transactions.extend(expect_oneshot_read_config(
+            DEVICE_ADDRESS,
+            gain_four,
+            i16::MAX,
+        ));
+        transactions.extend(expect_oneshot_read_config(
+            DEVICE_ADDRESS,
+            config.bits(),
+            20_000,
+        ));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        // small enough for gain 4
+        let reading = device.read_auto_range(&config).unwrap();
+        assert_eq!((reading.gain, reading.raw), (Gain::Four, 1000));
+        // clipped at gain 4
+        let reading = device.read_auto_range(&config).unwrap();
+        assert_eq!((reading.gain, reading.raw), (Gain::One, 20_000));
+        assert_eq!(reading.clipping, Clipping::InRange);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn classifies_the_ends_of_the_range() {
+        assert_eq!(Clipping::of(0, DEFAULT_CLIPPING_MARGIN), Clipping::InRange);
+        assert_eq!(
+            Clipping::of(32_000, DEFAULT_CLIPPING_MARGIN),
+            Clipping::InRange
+        );
+        assert_eq!(
+            Clipping::of(32_500, DEFAULT_CLIPPING_MARGIN),
+            Clipping::NearClipping
+        );
+        assert_eq!(
+            Clipping::of(-32_500, DEFAULT_CLIPPING_MARGIN),
+            Clipping::NearClipping
+        );
+        assert_eq!(Clipping::of(i16::MAX, 0.0), Clipping::Clipped);
+        assert_eq!(Clipping::of(i16::MIN, 0.0), Clipping::Clipped);
+        // a wider margin
+        assert_eq!(Clipping::of(30_000, 0.1), Clipping::NearClipping);
+        assert!(!Clipping::of(-32_000, DEFAULT_CLIPPING_MARGIN).is_suspect());
+    }
+}
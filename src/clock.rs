@@ -0,0 +1,163 @@
+//! Time source of the driver's timeouts.
+//!
+//! The driver reads the time through [Clock] instead of `std::time::Instant`, so timeouts
+//! can run off a hardware timer, or be driven deterministically in tests. Likewise, it
+//! waits between status checks with an `embedded_hal::delay::DelayNs`, [StdDelay] on `std`.
+//!
+//! The results are timestamped on the same clock, unless a [TimestampSource] is set with
+//! [crate::Ads1119::set_timestamp_source], e.g. to stamp them with GPS/PPS-disciplined time
+//! or a mission elapsed timer so captured data lines up with vehicle time.
+
+use core::time::Duration;
+
+/// Monotonic time source
+pub trait Clock {
+    /// Time elapsed since an arbitrary point, which must not change
+    fn now(&self) -> Duration;
+}
+
+/// Source of the timestamps of results, see [crate::Ads1119::set_timestamp_source].
+///
+/// Unlike a [Clock], it may jump, e.g. when it's disciplined to an external reference: the
+/// driver keeps running its timeouts and measuring the intervals between samples on its
+/// [Clock].
+pub trait TimestampSource {
+    /// Time of the result being read, since the source's epoch
+    fn timestamp(&self) -> Duration;
+}
+
+/// A value stamped with the driver's timestamp when it was read, see [TimestampSource]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timestamped<T> {
+    pub timestamp: Duration,
+    pub value: T,
+}
+
+/// [Clock] reading `std::time::Instant`, the default on `std`
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StdClock;
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now(&self) -> Duration {
+        static ORIGIN: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        ORIGIN.get_or_init(std::time::Instant::now).elapsed()
+    }
+}
+
+/// [TimestampSource] following an external time, e.g. GPS time at each PPS edge or a mission
+/// elapsed timer, in between [SyncedTimestamps::sync] calls by a [Clock].
+///
+/// Before the first sync, it gives the time on the clock.
+#[cfg(feature = "std")]
+pub struct SyncedTimestamps {
+    clock: &'static (dyn Clock + Sync),
+    // time on the clock and external time at the last sync
+    reference: std::sync::Mutex<Option<(Duration, Duration)>>,
+}
+
+#[cfg(feature = "std")]
+impl SyncedTimestamps {
+    /// Follow the external time with `clock`, e.g. [StdClock], which can be set up as a
+    /// `static`
+    pub const fn new(clock: &'static (dyn Clock + Sync)) -> Self {
+        SyncedTimestamps {
+            clock,
+            reference: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// The external time is `time` now, e.g. when a PPS edge marks the second of the last
+    /// GPS time message
+    pub fn sync(&self, time: Duration) {
+        self.sync_at(self.clock.now(), time);
+    }
+
+    /// The external time was `time` when the clock read `at`, e.g. the time of a PPS edge
+    /// captured by an interrupt
+    pub fn sync_at(&self, at: Duration, time: Duration) {
+        let mut reference = self.reference.lock().unwrap_or_else(|e| e.into_inner());
+        *reference = Some((at, time));
+    }
+
+    /// Whether the external time was synced at least once
+    pub fn is_synced(&self) -> bool {
+        self.reference
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    }
+}
+
+#[cfg(feature = "std")]
+impl TimestampSource for SyncedTimestamps {
+    fn timestamp(&self) -> Duration {
+        let now = self.clock.now();
+        let reference = *self.reference.lock().unwrap_or_else(|e| e.into_inner());
+        match reference {
+            // the clock may have been read before the sync, on another thread
+            Some((at, time)) if now >= at => time + (now - at),
+            Some((at, time)) => time.saturating_sub(at - now),
+            None => now,
+        }
+    }
+}
+
+/// `DelayNs` sleeping the thread, the default delay on `std`
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StdDelay;
+
+#[cfg(feature = "std")]
+impl embedded_hal::delay::DelayNs for StdDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        std::thread::sleep(Duration::from_nanos(ns.into()))
+    }
+}
+
+/// Wait `duration` with `delay`, in steps of at most `u32::MAX` µs
+#[cfg(feature = "std")]
+pub(crate) fn delay_for(delay: &mut dyn embedded_hal::delay::DelayNs, duration: Duration) {
+    let mut remaining = duration.as_micros();
+    while remaining > 0 {
+        let step = remaining.min(u32::MAX.into()) as u32;
+        delay.delay_us(step);
+        remaining -= u128::from(step);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn std_clock_is_monotonic() {
+        let before = StdClock.now();
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(StdClock.now() >= before + Duration::from_millis(2));
+    }
+
+    #[test]
+    fn synced_timestamps_follow_the_external_time() {
+        struct FixedClock;
+
+        impl Clock for FixedClock {
+            fn now(&self) -> Duration {
+                Duration::from_secs(10)
+            }
+        }
+
+        static TIMESTAMPS: SyncedTimestamps = SyncedTimestamps::new(&FixedClock);
+
+        assert!(!TIMESTAMPS.is_synced());
+        assert_eq!(TIMESTAMPS.timestamp(), Duration::from_secs(10));
+        // the clock read 8 s at the edge marking second 1000
+        TIMESTAMPS.sync_at(Duration::from_secs(8), Duration::from_secs(1000));
+        assert_eq!(TIMESTAMPS.timestamp(), Duration::from_secs(1002));
+        TIMESTAMPS.sync_at(Duration::from_secs(11), Duration::from_secs(2000));
+        assert_eq!(TIMESTAMPS.timestamp(), Duration::from_secs(1999));
+        assert!(TIMESTAMPS.is_synced());
+    }
+}
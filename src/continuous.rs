@@ -0,0 +1,480 @@
+use embedded_hal::i2c::I2c;
+
+#[cfg(feature = "std")]
+use crate::{clock::Timestamped, sequence::Sequenced, Ads1119Err, DataRate, READ_INPUT_TIMEOUT};
+use crate::{Ads1119, Config, ConversionMode};
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Write the given config in continuous conversion mode and start converting.
+    ///
+    /// The conversion mode of `config` is ignored. Use [Ads1119::samples] to read the results,
+    /// and [Ads1119::power_down] (or write a single-shot config) to stop.
+    /// See 8.4.2.2 Continuous-Conversion Mode
+    pub fn start_continuous(&mut self, config: &Config) -> Result<(), I2C::Error> {
+        let config = config
+            .clone()
+            .with_conversion_mode(ConversionMode::Continuous);
+        self.write_config(config.bits())?;
+        self.start_sync()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Blocking iterator over the conversions of a device running in continuous mode
+    /// (see [Ads1119::start_continuous]).
+    ///
+    /// Each call to `next` waits until the status register reports a new conversion and then
+    /// reads it. The iterator only ends when cancelled (see [Ads1119::set_cancellation_token]);
+    /// other errors (including timeouts) are yielded and the caller decides whether to keep going.
+    ///
+    /// Conversions that finished while the caller wasn't reading are lost; see
+    /// [Samples::overrun].
+    pub fn samples(&mut self) -> Samples<'_, I2C> {
+        Samples {
+            device: self,
+            last_read: None,
+            last_timestamp: Duration::ZERO,
+            overrun: None,
+            missed: 0,
+            sequence: None,
+            watchdog: None,
+            stalls: 0,
+        }
+    }
+
+    /// Same as [Ads1119::samples], with each sample stamped with the time it was read on
+    /// the driver's clock (see [Ads1119::set_clock]), or by its timestamp source (see
+    /// [Ads1119::set_timestamp_source]).
+    pub fn timestamped_samples(&mut self) -> TimestampedSamples<'_, I2C> {
+        self.samples().timestamped()
+    }
+}
+
+/// Conversions lost before a sample of [Samples], because the previous one wasn't read in time
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SampleOverrun {
+    /// Number of conversions missed
+    pub missed: u32,
+    /// Time between the two samples, on the driver's clock
+    pub gap: Duration,
+}
+
+/// Iterator returned by [Ads1119::samples]
+#[cfg(feature = "std")]
+pub struct Samples<'a, I2C> {
+    device: &'a mut Ads1119<I2C>,
+    // when the last sample was read, on the clock
+    last_read: Option<Duration>,
+    // its timestamp, see [crate::clock::TimestampSource]
+    last_timestamp: Duration,
+    overrun: Option<SampleOverrun>,
+    missed: u64,
+    // sequence number of the last sample
+    sequence: Option<u64>,
+    watchdog: Option<Watchdog>,
+    stalls: u64,
+}
+
+/// Detects a continuous-mode stream that stopped converting, e.g. because the device reset
+/// and lost its config. See [Samples::with_watchdog].
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Watchdog {
+    periods: u32,
+    restart: bool,
+}
+
+#[cfg(feature = "std")]
+impl Watchdog {
+    /// Flag a stall when no conversion is ready within `periods` conversion times
+    pub fn new(periods: u32) -> Self {
+        Watchdog {
+            periods,
+            restart: false,
+        }
+    }
+
+    /// After a stall, write the config again and restart the conversions
+    pub fn with_restart(mut self) -> Self {
+        self.restart = true;
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, I2C> Samples<'a, I2C> {
+    /// The overrun before the last sample, if conversions were missed.
+    ///
+    /// The device keeps only the latest result and doesn't flag lost ones, so missed
+    /// conversions are inferred from the time between samples and the configured data rate.
+    pub fn overrun(&self) -> Option<SampleOverrun> {
+        self.overrun
+    }
+
+    /// Total number of conversions missed since the iterator was created
+    pub fn missed_conversions(&self) -> u64 {
+        self.missed
+    }
+
+    /// Wait for each sample for the number of conversion times set by `watchdog` instead of
+    /// the usual one second. A stall is yielded as [Ads1119Err::ConversionTimeout] and
+    /// counted in [Samples::stalls].
+    ///
+    /// Without a known data rate (e.g. after a failed config write), the usual timeout applies.
+    pub fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Number of stalls flagged by the watchdog
+    pub fn stalls(&self) -> u64 {
+        self.stalls
+    }
+
+    /// Stamp each sample with the time it was read, see [Ads1119::timestamped_samples]
+    pub fn timestamped(self) -> TimestampedSamples<'a, I2C> {
+        TimestampedSamples { samples: self }
+    }
+
+    /// Sequence number of the last sample, see [crate::sequence]. The conversions missed
+    /// before a sample (see [Samples::overrun]) use up sequence numbers, so a consumer sees
+    /// them as a gap.
+    pub fn sequence(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// Stamp each sample with the time it was read and its sequence number
+    pub fn sequenced(self) -> SequencedSamples<'a, I2C> {
+        SequencedSamples { samples: self }
+    }
+
+    /// When the last sample was read on the driver's clock, which unlike its timestamp
+    /// (see [crate::clock::TimestampSource]) doesn't jump
+    pub(crate) fn read_at(&self) -> Duration {
+        self.last_read.unwrap_or_default()
+    }
+
+    fn check_overrun(&mut self, now: Duration) {
+        let gap = self
+            .last_read
+            .map(|last_read| now.saturating_sub(last_read));
+        self.last_read = Some(now);
+        if let Some(gap) = gap {
+            self.device.stats.record_interval(gap);
+        }
+        let period = self
+            .device
+            .config
+            .map(|config| DataRate::from_bits(config).conversion_time().as_nanos());
+        self.overrun = match (gap, period) {
+            (Some(gap), Some(period)) if period > 0 => {
+                // conversions completed in the gap, rounded to absorb the clock tolerance
+                let conversions = (gap.as_nanos() + period / 2) / period;
+                let missed = u32::try_from(conversions.saturating_sub(1)).unwrap_or(u32::MAX);
+                (missed > 0).then_some(SampleOverrun { missed, gap })
+            }
+            _ => None,
+        };
+        if let Some(overrun) = self.overrun {
+            self.missed += u64::from(overrun.missed);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, I2C> Iterator for Samples<'a, I2C>
+where
+    I2C: I2c,
+{
+    type Item = Result<i16, Ads1119Err<I2C::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(watchdog) = self.watchdog else {
+            return self.read_next(READ_INPUT_TIMEOUT);
+        };
+        let Some(config) = self.device.config else {
+            return self.read_next(READ_INPUT_TIMEOUT);
+        };
+        let timeout = DataRate::from_bits(config).conversion_time() * watchdog.periods;
+        let sample = self.read_next(timeout);
+        if let Some(Err(Ads1119Err::ConversionTimeout(_))) = sample {
+            self.stalls += 1;
+            if watchdog.restart {
+                if let Err(e) = self
+                    .device
+                    .write_config(config)
+                    .and_then(|_| self.device.start_sync())
+                {
+                    return Some(Err(e.into()));
+                }
+                // the stream starts over
+                self.last_read = None;
+            }
+        }
+        sample
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Samples<'_, I2C>
+where
+    I2C: I2c,
+{
+    fn read_next(&mut self, timeout: Duration) -> Option<Result<i16, Ads1119Err<I2C::Error>>> {
+        match self.device.wait_for_conversion(timeout) {
+            Err(Ads1119Err::Cancelled) => None,
+            Err(e) => Some(Err(e)),
+            Ok(()) => {
+                let sample = self.device.read_data();
+                if sample.is_ok() {
+                    self.check_overrun(self.device.now());
+                    self.last_timestamp = self.device.timestamp();
+                    let missed = self.overrun.map_or(0, |overrun| overrun.missed);
+                    self.sequence = Some(self.device.next_sequence(missed.into()));
+                }
+                Some(sample.map_err(Ads1119Err::from))
+            }
+        }
+    }
+}
+
+/// Iterator returned by [Ads1119::timestamped_samples]
+#[cfg(feature = "std")]
+pub struct TimestampedSamples<'a, I2C> {
+    samples: Samples<'a, I2C>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, I2C> TimestampedSamples<'a, I2C> {
+    /// The underlying iterator, e.g. to check for overruns
+    pub fn samples(&self) -> &Samples<'a, I2C> {
+        &self.samples
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Iterator for TimestampedSamples<'_, I2C>
+where
+    I2C: I2c,
+{
+    type Item = Result<Timestamped<i16>, Ads1119Err<I2C::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.samples.next()?;
+        Some(sample.map(|value| Timestamped {
+            timestamp: self.samples.last_timestamp,
+            value,
+        }))
+    }
+}
+
+/// Iterator returned by [Samples::sequenced]
+#[cfg(feature = "std")]
+pub struct SequencedSamples<'a, I2C> {
+    samples: Samples<'a, I2C>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, I2C> SequencedSamples<'a, I2C> {
+    /// The underlying iterator, e.g. to check for overruns
+    pub fn samples(&self) -> &Samples<'a, I2C> {
+        &self.samples
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Iterator for SequencedSamples<'_, I2C>
+where
+    I2C: I2c,
+{
+    type Item = Result<Sequenced<Timestamped<i16>>, Ads1119Err<I2C::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.samples.next()?;
+        Some(sample.map(|value| Sequenced {
+            sequence: self.samples.sequence.unwrap_or_default(),
+            value: Timestamped {
+                timestamp: self.samples.last_timestamp,
+                value,
+            },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SampleOverrun, Watchdog};
+    use crate::clock::Clock;
+    use crate::wait::BusyPoll;
+    use crate::Ads1119Err;
+    use crate::{
+        test_support, Ads1119, CmdFlags, Config, ConversionMode, DataRate, InputSelection,
+        RegSelectFlags, STATUS_CONV_RDY,
+    };
+    use core::time::Duration;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+    const NOT_READY_STATUS: u8 = !0b1000_0000;
+
+    #[test]
+    fn can_start_continuous_and_read_samples() {
+        let config = Config::new(InputSelection::AN1SingleEnded).with_data_rate(DataRate::Sps90);
+        let status_read = |status| {
+            I2cTransaction::write_read(
+                DEVICE_ADDRESS,
+                vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                vec![status],
+            )
+        };
+        let data_read = |value: i16| {
+            I2cTransaction::write_read(
+                DEVICE_ADDRESS,
+                vec![CmdFlags::RDATA],
+                value.to_be_bytes().to_vec(),
+            )
+        };
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                // continuous mode bit is set
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, 0b1000_0110],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                status_read(NOT_READY_STATUS),
+                status_read(STATUS_CONV_RDY),
+                data_read(1234),
+                status_read(STATUS_CONV_RDY),
+                data_read(-42),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        device.start_continuous(&config).unwrap();
+        let samples: Vec<_> = device
+            .timestamped_samples()
+            .take(2)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(samples[0].value, 1234);
+        assert_eq!(samples[1].value, -42);
+        assert!(samples[1].timestamp >= samples[0].timestamp);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn overruns_are_inferred_from_the_gaps() {
+        struct TestClock;
+
+        static NOW_MS: AtomicU64 = AtomicU64::new(0);
+
+        impl Clock for TestClock {
+            fn now(&self) -> Duration {
+                Duration::from_millis(NOW_MS.load(Ordering::Relaxed))
+            }
+        }
+
+        let config = Config::new(InputSelection::AN0SingleEnded)
+            .with_data_rate(DataRate::Sps1000)
+            .with_conversion_mode(ConversionMode::Continuous);
+        let mut transactions = vec![
+            test_support::expect_write_config(DEVICE_ADDRESS, config.bits()),
+            test_support::expect_start_sync(DEVICE_ADDRESS),
+        ];
+        for value in 0..3 {
+            transactions.push(test_support::expect_read_status(
+                DEVICE_ADDRESS,
+                STATUS_CONV_RDY,
+            ));
+            transactions.push(test_support::expect_read_data(DEVICE_ADDRESS, value));
+        }
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.set_clock(&TestClock);
+        device.start_continuous(&config).unwrap();
+
+        let mut samples = device.samples();
+        // read on time, then after three conversion periods
+        for (now_ms, overrun) in [
+            (10, None),
+            (11, None),
+            (
+                14,
+                Some(SampleOverrun {
+                    missed: 2,
+                    gap: Duration::from_millis(3),
+                }),
+            ),
+        ] {
+            NOW_MS.store(now_ms, Ordering::Relaxed);
+            samples.next().unwrap().unwrap();
+            assert_eq!(samples.overrun(), overrun);
+        }
+        assert_eq!(samples.missed_conversions(), 2);
+        // the missed conversions use up sequence numbers
+        assert_eq!(samples.sequence(), Some(4));
+        device.destroy().done();
+    }
+
+    #[test]
+    fn watchdog_restarts_a_stalled_stream() {
+        static CLOCK: test_support::SteppingClock =
+            test_support::SteppingClock::new(Duration::from_millis(1));
+
+        let config = Config::new(InputSelection::AN0SingleEnded)
+            .with_data_rate(DataRate::Sps1000)
+            .with_conversion_mode(ConversionMode::Continuous);
+        let mut transactions = vec![
+            test_support::expect_write_config(DEVICE_ADDRESS, config.bits()),
+            test_support::expect_start_sync(DEVICE_ADDRESS),
+        ];
+        // three conversion times without a result
+        for _ in 0..3 {
+            transactions.push(test_support::expect_read_status(
+                DEVICE_ADDRESS,
+                NOT_READY_STATUS,
+            ));
+        }
+        transactions.extend([
+            test_support::expect_write_config(DEVICE_ADDRESS, config.bits()),
+            test_support::expect_start_sync(DEVICE_ADDRESS),
+            test_support::expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            test_support::expect_read_data(DEVICE_ADDRESS, 7),
+        ]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.set_clock(&CLOCK);
+        device.set_wait_strategy(BusyPoll);
+        device.start_continuous(&config).unwrap();
+
+        let mut samples = device
+            .samples()
+            .with_watchdog(Watchdog::new(3).with_restart());
+        assert!(matches!(
+            samples.next(),
+            Some(Err(Ads1119Err::ConversionTimeout(3)))
+        ));
+        assert_eq!(samples.stalls(), 1);
+        assert_eq!(samples.next().unwrap().unwrap(), 7);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn cancelling_ends_the_samples() {
+        let mut device = Ads1119::new(I2cMock::new(&[]), DEVICE_ADDRESS);
+        let token = crate::cancel::CancellationToken::new();
+        device.set_cancellation_token(token.clone());
+        token.cancel();
+        assert!(device.samples().next().is_none());
+        device.destroy().done();
+    }
+}
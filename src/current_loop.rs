@@ -0,0 +1,207 @@
+//! 4–20 mA current-loop transmitters read across a sense resistor.
+
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    rdata_to_voltage, Ads1119, Ads1119Err, Gain, InputSelection, INTERNAL_REFERENCE_VOLTAGE,
+};
+
+/// Current thresholds, in mA, used to classify loop readings.
+///
+/// The defaults follow NAMUR NE 43.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoopThresholds {
+    /// Below this, the loop is considered broken (open wire or dead transmitter)
+    pub fault_low: f32,
+    /// Below this, the signal is under range
+    pub under_range: f32,
+    /// Above this, the signal is over range
+    pub over_range: f32,
+    /// Above this, the loop is considered faulty (e.g. shorted)
+    pub fault_high: f32,
+}
+
+impl Default for LoopThresholds {
+    fn default() -> Self {
+        LoopThresholds {
+            fault_low: 3.6,
+            under_range: 3.8,
+            over_range: 20.5,
+            fault_high: 21.0,
+        }
+    }
+}
+
+/// Classification of a loop current
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoopStatus {
+    Ok,
+    UnderRange,
+    OverRange,
+    /// Current below [LoopThresholds::fault_low], typically a broken wire
+    BrokenWire,
+    /// Current above [LoopThresholds::fault_high], or at the full scale of the sense
+    /// resistor, beyond which the current can't be told apart
+    ShortCircuit,
+}
+
+/// A reading from a [CurrentLoop]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoopReading {
+    pub milliamps: f32,
+    /// The current mapped linearly onto the transmitter's range (4 mA is `range_low`,
+    /// 20 mA is `range_high`). Not clamped, check `status` before trusting it.
+    pub value: f32,
+    pub status: LoopStatus,
+}
+
+/// A 4–20 mA transmitter whose loop current flows through a sense resistor on one input
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurrentLoop {
+    pub input: InputSelection,
+    /// Sense resistance, in ohms. With the 2.048V full scale, 100 ohms reads up to 20.48 mA.
+    pub sense_resistance: f32,
+    /// Value reported by the transmitter at 4 mA, in engineering units
+    pub range_low: f32,
+    /// Value reported by the transmitter at 20 mA, in engineering units
+    pub range_high: f32,
+    pub thresholds: LoopThresholds,
+}
+
+impl CurrentLoop {
+    /// A loop reported as 0 to 100 (percent of span), with the default thresholds
+    pub fn new(input: InputSelection, sense_resistance: f32) -> Self {
+        CurrentLoop {
+            input,
+            sense_resistance,
+            range_low: 0.0,
+            range_high: 100.0,
+            thresholds: LoopThresholds::default(),
+        }
+    }
+
+    /// Set the engineering values corresponding to 4 mA and 20 mA
+    pub fn with_range(mut self, range_low: f32, range_high: f32) -> Self {
+        self.range_low = range_low;
+        self.range_high = range_high;
+        self
+    }
+
+    pub fn with_thresholds(mut self, thresholds: LoopThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Largest current the sense resistor can measure, in mA, at the 2.048V full scale
+    pub fn full_scale(&self) -> f32 {
+        INTERNAL_REFERENCE_VOLTAGE / self.sense_resistance * 1000.0
+    }
+
+    /// Classify a loop current, in mA
+    pub fn status(&self, milliamps: f32) -> LoopStatus {
+        let thresholds = &self.thresholds;
+        if milliamps < thresholds.fault_low {
+            LoopStatus::BrokenWire
+        } else if milliamps < thresholds.under_range {
+            LoopStatus::UnderRange
+        } else if milliamps > thresholds.fault_high {
+            LoopStatus::ShortCircuit
+        } else if milliamps > thresholds.over_range {
+            LoopStatus::OverRange
+        } else {
+            LoopStatus::Ok
+        }
+    }
+
+    /// Convert the voltage across the sense resistor into a reading.
+    ///
+    /// A current at [CurrentLoop::full_scale] or above is reported as
+    /// [LoopStatus::ShortCircuit]: the ADC saturates there, so thresholds above it (e.g. the
+    /// default ones with a 100 ohm resistor) would never be crossed.
+    pub fn reading(&self, volts: f32) -> LoopReading {
+        let milliamps = volts / self.sense_resistance * 1000.0;
+        let status = if milliamps >= self.full_scale() {
+            LoopStatus::ShortCircuit
+        } else {
+            self.status(milliamps)
+        };
+        LoopReading {
+            milliamps,
+            value: self.range_low + (milliamps - 4.0) / 16.0 * (self.range_high - self.range_low),
+            status,
+        }
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Take a single-shot reading of the loop's sense resistor.
+    /// The input's calibration, if any, is applied to the measured voltage.
+    ///
+    /// A saturated code is reported as [LoopStatus::ShortCircuit] whatever the calibration.
+    pub fn read_current_loop(
+        &mut self,
+        current_loop: &CurrentLoop,
+    ) -> Result<LoopReading, Ads1119Err<I2C::Error>> {
+        let raw = self.read_input_oneshot(&current_loop.input)?;
+        let volts = self.apply_calibration(&current_loop.input, rdata_to_voltage(raw, Gain::One));
+        let mut reading = current_loop.reading(volts);
+        if raw == i16::MAX {
+            reading.status = LoopStatus::ShortCircuit;
+        }
+        Ok(reading)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_oneshot_read;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    const EPS: f32 = 0.0001;
+
+    #[test]
+    fn maps_onto_range() {
+        let current_loop =
+            CurrentLoop::new(InputSelection::AN0SingleEnded, 100.0).with_range(-50.0, 150.0);
+        let reading = current_loop.reading(0.4);
+        assert!((reading.milliamps - 4.0).abs() < EPS);
+        assert!((reading.value + 50.0).abs() < EPS);
+        assert_eq!(reading.status, LoopStatus::Ok);
+
+        let reading = current_loop.reading(1.2);
+        assert!((reading.value - 50.0).abs() < EPS);
+        let reading = current_loop.reading(2.0);
+        assert!((reading.value - 150.0).abs() < EPS);
+    }
+
+    #[test]
+    fn detects_faults() {
+        let current_loop = CurrentLoop::new(InputSelection::AN0SingleEnded, 100.0);
+        assert_eq!(current_loop.status(0.0), LoopStatus::BrokenWire);
+        assert_eq!(current_loop.status(3.7), LoopStatus::UnderRange);
+        assert_eq!(current_loop.status(12.0), LoopStatus::Ok);
+        assert_eq!(current_loop.status(20.7), LoopStatus::OverRange);
+        assert_eq!(current_loop.status(22.0), LoopStatus::ShortCircuit);
+    }
+
+    #[test]
+    fn saturated_code_is_a_fault() {
+        let input = InputSelection::AN0SingleEnded;
+        let transactions = expect_oneshot_read(DEVICE_ADDRESS, &input, i16::MAX);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        // full scale is 20.48 mA, below the default over-range threshold
+        let current_loop = CurrentLoop::new(input, 100.0);
+        assert!((current_loop.full_scale() - 20.48).abs() < EPS);
+
+        let reading = device.read_current_loop(&current_loop).unwrap();
+        assert_eq!(reading.status, LoopStatus::ShortCircuit);
+        assert_eq!(current_loop.reading(2.047).status, LoopStatus::Ok);
+        device.destroy().done();
+    }
+}
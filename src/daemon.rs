@@ -0,0 +1,182 @@
+//! A daemon that owns the device and serves reads to other processes over a Unix domain socket.
+//!
+//! Enabled with the `daemon` feature.
+//!
+//! One-shot reads write the config register, start a conversion and poll for it, so two
+//! processes using the bus at once can corrupt each other's readings (see
+//! [Ads1119::read_input_oneshot]). With the daemon, a single process does all the I2C traffic
+//! and the others send it requests through [Ads1119Client].
+//!
+//! The protocol is line based. A request is `READ <mux>`, `<mux>` being the decimal value of
+//! [InputSelection::bits]. The response is `OK <raw>` with the raw ADC code, or `ERR <message>`.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, InputSelection};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DaemonError {
+    #[error("communicating with the daemon failed")]
+    Io(#[from] io::Error),
+    /// The daemon couldn't take the reading
+    #[error("daemon error: {0}")]
+    Remote(String),
+    #[error("unexpected response from the daemon: {0}")]
+    Protocol(String),
+}
+
+/// Serves the reads of one device to any number of clients.
+///
+/// Requests are handled one at a time, each client on its own thread.
+pub struct Ads1119Server<I2C> {
+    device: Arc<Mutex<Ads1119<I2C>>>,
+    listener: UnixListener,
+}
+
+impl<I2C> Ads1119Server<I2C>
+where
+    I2C: I2c + Send + 'static,
+{
+    /// Listen on the socket at the given path, which must not exist yet.
+    ///
+    /// Reads wait with the device's [crate::wait::WaitStrategy], so set it first, e.g. to
+    /// wait for DRDY edges with the `gpio-cdev` feature.
+    pub fn bind(device: Ads1119<I2C>, path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Ads1119Server {
+            device: Arc::new(Mutex::new(device)),
+            listener: UnixListener::bind(path)?,
+        })
+    }
+
+    /// Accept clients and serve their requests. Only returns if accepting a client fails.
+    pub fn serve(&self) -> io::Result<()> {
+        loop {
+            let (stream, _) = self.listener.accept()?;
+            let device = self.device.clone();
+            thread::spawn(move || {
+                // a client going away is not the server's problem
+                let _ = handle_client(&device, stream);
+            });
+        }
+    }
+}
+
+fn handle_client<I2C>(device: &Mutex<Ads1119<I2C>>, stream: UnixStream) -> io::Result<()>
+where
+    I2C: I2c,
+{
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let response = match line.split_once(' ') {
+            Some(("READ", mux)) => match mux.trim().parse::<u8>() {
+                Ok(bits) => {
+                    let input = InputSelection::from_bits(bits);
+                    // a panicking client thread doesn't leave the device in a bad state
+                    let mut device = device.lock().unwrap_or_else(|e| e.into_inner());
+                    match device.read_input_oneshot(&input) {
+                        Ok(raw) => format!("OK {}", raw),
+                        Err(e) => format!("ERR {}", e),
+                    }
+                }
+                Err(_) => format!("ERR invalid input {}", mux),
+            },
+            _ => format!("ERR unknown request {}", line),
+        };
+        writeln!(writer, "{}", response)?;
+    }
+    Ok(())
+}
+
+/// Client of an [Ads1119Server]
+pub struct Ads1119Client {
+    reader: BufReader<UnixStream>,
+}
+
+impl Ads1119Client {
+    pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Ads1119Client {
+            reader: BufReader::new(UnixStream::connect(path)?),
+        })
+    }
+
+    /// Have the daemon take a single-shot reading of the given input
+    pub fn read_input_oneshot(&mut self, input: &InputSelection) -> Result<i16, DaemonError> {
+        writeln!(self.reader.get_mut(), "READ {}", input.bits())?;
+        let mut response = String::new();
+        self.reader.read_line(&mut response)?;
+        let response = response.trim_end();
+        match response.split_once(' ') {
+            Some(("OK", raw)) => raw
+                .parse()
+                .map_err(|_| DaemonError::Protocol(response.to_string())),
+            Some(("ERR", message)) => Err(DaemonError::Remote(message.to_string())),
+            _ => Err(DaemonError::Protocol(response.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn clients_read_through_the_daemon() {
+        let read = |input: &InputSelection, value: i16| {
+            [
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RDATA],
+                    value.to_be_bytes().to_vec(),
+                ),
+            ]
+        };
+        let mut transactions = vec![];
+        transactions.extend(read(&InputSelection::AN1SingleEnded, 1000));
+        transactions.extend(read(&InputSelection::AN2AN3Differential, -5));
+        let mut i2c = I2cMock::new(&transactions);
+
+        let path = std::env::temp_dir().join(format!("ads1119-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let server = Ads1119Server::bind(Ads1119::new(i2c.clone(), DEVICE_ADDRESS), &path).unwrap();
+        thread::spawn(move || server.serve());
+
+        let mut client = Ads1119Client::connect(&path).unwrap();
+        assert_eq!(
+            client
+                .read_input_oneshot(&InputSelection::AN1SingleEnded)
+                .unwrap(),
+            1000
+        );
+        // a second client shares the same device
+        let mut other = Ads1119Client::connect(&path).unwrap();
+        assert_eq!(
+            other
+                .read_input_oneshot(&InputSelection::AN2AN3Differential)
+                .unwrap(),
+            -5
+        );
+        std::fs::remove_file(&path).unwrap();
+        i2c.done();
+    }
+}
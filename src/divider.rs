@@ -0,0 +1,111 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, InputSelection};
+
+/// A resistor divider in front of an input, so that voltages above the ADC's range
+/// (e.g. 12V or 28V rails) can be measured:
+///
+/// ```text
+/// V_rail --- R1 ---+--- R2 --- GND
+///                  |
+///                 AINx
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VoltageDivider {
+    /// Top resistor, in ohms
+    pub r1: f32,
+    /// Bottom resistor, in ohms
+    pub r2: f32,
+}
+
+impl VoltageDivider {
+    pub fn new(r1: f32, r2: f32) -> Self {
+        VoltageDivider { r1, r2 }
+    }
+
+    /// Ratio of the divider's input voltage to its output voltage, `(R1 + R2) / R2`
+    pub fn factor(&self) -> f32 {
+        (self.r1 + self.r2) / self.r2
+    }
+
+    /// Voltage at the divider's input, given the voltage measured at its output
+    pub fn apply(&self, volts: f32) -> f32 {
+        volts * self.factor()
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Attach a voltage divider to the given input, replacing any previous one.
+    /// It is applied by [Ads1119::read_input_voltage], after the input's calibration.
+    pub fn set_divider(&mut self, input: &InputSelection, divider: VoltageDivider) {
+        self.dividers[input.mux_index()] = Some(divider);
+    }
+
+    /// Remove the voltage divider of the given input
+    pub fn clear_divider(&mut self, input: &InputSelection) {
+        self.dividers[input.mux_index()] = None;
+    }
+
+    /// The voltage divider attached to the given input, if any
+    pub fn divider(&self, input: &InputSelection) -> Option<VoltageDivider> {
+        self.dividers[input.mux_index()]
+    }
+
+    /// Scale a voltage measured at the given input by its divider. Voltages of inputs
+    /// without a divider are returned unchanged.
+    pub fn apply_divider(&self, input: &InputSelection, volts: f32) -> f32 {
+        self.divider(input)
+            .map_or(volts, |divider| divider.apply(volts))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn divider_factor() {
+        // 28V bus into a ~2V range
+        let divider = VoltageDivider::new(130_000.0, 10_000.0);
+        assert_eq!(divider.factor(), 14.0);
+        assert_eq!(divider.apply(2.0), 28.0);
+    }
+
+    #[test]
+    fn read_input_voltage_applies_divider() {
+        let input = InputSelection::AN3SingleEnded;
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                // 1.024V at the pin
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0x40, 0x00]),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        device.set_divider(&input, VoltageDivider::new(110_000.0, 10_000.0));
+        assert!((device.read_input_voltage(&input).unwrap() - 12.288).abs() < 0.001);
+        assert_eq!(
+            device.apply_divider(&InputSelection::AN0SingleEnded, 1.0),
+            1.0
+        );
+        device.clear_divider(&input);
+        assert_eq!(device.divider(&input), None);
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,172 @@
+//! Duty-cycled sampling: the device is powered down between samples and woken up only for
+//! each conversion, the low-power pattern for slow measurements.
+
+use std::time::Duration;
+
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, Ads1119Err, Config, ConversionMode, DataRate};
+
+/// Typical supply current while converting
+pub const ACTIVE_CURRENT: f32 = 300e-6;
+
+/// Typical supply current in power-down mode
+pub const POWER_DOWN_CURRENT: f32 = 0.1e-6;
+
+/// Estimated average supply current, in amps, of one single-shot conversion at `data_rate`
+/// every `period`, with the device powered down in between.
+///
+/// The device is counted as active for one conversion time; the I2C traffic is ignored.
+pub fn estimated_average_current(data_rate: DataRate, period: Duration) -> f32 {
+    let period = period.as_secs_f32();
+    let active = data_rate.conversion_time().as_secs_f32().min(period);
+    if period == 0.0 {
+        return ACTIVE_CURRENT;
+    }
+    (ACTIVE_CURRENT * active + POWER_DOWN_CURRENT * (period - active)) / period
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Blocking iterator taking one single-shot conversion with `config` every `period`,
+    /// powering the device down after each one.
+    ///
+    /// The conversion mode of `config` is ignored. The first sample is taken right away;
+    /// if a sample takes longer than `period`, the next one follows immediately. Like
+    /// [Ads1119::samples], the iterator never ends and errors are yielded to the caller.
+    pub fn sample_duty_cycled(&mut self, config: &Config, period: Duration) -> DutyCycled<'_, I2C> {
+        let config = config
+            .clone()
+            .with_conversion_mode(ConversionMode::SingleShot);
+        DutyCycled {
+            device: self,
+            config,
+            period,
+            next: None,
+        }
+    }
+}
+
+/// Iterator returned by [Ads1119::sample_duty_cycled]
+pub struct DutyCycled<'a, I2C> {
+    device: &'a mut Ads1119<I2C>,
+    config: Config,
+    period: Duration,
+    // when the next sample is due on the driver's clock, None before the first one
+    next: Option<Duration>,
+}
+
+impl<I2C> DutyCycled<'_, I2C> {
+    /// See [estimated_average_current]
+    pub fn estimated_average_current(&self) -> f32 {
+        estimated_average_current(self.config.data_rate, self.period)
+    }
+}
+
+impl<I2C> Iterator for DutyCycled<'_, I2C>
+where
+    I2C: I2c,
+{
+    type Item = Result<i16, Ads1119Err<I2C::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let due = match self.next {
+            Some(due) => {
+                let remaining = due.saturating_sub(self.device.now());
+                self.device.sleep(remaining);
+                due
+            }
+            None => self.device.now(),
+        };
+        // don't try to catch up on samples that were missed
+        self.next = Some((due + self.period).max(self.device.now()));
+
+        Some(self.sample())
+    }
+}
+
+impl<I2C> DutyCycled<'_, I2C>
+where
+    I2C: I2c,
+{
+    fn sample(&mut self) -> Result<i16, Ads1119Err<I2C::Error>> {
+        // the config is kept while powered down, so it's only written when it changed
+        // (or after a failed write)
+        if self.device.config != Some(self.config.bits()) {
+            self.device.write_config(self.config.bits())?;
+        }
+        let sample = self.device.convert_oneshot();
+        self.device.power_down()?;
+        sample
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clock::Clock;
+    use crate::test_support::{
+        expect_power_down, expect_read_data, expect_read_status, expect_start_sync,
+        expect_write_config, RecordingDelay,
+    };
+    use crate::wait::BusyPoll;
+    use crate::{InputSelection, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    struct FixedClock;
+
+    impl Clock for FixedClock {
+        fn now(&self) -> Duration {
+            Duration::from_secs(1)
+        }
+    }
+
+    #[test]
+    fn powers_down_between_samples() {
+        let config = Config::new(InputSelection::AN2SingleEnded)
+            .with_data_rate(DataRate::Sps1000)
+            .with_conversion_mode(ConversionMode::Continuous);
+        let single_shot = config
+            .clone()
+            .with_conversion_mode(ConversionMode::SingleShot);
+        let mut transactions = vec![expect_write_config(DEVICE_ADDRESS, single_shot.bits())];
+        for value in [3, 4] {
+            transactions.extend([
+                expect_start_sync(DEVICE_ADDRESS),
+                expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+                expect_read_data(DEVICE_ADDRESS, value),
+                expect_power_down(DEVICE_ADDRESS),
+            ]);
+        }
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        let delay = RecordingDelay::default();
+        device.set_delay(delay.clone());
+        device.set_wait_strategy(BusyPoll);
+        device.set_clock(&FixedClock);
+
+        let samples: Vec<i16> = device
+            .sample_duty_cycled(&config, Duration::from_millis(20))
+            .take(2)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(samples, vec![3, 4]);
+        // the clock stands still, so the whole period is left to wait
+        assert_eq!(delay.delays(), [20_000_000]);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn average_current_estimate() {
+        // 1 ms conversions once a second
+        let current = estimated_average_current(DataRate::Sps1000, Duration::from_secs(1));
+        let expected = ACTIVE_CURRENT / 1000.0 + POWER_DOWN_CURRENT * 0.999;
+        assert!((current - expected).abs() < 1e-9);
+        // converting all the time
+        let current = estimated_average_current(DataRate::Sps20, Duration::from_millis(10));
+        assert_eq!(current, ACTIVE_CURRENT);
+    }
+}
@@ -0,0 +1,186 @@
+//! Logical channels behind an external analog multiplexer.
+//!
+//! An [ExpandedChannels] drives the select lines of a multiplexer (e.g. a CD74HC4051 with 3
+//! lines and 8 channels, or a CD74HC4067 with 4 lines and 16) whose output feeds one input
+//! of the ADS1119, and reads its channels with [Ads1119::read].
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::i2c::I2c;
+use std::time::Duration;
+
+use crate::clock::delay_for;
+use crate::reading::Reading;
+use crate::{Ads1119, Ads1119Err, Config, ConversionMode, InputSelection};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExpansionError<I2CE, PE> {
+    #[error("channel {0} is beyond the multiplexer's channels")]
+    NoSuchChannel(usize),
+    #[error("setting a select line failed")]
+    Pin(PE),
+    #[error("reading the device failed")]
+    Device(#[from] Ads1119Err<I2CE>),
+}
+
+/// `2^N` logical channels through a multiplexer with `N` select lines, least significant
+/// line first
+pub struct ExpandedChannels<P, const N: usize> {
+    select: [P; N],
+    config: Config,
+    settle: Duration,
+    // None until a channel is selected, or after a line failed to switch
+    selected: Option<usize>,
+}
+
+impl<P, const N: usize> ExpandedChannels<P, N>
+where
+    P: OutputPin,
+{
+    /// Channels read on `input` with the default gain and data rate, and no settle delay
+    pub fn new(select: [P; N], input: InputSelection) -> Self {
+        ExpandedChannels {
+            select,
+            config: Config::new(input),
+            settle: Duration::ZERO,
+            selected: None,
+        }
+    }
+
+    /// Read the channels with this config; it's used in single-shot mode
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config.with_conversion_mode(ConversionMode::SingleShot);
+        self
+    }
+
+    /// Wait this long after switching channels, for the multiplexer output and the input
+    /// filter to settle. Reads of the channel already selected don't wait.
+    pub fn with_settle(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    pub fn channel_count(&self) -> usize {
+        1 << N
+    }
+
+    /// The channel the select lines are set to, if known
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Set the select lines to `channel`, then wait the settle delay with `delay` if it
+    /// changed.
+    pub fn select<I2CE>(
+        &mut self,
+        channel: usize,
+        delay: &mut dyn DelayNs,
+    ) -> Result<(), ExpansionError<I2CE, P::Error>> {
+        if channel >= self.channel_count() {
+            return Err(ExpansionError::NoSuchChannel(channel));
+        }
+        if self.selected == Some(channel) {
+            return Ok(());
+        }
+        self.selected = None;
+        for (line, pin) in self.select.iter_mut().enumerate() {
+            if channel & (1 << line) == 0 {
+                pin.set_low()
+            } else {
+                pin.set_high()
+            }
+            .map_err(ExpansionError::Pin)?;
+        }
+        self.selected = Some(channel);
+        delay_for(delay, self.settle);
+        Ok(())
+    }
+
+    /// Select `channel`, waiting with the driver's delay (see [Ads1119::set_delay]), and
+    /// read it
+    pub fn read_channel<I2C>(
+        &mut self,
+        device: &mut Ads1119<I2C>,
+        channel: usize,
+    ) -> Result<Reading, ExpansionError<I2C::Error, P::Error>>
+    where
+        I2C: I2c,
+    {
+        self.select(channel, &mut *device.delay)?;
+        Ok(device.read(&self.config)?)
+    }
+
+    /// Read every channel in order, the reading of channel `i` at index `i`
+    pub fn read_all<I2C>(
+        &mut self,
+        device: &mut Ads1119<I2C>,
+    ) -> Result<Vec<Reading>, ExpansionError<I2C::Error, P::Error>>
+    where
+        I2C: I2c,
+    {
+        (0..self.channel_count())
+            .map(|channel| self.read_channel(device, channel))
+            .collect()
+    }
+
+    /// Get the select lines back
+    pub fn into_inner(self) -> [P; N] {
+        self.select
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{expect_oneshot_read, RecordingDelay};
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+    use embedded_hal_mock::eh1::pin::{Mock as PinMock, State, Transaction as PinTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    fn pin(states: &[State]) -> PinMock {
+        let transactions: Vec<_> = states.iter().map(|s| PinTransaction::set(*s)).collect();
+        PinMock::new(&transactions)
+    }
+
+    #[test]
+    fn channel_sets_the_select_lines() {
+        let input = InputSelection::AN2SingleEnded;
+        let mut transactions = expect_oneshot_read(DEVICE_ADDRESS, &input, 10);
+        transactions.extend(expect_oneshot_read(DEVICE_ADDRESS, &input, 20));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        let mut channels = ExpandedChannels::new(
+            [
+                pin(&[State::Low, State::High]),
+                pin(&[State::High, State::Low]),
+                pin(&[State::High, State::High]),
+            ],
+            input,
+        );
+        assert_eq!(channels.channel_count(), 8);
+        assert_eq!(channels.read_channel(&mut device, 6).unwrap().raw, 10);
+        // reading the same channel again doesn't touch the lines
+        channels
+            .select::<()>(6, &mut RecordingDelay::default())
+            .unwrap();
+        assert_eq!(channels.read_channel(&mut device, 5).unwrap().raw, 20);
+        assert_eq!(channels.selected(), Some(5));
+        for mut pin in channels.into_inner() {
+            pin.done();
+        }
+        device.destroy().done();
+    }
+
+    #[test]
+    fn channel_out_of_range() {
+        let mut channels =
+            ExpandedChannels::new([pin(&[]), pin(&[])], InputSelection::AN0SingleEnded);
+        assert!(matches!(
+            channels.select::<()>(4, &mut RecordingDelay::default()),
+            Err(ExpansionError::NoSuchChannel(4))
+        ));
+        for mut pin in channels.into_inner() {
+            pin.done();
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Command and register access shared by the devices of the family (ADS1119, ADS1219).
+//!
+//! Both parts have the same commands, register map and config layout;
+//! they differ in the width of the conversion result.
+
+use core::cell::Cell;
+use core::time::Duration;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::registers::StatusRegister;
+use crate::{clock::Clock, wait::WaitStrategy, Ads1119Err, MIN_CHECK_TIME};
+use crate::{Command, Register};
+
+/// Read a register. See 8.5.3.6 RREG
+pub(crate) fn read_register<I2C>(
+    i2c: &mut I2C,
+    address: u8,
+    register: Register,
+) -> Result<u8, I2C::Error>
+where
+    I2C: I2c,
+{
+    let mut read_buffer = [0];
+    i2c.write_read(
+        address,
+        &[Command::ReadRegister(register).encode()],
+        &mut read_buffer,
+    )
+    .and(Ok(read_buffer[0]))
+}
+
+/// Write a register. See 8.5.3.7 WREG
+pub(crate) fn write_register<I2C>(
+    i2c: &mut I2C,
+    address: u8,
+    register: Register,
+    value: u8,
+) -> Result<(), I2C::Error>
+where
+    I2C: I2c,
+{
+    i2c.write(address, &[Command::WriteRegister(register).encode(), value])
+}
+
+/// Write the config register
+pub(crate) fn write_config<I2C>(i2c: &mut I2C, address: u8, value: u8) -> Result<(), I2C::Error>
+where
+    I2C: I2c,
+{
+    write_register(i2c, address, Register::Config, value)
+}
+
+/// Send a single-byte command
+pub(crate) fn command<I2C>(i2c: &mut I2C, address: u8, command: Command) -> Result<(), I2C::Error>
+where
+    I2C: I2c,
+{
+    i2c.write(address, &[command.encode()])
+}
+
+/// Read the `N` bytes of the conversion result, MSB first. See 8.5.3.5 RDATA
+pub(crate) fn read_data<I2C, const N: usize>(
+    i2c: &mut I2C,
+    address: u8,
+) -> Result<[u8; N], I2C::Error>
+where
+    I2C: I2c,
+{
+    let mut read_buffer = [0u8; N];
+    i2c.write_read(address, &[Command::ReadData.encode()], &mut read_buffer)
+        .and(Ok(read_buffer))
+}
+
+/// How [wait_conversion_ready] waits between status checks
+pub(crate) struct Waiter<'a> {
+    pub strategy: &'a mut dyn WaitStrategy,
+    /// Measures the timeout. Without one, it's approximated by adding up the delays, plus
+    /// [MIN_CHECK_TIME] for each status check.
+    pub clock: Option<&'a dyn Clock>,
+    pub delay: &'a mut dyn DelayNs,
+    /// Checked before each wait
+    pub cancelled: &'a dyn Fn() -> bool,
+}
+
+/// Wait for a new conversion result, failing with [Ads1119Err::ConversionTimeout] after
+/// `timeout`, or with [Ads1119Err::Cancelled] as soon as the waiter is cancelled.
+///
+/// Returns the number of status register checks.
+pub(crate) fn wait_conversion_ready<I2C>(
+    i2c: &mut I2C,
+    address: u8,
+    waiter: Waiter<'_>,
+    conversion_time: Option<Duration>,
+    timeout: Duration,
+) -> Result<u32, Ads1119Err<I2C::Error>>
+where
+    I2C: I2c,
+{
+    let Waiter {
+        strategy,
+        clock,
+        delay,
+        cancelled,
+    } = waiter;
+    let start_time = clock.map(|clock| clock.now());
+    // time spent in the delays
+    let waited = Cell::new(Duration::ZERO);
+    let mut sleep = |time: Duration| {
+        delay.delay_us(u32::try_from(time.as_micros()).unwrap_or(u32::MAX));
+        waited.set(waited.get() + time);
+    };
+    if cancelled() {
+        return Err(Ads1119Err::Cancelled);
+    }
+    // the waits end at the timeout, whatever the strategy asks for
+    let first_delay = strategy.first_delay(conversion_time).min(timeout);
+    strategy.wait(first_delay, &mut sleep);
+
+    let mut checks = 0;
+    // wait until the status register tells us there is data to read
+    loop {
+        let status = read_register(i2c, address, Register::Status)?;
+        if StatusRegister::from_bits(status).conversion_ready() {
+            break;
+        }
+        checks += 1;
+
+        // Check if the timeout duration has elapsed
+        let elapsed = match (clock, start_time) {
+            (Some(clock), Some(start_time)) => clock.now().saturating_sub(start_time),
+            _ => waited.get() + MIN_CHECK_TIME * checks,
+        };
+        if elapsed >= timeout {
+            return Err(Ads1119Err::ConversionTimeout(timeout.as_millis()));
+        }
+
+        if cancelled() {
+            return Err(Ads1119Err::Cancelled);
+        }
+        let next_delay = strategy.next_delay(checks).min(timeout - elapsed);
+        strategy.wait(next_delay, &mut sleep);
+    }
+
+    Ok(checks + 1)
+}
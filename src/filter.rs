@@ -0,0 +1,782 @@
+//! Filters for smoothing successive readings.
+//!
+//! The filters don't allocate, so they can be used on no_std targets as well.
+
+use embedded_hal::i2c::I2c;
+
+#[cfg(feature = "std")]
+use crate::Ads1119Err;
+use crate::{Ads1119, DataRate, InputSelection};
+
+/// A filter fed one sample at a time.
+pub trait Filter {
+    /// Add a sample and return the filter's new output
+    fn update(&mut self, sample: f32) -> f32;
+
+    /// Forget all previous samples
+    fn reset(&mut self);
+}
+
+/// Sliding-window mean over the last `N` samples.
+///
+/// Until `N` samples have been seen, the mean is taken over the samples received so far.
+#[derive(Clone, Debug)]
+pub struct MovingAverage<const N: usize> {
+    window: [f32; N],
+    // index the next sample is written to
+    next: usize,
+    len: usize,
+}
+
+impl<const N: usize> MovingAverage<N> {
+    pub fn new() -> Self {
+        assert!(N > 0, "the window must hold at least one sample");
+        MovingAverage {
+            window: [0.0; N],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// The mean of the samples in the window, or `None` if it is empty
+    pub fn mean(&self) -> Option<f32> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(self.window[..self.len].iter().sum::<f32>() / self.len as f32)
+    }
+
+    /// Number of samples currently in the window
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// True once the window holds `N` samples
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+}
+
+impl<const N: usize> Default for MovingAverage<N> {
+    fn default() -> Self {
+        MovingAverage::new()
+    }
+}
+
+impl<const N: usize> Filter for MovingAverage<N> {
+    fn update(&mut self, sample: f32) -> f32 {
+        self.window[self.next] = sample;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+        // can't be empty, a sample was just added
+        self.mean().unwrap_or(sample)
+    }
+
+    fn reset(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+}
+
+/// Exponential moving average: each output moves `alpha` of the way from the previous one
+/// to the new sample. A smaller alpha smooths more and follows changes more slowly; the
+/// first sample is passed through.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExponentialMovingAverage {
+    alpha: f32,
+    value: Option<f32>,
+}
+
+impl ExponentialMovingAverage {
+    /// Panics unless `0 < alpha <= 1`
+    pub fn new(alpha: f32) -> Self {
+        assert!(
+            alpha > 0.0 && alpha <= 1.0,
+            "alpha must be in (0, 1], got {alpha}"
+        );
+        ExponentialMovingAverage { alpha, value: None }
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    /// The last output, or `None` before the first sample
+    pub fn value(&self) -> Option<f32> {
+        self.value
+    }
+}
+
+impl Filter for ExponentialMovingAverage {
+    fn update(&mut self, sample: f32) -> f32 {
+        let value = self
+            .value
+            .map_or(sample, |value| value + self.alpha * (sample - value));
+        self.value = Some(value);
+        value
+    }
+
+    fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Smooth the voltages of the given input returned by [Ads1119::read_input_voltage]
+    /// with an [ExponentialMovingAverage], replacing any previous smoothing and its state.
+    /// It's applied last, after the calibration and the divider.
+    pub fn set_smoothing(&mut self, input: &InputSelection, alpha: f32) {
+        self.smoothing[input.mux_index()] = Some(ExponentialMovingAverage::new(alpha));
+    }
+
+    /// Stop smoothing the voltages of the given input
+    pub fn clear_smoothing(&mut self, input: &InputSelection) {
+        self.smoothing[input.mux_index()] = None;
+    }
+
+    /// The smoothing of the given input, with its current value, if any
+    pub fn smoothing(&self, input: &InputSelection) -> Option<&ExponentialMovingAverage> {
+        self.smoothing[input.mux_index()].as_ref()
+    }
+
+    /// Feed a voltage of the given input through its smoothing, returning the smoothed value.
+    /// Voltages of inputs without smoothing are returned unchanged.
+    pub fn apply_smoothing(&mut self, input: &InputSelection, volts: f32) -> f32 {
+        self.smoothing[input.mux_index()]
+            .as_mut()
+            .map_or(volts, |filter| filter.update(volts))
+    }
+}
+
+/// Finite impulse response filter with `N` taps, e.g. a low-pass band-limiting a
+/// continuous stream before it's decimated.
+///
+/// The output is the dot product of the taps with the last `N` samples, the newest one
+/// weighted by `taps[0]`. Until `N` samples have been seen, the missing ones count as 0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fir<const N: usize> {
+    taps: [f32; N],
+    history: [f32; N],
+    // index the next sample is written to
+    next: usize,
+}
+
+impl<const N: usize> Fir<N> {
+    /// Filter with the given coefficients
+    pub fn new(taps: [f32; N]) -> Self {
+        assert!(N > 0, "the filter needs at least one tap");
+        Fir {
+            taps,
+            history: [0.0; N],
+            next: 0,
+        }
+    }
+
+    /// Windowed-sinc low-pass (Hamming window) passing frequencies below `cutoff_hz` at
+    /// `sample_rate_hz`, normalized to a DC gain of 1. More taps give a sharper cutoff;
+    /// an odd count makes the delay a whole number of samples.
+    #[cfg(feature = "std")]
+    pub fn low_pass(cutoff_hz: f32, sample_rate_hz: f32) -> Self {
+        use core::f32::consts::{PI, TAU};
+
+        let fc = cutoff_hz / sample_rate_hz;
+        let middle = (N - 1) as f32 / 2.0;
+        let mut taps = [0.0; N];
+        for (i, tap) in taps.iter_mut().enumerate() {
+            let x = i as f32 - middle;
+            let sinc = if x == 0.0 {
+                2.0 * fc
+            } else {
+                (TAU * fc * x).sin() / (PI * x)
+            };
+            let window = if N == 1 {
+                1.0
+            } else {
+                0.54 - 0.46 * (TAU * i as f32 / (N - 1) as f32).cos()
+            };
+            *tap = sinc * window;
+        }
+        let sum: f32 = taps.iter().sum();
+        for tap in taps.iter_mut() {
+            *tap /= sum;
+        }
+        Fir::new(taps)
+    }
+
+    pub fn taps(&self) -> &[f32; N] {
+        &self.taps
+    }
+
+    /// Delay of a symmetric (linear-phase) filter, in samples
+    pub fn group_delay(&self) -> f32 {
+        (N - 1) as f32 / 2.0
+    }
+}
+
+impl<const N: usize> Filter for Fir<N> {
+    fn update(&mut self, sample: f32) -> f32 {
+        self.history[self.next] = sample;
+        let newest = self.next;
+        self.next = (self.next + 1) % N;
+        // taps[k] weighs the sample k steps back
+        (0..N)
+            .map(|k| self.taps[k] * self.history[(newest + N - k) % N])
+            .sum()
+    }
+
+    fn reset(&mut self) {
+        self.history = [0.0; N];
+        self.next = 0;
+    }
+}
+
+/// Coefficients of a [Biquad], normalized so that `a0` is 1:
+/// `y[n] = b0 x[n] + b1 x[n-1] + b2 x[n-2] - a1 y[n-1] - a2 y[n-2]`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BiquadCoefficients {
+    pub b0: f32,
+    pub b1: f32,
+    pub b2: f32,
+    pub a1: f32,
+    pub a2: f32,
+}
+
+#[cfg(feature = "std")]
+impl BiquadCoefficients {
+    /// Butterworth Q, the flattest passband without a resonant peak
+    pub const BUTTERWORTH_Q: f32 = core::f32::consts::FRAC_1_SQRT_2;
+
+    /// Second-order low-pass, -3 dB at `cutoff_hz`
+    pub fn low_pass(cutoff_hz: f32, sample_rate_hz: f32) -> Self {
+        let (cos, alpha) = Self::prewarp(cutoff_hz, sample_rate_hz, Self::BUTTERWORTH_Q);
+        Self::normalize(
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// Second-order high-pass, -3 dB at `cutoff_hz`, e.g. to remove a drifting offset
+    pub fn high_pass(cutoff_hz: f32, sample_rate_hz: f32) -> Self {
+        let (cos, alpha) = Self::prewarp(cutoff_hz, sample_rate_hz, Self::BUTTERWORTH_Q);
+        Self::normalize(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// Notch rejecting `center_hz`, e.g. mains hum. A higher `q` makes the notch narrower.
+    pub fn notch(center_hz: f32, sample_rate_hz: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::prewarp(center_hz, sample_rate_hz, q);
+        Self::normalize(
+            [1.0, -2.0 * cos, 1.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    // Audio EQ Cookbook (R. Bristow-Johnson) intermediates: cos(w0) and alpha
+    fn prewarp(frequency_hz: f32, sample_rate_hz: f32, q: f32) -> (f32, f32) {
+        let w0 = core::f32::consts::TAU * frequency_hz / sample_rate_hz;
+        (w0.cos(), w0.sin() / (2.0 * q))
+    }
+
+    fn normalize([b0, b1, b2]: [f32; 3], [a0, a1, a2]: [f32; 3]) -> Self {
+        BiquadCoefficients {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+}
+
+/// Second-order IIR filter. It keeps two values of state whatever its response, where a
+/// [Fir] with a comparable cutoff needs tens of taps; chain several for steeper slopes.
+///
+/// Coefficients from the cutoff and sample rate ([BiquadCoefficients::low_pass],
+/// [BiquadCoefficients::high_pass], [BiquadCoefficients::notch]) need `std`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Biquad {
+    coefficients: BiquadCoefficients,
+    // transposed direct form II state
+    s1: f32,
+    s2: f32,
+}
+
+impl Biquad {
+    pub fn new(coefficients: BiquadCoefficients) -> Self {
+        Biquad {
+            coefficients,
+            s1: 0.0,
+            s2: 0.0,
+        }
+    }
+
+    pub fn coefficients(&self) -> &BiquadCoefficients {
+        &self.coefficients
+    }
+}
+
+impl Filter for Biquad {
+    fn update(&mut self, sample: f32) -> f32 {
+        let c = &self.coefficients;
+        let out = c.b0 * sample + self.s1;
+        self.s1 = c.b1 * sample - c.a1 * out + self.s2;
+        self.s2 = c.b2 * sample - c.a2 * out;
+        out
+    }
+
+    fn reset(&mut self) {
+        self.s1 = 0.0;
+        self.s2 = 0.0;
+    }
+}
+
+/// Iterator feeding the raw results of another, e.g. [crate::Ads1119::samples], through a
+/// filter. Errors are passed through without touching the filter.
+pub struct FilteredSamples<S, F> {
+    samples: S,
+    filter: F,
+}
+
+impl<S, F> FilteredSamples<S, F> {
+    pub fn new(samples: S, filter: F) -> Self {
+        FilteredSamples { samples, filter }
+    }
+
+    pub fn filter(&self) -> &F {
+        &self.filter
+    }
+
+    /// Get the iterator and the filter back
+    pub fn into_inner(self) -> (S, F) {
+        (self.samples, self.filter)
+    }
+}
+
+impl<S, F, E> Iterator for FilteredSamples<S, F>
+where
+    S: Iterator<Item = Result<i16, E>>,
+    F: Filter,
+{
+    type Item = Result<f32, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.samples.next()?;
+        Some(sample.map(|raw| self.filter.update(raw as f32)))
+    }
+}
+
+/// [Filter] passing samples through unchanged, for a [Decimator] without filtering
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Passthrough;
+
+impl Filter for Passthrough {
+    fn update(&mut self, sample: f32) -> f32 {
+        sample
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Keeps every `factor`th output of a filter fed with every sample, e.g. to capture at
+/// 1000 SPS and store at 100. Without a low-pass in front ([Decimator::new]), content
+/// above half the output rate aliases into the result.
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use ads1119::filter::{Decimator, Fir};
+/// // 1000 SPS down to 100, band-limited to 40 Hz (designing the FIR needs `std`)
+/// let decimator = Decimator::with_filter(10, Fir::<31>::low_pass(40.0, 1000.0));
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decimator<F> {
+    factor: usize,
+    filter: F,
+    // samples fed since the last output
+    count: usize,
+}
+
+impl Decimator<Passthrough> {
+    /// Keep every `factor`th sample as is. Panics if `factor` is 0.
+    pub fn new(factor: usize) -> Self {
+        Decimator::with_filter(factor, Passthrough)
+    }
+}
+
+impl<F> Decimator<F>
+where
+    F: Filter,
+{
+    /// Feed every sample to `filter`, keeping every `factor`th output. Panics if `factor`
+    /// is 0.
+    pub fn with_filter(factor: usize, filter: F) -> Self {
+        assert!(factor > 0, "the decimation factor must be at least 1");
+        Decimator {
+            factor,
+            filter,
+            count: 0,
+        }
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Samples per second out of the decimator for the given input rate
+    pub fn output_rate(&self, data_rate: DataRate) -> f32 {
+        data_rate.sps() as f32 / self.factor as f32
+    }
+
+    /// Feed a sample, returning an output on every `factor`th one
+    pub fn push(&mut self, sample: f32) -> Option<f32> {
+        let out = self.filter.update(sample);
+        self.count += 1;
+        if self.count < self.factor {
+            return None;
+        }
+        self.count = 0;
+        Some(out)
+    }
+
+    /// Forget the samples fed so far, and the filter's state
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.filter.reset();
+    }
+
+    /// Decimate the raw results of another iterator, e.g. [crate::Ads1119::samples].
+    /// Errors are passed through as they come.
+    pub fn decimate<S>(self, samples: S) -> DecimatedSamples<S, F> {
+        DecimatedSamples {
+            samples,
+            decimator: self,
+        }
+    }
+}
+
+/// Iterator returned by [Decimator::decimate]
+pub struct DecimatedSamples<S, F> {
+    samples: S,
+    decimator: Decimator<F>,
+}
+
+impl<S, F> DecimatedSamples<S, F> {
+    /// Get the iterator and the decimator back
+    pub fn into_inner(self) -> (S, Decimator<F>) {
+        (self.samples, self.decimator)
+    }
+}
+
+impl<S, F, E> Iterator for DecimatedSamples<S, F>
+where
+    S: Iterator<Item = Result<i16, E>>,
+    F: Filter,
+{
+    type Item = Result<f32, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.samples.next()? {
+                Ok(raw) => {
+                    if let Some(out) = self.decimator.push(raw as f32) {
+                        return Some(Ok(out));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Report-on-change: lets a value through only when it differs from the last one let
+/// through by more than the deadband, e.g. to publish a slowly-varying channel only when it
+/// moves. The first value always goes through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deadband {
+    deadband: f32,
+    // the last value let through
+    reported: Option<f32>,
+}
+
+impl Deadband {
+    /// Report changes of more than `deadband`, in the unit of the values. Panics if
+    /// `deadband` is negative.
+    pub fn new(deadband: f32) -> Self {
+        assert!(deadband >= 0.0, "the deadband can't be negative");
+        Deadband {
+            deadband,
+            reported: None,
+        }
+    }
+
+    pub fn deadband(&self) -> f32 {
+        self.deadband
+    }
+
+    /// The last value let through
+    pub fn reported(&self) -> Option<f32> {
+        self.reported
+    }
+
+    /// Return `value` if it should be reported, which makes it the new reference
+    pub fn check(&mut self, value: f32) -> Option<f32> {
+        match self.reported {
+            // no f32::abs on no_std before Rust 1.85
+            Some(reported)
+                if value <= reported + self.deadband && value >= reported - self.deadband =>
+            {
+                None
+            }
+            _ => {
+                self.reported = Some(value);
+                Some(value)
+            }
+        }
+    }
+
+    /// Forget the last value let through, so the next one is reported
+    pub fn reset(&mut self) {
+        self.reported = None;
+    }
+
+    /// Report the changes in the raw results of another iterator, e.g.
+    /// [crate::Ads1119::samples], with the deadband in ADC codes. Errors are passed through
+    /// as they come.
+    pub fn report_changes<S>(self, samples: S) -> ChangedSamples<S> {
+        ChangedSamples {
+            samples,
+            deadband: self,
+        }
+    }
+}
+
+/// Iterator returned by [Deadband::report_changes]
+pub struct ChangedSamples<S> {
+    samples: S,
+    deadband: Deadband,
+}
+
+impl<S> ChangedSamples<S> {
+    /// Get the iterator and the deadband back
+    pub fn into_inner(self) -> (S, Deadband) {
+        (self.samples, self.deadband)
+    }
+}
+
+impl<S, E> Iterator for ChangedSamples<S>
+where
+    S: Iterator<Item = Result<i16, E>>,
+{
+    type Item = Result<i16, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.samples.next()? {
+                Ok(raw) => {
+                    if self.deadband.check(raw as f32).is_some() {
+                        return Some(Ok(raw));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// An input paired with the filter that smooths its readings.
+#[derive(Clone, Debug)]
+pub struct FilteredInput<F> {
+    pub input: InputSelection,
+    pub filter: F,
+}
+
+impl<F> FilteredInput<F>
+where
+    F: Filter,
+{
+    pub fn new(input: InputSelection, filter: F) -> Self {
+        FilteredInput { input, filter }
+    }
+
+    /// Feed a reading of the input taken elsewhere, e.g. with the async driver, through the
+    /// filter. Returns the filtered value.
+    pub fn update(&mut self, raw: i16) -> f32 {
+        self.filter.update(raw as f32)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F> FilteredInput<F>
+where
+    F: Filter,
+{
+    /// Take a single-shot reading of the input and feed it through the filter.
+    /// Returns the filtered value, in raw ADC codes.
+    pub fn read<I2C>(&mut self, device: &mut Ads1119<I2C>) -> Result<f32, Ads1119Err<I2C::Error>>
+    where
+        I2C: I2c,
+    {
+        let raw = device.read_input_oneshot(&self.input)?;
+        Ok(self.update(raw))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn moving_average_fills_then_slides() {
+        let mut filter = MovingAverage::<3>::new();
+        assert_eq!(filter.mean(), None);
+        assert_eq!(filter.update(3.0), 3.0);
+        assert_eq!(filter.update(6.0), 4.5);
+        assert!(!filter.is_full());
+        assert_eq!(filter.update(9.0), 6.0);
+        assert!(filter.is_full());
+        // the 3 drops out of the window
+        assert_eq!(filter.update(12.0), 9.0);
+        assert_eq!(filter.len(), 3);
+    }
+
+    #[test]
+    fn ema_moves_alpha_of_the_way() {
+        let mut filter = ExponentialMovingAverage::new(0.25);
+        assert_eq!(filter.value(), None);
+        assert_eq!(filter.update(8.0), 8.0);
+        assert_eq!(filter.update(16.0), 10.0);
+        assert_eq!(filter.update(10.0), 10.0);
+        filter.reset();
+        assert_eq!(filter.update(1.0), 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ema_rejects_zero_alpha() {
+        ExponentialMovingAverage::new(0.0);
+    }
+
+    #[test]
+    fn fir_weighs_the_newest_sample_by_the_first_tap() {
+        let mut filter = Fir::new([0.5, 0.25, 0.25]);
+        assert_eq!(filter.update(4.0), 2.0);
+        assert_eq!(filter.update(8.0), 5.0);
+        assert_eq!(filter.update(0.0), 3.0);
+        assert_eq!(filter.update(0.0), 2.0);
+        filter.reset();
+        assert_eq!(filter.update(4.0), 2.0);
+    }
+
+    #[test]
+    fn fir_low_pass_passes_dc_and_stops_nyquist() {
+        let mut filter = Fir::<15>::low_pass(50.0, 1000.0);
+        assert!((filter.taps().iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert_eq!(filter.group_delay(), 7.0);
+        let mut out = 0.0;
+        for i in 0..30 {
+            // alternates at 500 Hz
+            out = filter.update(if i % 2 == 0 { 1.0 } else { -1.0 });
+        }
+        assert!(out.abs() < 0.05, "{out}");
+    }
+
+    // gain of `filter` for a sine at `frequency_hz`, once settled: RMS out over RMS in
+    fn settled_gain(mut filter: impl Filter, frequency_hz: f32, sample_rate_hz: f32) -> f32 {
+        let (mut power_in, mut power_out) = (0.0, 0.0);
+        for i in 0..2000 {
+            let t = i as f32 / sample_rate_hz;
+            let sample = (core::f32::consts::TAU * frequency_hz * t).sin();
+            let out = filter.update(sample);
+            if i >= 1000 {
+                power_in += sample * sample;
+                power_out += out * out;
+            }
+        }
+        (power_out / power_in).sqrt()
+    }
+
+    #[test]
+    fn biquad_low_pass_and_high_pass() {
+        let low_pass = Biquad::new(BiquadCoefficients::low_pass(10.0, 1000.0));
+        assert!(settled_gain(low_pass, 1.0, 1000.0) > 0.99);
+        assert!(settled_gain(low_pass, 200.0, 1000.0) < 0.01);
+        assert!((settled_gain(low_pass, 10.0, 1000.0) - 0.707).abs() < 0.01);
+
+        let high_pass = Biquad::new(BiquadCoefficients::high_pass(10.0, 1000.0));
+        assert!(settled_gain(high_pass, 1.0, 1000.0) < 0.02);
+        assert!(settled_gain(high_pass, 200.0, 1000.0) > 0.99);
+    }
+
+    #[test]
+    fn biquad_notch_rejects_its_center() {
+        let notch = Biquad::new(BiquadCoefficients::notch(50.0, 1000.0, 5.0));
+        assert!(settled_gain(notch, 50.0, 1000.0) < 0.01);
+        assert!(settled_gain(notch, 10.0, 1000.0) > 0.95);
+    }
+
+    #[test]
+    fn filtered_samples_pass_errors_through() {
+        let samples = [Ok(2), Err(()), Ok(4)].into_iter();
+        let filtered: Vec<_> =
+            FilteredSamples::new(samples, ExponentialMovingAverage::new(0.5)).collect();
+        assert_eq!(filtered, [Ok(2.0), Err(()), Ok(3.0)]);
+    }
+
+    #[test]
+    fn decimator_keeps_every_nth_sample() {
+        let samples = (1..=7).map(Ok::<i16, ()>);
+        let kept: Vec<_> = Decimator::new(3).decimate(samples).collect();
+        assert_eq!(kept, [Ok(3.0), Ok(6.0)]);
+    }
+
+    #[test]
+    fn decimator_filters_every_sample() {
+        let mut decimator = Decimator::with_filter(2, MovingAverage::<2>::new());
+        assert_eq!(decimator.output_rate(DataRate::Sps1000), 500.0);
+        assert_eq!(decimator.push(1.0), None);
+        assert_eq!(decimator.push(3.0), Some(2.0));
+        assert_eq!(decimator.push(5.0), None);
+        assert_eq!(decimator.push(7.0), Some(6.0));
+        let errors: Vec<_> = decimator.decimate([Err(()), Ok(9)].into_iter()).collect();
+        assert_eq!(errors, [Err(())]);
+    }
+
+    #[test]
+    fn deadband_reports_changes_beyond_it() {
+        let mut deadband = Deadband::new(0.5);
+        assert_eq!(deadband.check(1.0), Some(1.0));
+        assert_eq!(deadband.check(1.5), None);
+        // measured from the last value reported, not the last one seen
+        assert_eq!(deadband.check(0.4), Some(0.4));
+        assert_eq!(deadband.reported(), Some(0.4));
+        deadband.reset();
+        assert_eq!(deadband.check(0.4), Some(0.4));
+    }
+
+    #[test]
+    fn deadband_filters_samples() {
+        let samples = [Ok(100), Ok(102), Err(()), Ok(98), Ok(96), Ok(110)];
+        let reported: Vec<_> = Deadband::new(3.0)
+            .report_changes(samples.into_iter())
+            .collect();
+        assert_eq!(reported, [Ok(100), Err(()), Ok(96), Ok(110)]);
+    }
+
+    #[test]
+    fn moving_average_reset() {
+        let mut filter = MovingAverage::<2>::new();
+        filter.update(100.0);
+        filter.reset();
+        assert!(filter.is_empty());
+        assert_eq!(filter.update(1.0), 1.0);
+    }
+}
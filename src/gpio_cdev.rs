@@ -0,0 +1,123 @@
+//! DRDY on a Linux GPIO line, through the GPIO character device (`/dev/gpiochip*`).
+//!
+//! Enabled with the `gpio-cdev` feature. [CdevDrdy] sleeps in the kernel until DRDY's
+//! falling edge instead of polling the status register or the pin. Set it as the driver's
+//! [WaitStrategy] (see [crate::Ads1119::set_wait_strategy]) and every blocking read path
+//! uses it, including those of the daemon and the `ads1119-cli` `--drdy` option; or hand it
+//! to [crate::Ads1119::spawn_drdy_capture].
+
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::time::Duration;
+
+use gpio_cdev::{Chip, EventRequestFlags, LineEventHandle, LineRequestFlags};
+
+use crate::interrupt::DrdyEdges;
+use crate::wait::WaitStrategy;
+use crate::READ_INPUT_SLEEP;
+
+/// Consumer label of the requested line, shown by `gpioinfo`
+const CONSUMER: &str = "ads1119-drdy";
+
+/// DRDY line requested for falling edge events
+pub struct CdevDrdy {
+    events: LineEventHandle,
+}
+
+impl CdevDrdy {
+    /// Request falling edge events of the given line offset of a GPIO chip, e.g.
+    /// `CdevDrdy::new("/dev/gpiochip0", 17)`
+    pub fn new(chip: impl AsRef<Path>, line: u32) -> Result<Self, gpio_cdev::Error> {
+        let events = Chip::new(chip)?.get_line(line)?.events(
+            LineRequestFlags::INPUT,
+            EventRequestFlags::FALLING_EDGE,
+            CONSUMER,
+        )?;
+        Ok(CdevDrdy { events })
+    }
+
+    /// Use a line already requested for falling edge events
+    pub fn from_handle(events: LineEventHandle) -> Self {
+        CdevDrdy { events }
+    }
+
+    /// Get the line handle back
+    pub fn into_inner(self) -> LineEventHandle {
+        self.events
+    }
+
+    /// Wait up to `timeout` for events, and consume those that are pending.
+    /// Returns false if there were none.
+    fn take_events(&mut self, timeout: Duration) -> Result<bool, gpio_cdev::Error> {
+        let mut pending = false;
+        let mut timeout = timeout;
+        while self.poll(timeout)? {
+            self.events.get_event()?;
+            pending = true;
+            // only drain the events that are already queued
+            timeout = Duration::ZERO;
+        }
+        Ok(pending)
+    }
+
+    fn poll(&self, timeout: Duration) -> io::Result<bool> {
+        let mut fd = libc::pollfd {
+            fd: self.events.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // rounded up, so a short wait doesn't become a busy poll
+        let millis = timeout.as_micros().div_ceil(1000).min(i32::MAX as u128) as i32;
+        // SAFETY: fd is a single valid pollfd, and the count is 1
+        match unsafe { libc::poll(&mut fd, 1, millis) } {
+            -1 => {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    Ok(false)
+                } else {
+                    Err(err)
+                }
+            }
+            0 => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    fn wait_for_low(&mut self, timeout: Duration) -> Result<(), gpio_cdev::Error> {
+        // edges queued before now are from earlier conversions; if this one is already
+        // done, the line is low
+        self.take_events(Duration::ZERO)?;
+        if self.events.get_value()? == 0 {
+            return Ok(());
+        }
+        self.take_events(timeout)?;
+        Ok(())
+    }
+}
+
+impl WaitStrategy for CdevDrdy {
+    fn first_delay(&mut self, _conversion_time: Option<Duration>) -> Duration {
+        // until the line goes low or the driver times out
+        Duration::MAX
+    }
+
+    fn next_delay(&mut self, _checks: u32) -> Duration {
+        Duration::MAX
+    }
+
+    fn wait(&mut self, delay: Duration, sleep: &mut dyn FnMut(Duration)) {
+        // a line error falls back to polling the status register
+        if self.wait_for_low(delay).is_err() {
+            sleep(delay.min(READ_INPUT_SLEEP));
+        }
+    }
+}
+
+impl DrdyEdges for CdevDrdy {
+    type Error = gpio_cdev::Error;
+
+    fn wait_for_edge(&mut self, timeout: Duration) -> Result<bool, Self::Error> {
+        self.take_events(timeout)
+    }
+}
@@ -0,0 +1,263 @@
+//! HTTP service for reading the device from other hosts.
+//!
+//! Enabled with the `http` feature.
+//!
+//! Inputs are named like in the JSON output, e.g. `AN0SingleEnded`. Routes:
+//!
+//! - `GET /reading/<input>`: take a single-shot reading, returned as a [JsonRecord]
+//! - `GET /samples/<input>?count=<n>`: run continuous conversions and stream `n` samples
+//!   (10 by default, at most [MAX_SAMPLE_COUNT]) as JSON Lines. The device is powered down
+//!   afterwards.
+
+use std::fmt::Debug;
+use std::io::{self, Read};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use embedded_hal::i2c::I2c;
+use tiny_http::{Header, Request, Response, Server, StatusCode};
+
+use crate::json::JsonRecord;
+use crate::{Ads1119, Ads1119Err, Config, InputSelection};
+
+const DEFAULT_SAMPLE_COUNT: usize = 10;
+
+/// Largest `count` of a sample stream, 10 s at 1000 SPS, as the stream holds off other
+/// requests. Above it, the request is refused with 400 Bad Request.
+pub const MAX_SAMPLE_COUNT: usize = 10_000;
+
+/// Serves the readings of one device over HTTP.
+///
+/// Requests are handled one at a time, so a sample stream holds off other requests
+/// until it is done.
+pub struct HttpService<I2C> {
+    device: Mutex<Ads1119<I2C>>,
+    server: Server,
+}
+
+impl<I2C> HttpService<I2C>
+where
+    I2C: I2c,
+{
+    pub fn bind(device: Ads1119<I2C>, addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let server = Server::http(addr).map_err(io::Error::other)?;
+        Ok(HttpService {
+            device: Mutex::new(device),
+            server,
+        })
+    }
+
+    /// The address the service listens on, e.g. to find the port when bound to port 0
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Serve requests. Only returns if receiving a request fails.
+    pub fn serve(&self) -> io::Result<()> {
+        loop {
+            let request = self.server.recv()?;
+            // a client going away is not the service's problem
+            let _ = self.handle(request);
+        }
+    }
+
+    fn handle(&self, request: Request) -> io::Result<()> {
+        let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+        let mut segments = path.trim_matches('/').split('/');
+        let route = (segments.next(), segments.next().map(parse_input));
+        let mut device = self.device.lock().unwrap_or_else(|e| e.into_inner());
+
+        match route {
+            (Some("reading"), Some(Some(input))) => match device.read_input_oneshot(&input) {
+                Ok(raw) => {
+                    let record = JsonRecord::new(&Config::new(input), raw, SystemTime::now());
+                    let body = serde_json::to_string(&record)?;
+                    request.respond(Response::from_string(body).with_header(json_header()))
+                }
+                Err(e) => request.respond(error(500, e)),
+            },
+            (Some("samples"), Some(Some(input))) => {
+                let count = match parse_count(query) {
+                    Some(count) if count <= MAX_SAMPLE_COUNT => count,
+                    Some(_) => {
+                        let message = format!("count is above {MAX_SAMPLE_COUNT}");
+                        return request.respond(error(400, message));
+                    }
+                    None => return request.respond(error(400, "invalid count")),
+                };
+                let config = Config::new(input);
+                if let Err(e) = device.start_continuous(&config) {
+                    return request.respond(error(500, Ads1119Err::from(e)));
+                }
+                let stream = SampleStream {
+                    device: &mut device,
+                    config,
+                    remaining: count,
+                    line: Vec::new(),
+                    pos: 0,
+                };
+                let result = request.respond(Response::new(
+                    StatusCode(200),
+                    vec![json_header()],
+                    stream,
+                    None,
+                    None,
+                ));
+                device.power_down().map_err(io_error)?;
+                result
+            }
+            (_, Some(None)) => request.respond(error(404, "unknown input")),
+            _ => request.respond(error(404, "not found")),
+        }
+    }
+}
+
+/// Reads continuous-mode samples as JSON Lines, one sample at a time
+struct SampleStream<'a, I2C> {
+    device: &'a mut Ads1119<I2C>,
+    config: Config,
+    remaining: usize,
+    // the current line and how much of it was read
+    line: Vec<u8>,
+    pos: usize,
+}
+
+impl<'a, I2C> Read for SampleStream<'a, I2C>
+where
+    I2C: I2c,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.line.len() {
+            if self.remaining == 0 {
+                return Ok(0);
+            }
+            let raw = self
+                .device
+                .samples()
+                .next()
+                .unwrap_or(Err(Ads1119Err::NoSamples))
+                .map_err(io_error)?;
+            self.line.clear();
+            JsonRecord::new(&self.config, raw, SystemTime::now()).write_line(&mut self.line)?;
+            self.pos = 0;
+            self.remaining -= 1;
+        }
+        let len = buf.len().min(self.line.len() - self.pos);
+        buf[..len].copy_from_slice(&self.line[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+fn parse_input(name: &str) -> Option<InputSelection> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+}
+
+fn parse_count(query: &str) -> Option<usize> {
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("count="))
+        .map_or(Some(DEFAULT_SAMPLE_COUNT), |count| count.parse().ok())
+}
+
+fn io_error(e: impl Debug) -> io::Error {
+    io::Error::other(format!("{:?}", e))
+}
+
+fn json_header() -> Header {
+    Header::from_bytes("Content-Type", "application/json").expect("valid header")
+}
+
+fn error(status: u16, message: impl ToString) -> Response<io::Cursor<Vec<u8>>> {
+    Response::from_string(message.to_string()).with_status_code(status)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use std::io::Write;
+    use std::net::TcpStream;
+    use std::thread;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            path
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn status_read() -> I2cTransaction {
+        I2cTransaction::write_read(
+            DEVICE_ADDRESS,
+            vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+            vec![STATUS_CONV_RDY],
+        )
+    }
+
+    fn data_read(value: i16) -> I2cTransaction {
+        I2cTransaction::write_read(
+            DEVICE_ADDRESS,
+            vec![CmdFlags::RDATA],
+            value.to_be_bytes().to_vec(),
+        )
+    }
+
+    #[test]
+    fn serves_readings_and_sample_streams() {
+        let input = InputSelection::AN1SingleEnded;
+        let mut i2c = I2cMock::new(&[
+            // GET /reading
+            I2cTransaction::write(
+                DEVICE_ADDRESS,
+                vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+            ),
+            I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+            status_read(),
+            data_read(4321),
+            // GET /samples, in continuous mode
+            I2cTransaction::write(
+                DEVICE_ADDRESS,
+                vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits() | 0b10],
+            ),
+            I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+            status_read(),
+            data_read(1),
+            status_read(),
+            data_read(2),
+            I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::POWER_DOWN]),
+        ]);
+        let service =
+            HttpService::bind(Ads1119::new(i2c.clone(), DEVICE_ADDRESS), "127.0.0.1:0").unwrap();
+        let addr = service.local_addr().unwrap();
+        thread::spawn(move || service.serve());
+
+        let response = get(addr, "/reading/AN1SingleEnded");
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.contains(r#""raw":4321"#), "{}", response);
+
+        let response = get(addr, "/samples/AN1SingleEnded?count=2");
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        let first = response.find(r#""raw":1,"#).unwrap();
+        let second = response.find(r#""raw":2,"#).unwrap();
+        assert!(first < second);
+
+        assert!(get(addr, "/reading/AN9").starts_with("HTTP/1.1 404"));
+        // a stream this long would hold off other clients for good
+        let response = get(addr, "/samples/AN1SingleEnded?count=18446744073709551615");
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+        let path = format!("/samples/AN1SingleEnded?count={}", MAX_SAMPLE_COUNT + 1);
+        assert!(get(addr, &path).starts_with("HTTP/1.1 400"));
+        i2c.done();
+    }
+}
@@ -0,0 +1,286 @@
+//! Interrupt-driven conversions, for designs where the DRDY pin triggers a hardware task
+//! (e.g. with RTIC) instead of the driver polling the status register.
+//!
+//! None of these calls wait, so they can be made from an interrupt handler.
+//!
+//! On `std`, [Ads1119::spawn_drdy_capture] runs the same on a thread woken by DRDY edges
+//! (see [DrdyEdges]) and hands each result to a callback.
+
+use embedded_hal::i2c::I2c;
+
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use crate::cancel::CancellationToken;
+use crate::{clock::Timestamped, Ads1119, Config};
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Write the given config and start converting, without waiting for the result.
+    ///
+    /// DRDY goes low when the conversion is done; call [Ads1119::on_drdy_interrupt] then.
+    /// In continuous mode, it does so after every conversion.
+    pub fn start(&mut self, config: &Config) -> Result<(), I2C::Error> {
+        self.result = None;
+        self.write_config(config.bits())?;
+        self.start_sync()
+    }
+
+    /// Read the finished conversion, to be called when DRDY goes low.
+    /// The result is kept until [Ads1119::take_result], stamped with the time on the
+    /// driver's clock (see [Ads1119::set_clock]) or by its timestamp source (see
+    /// [Ads1119::set_timestamp_source]).
+    ///
+    /// In continuous mode, a result that wasn't taken is replaced by the new one.
+    pub fn on_drdy_interrupt(&mut self) -> Result<(), I2C::Error> {
+        self.result = Some(self.read_timestamped()?);
+        Ok(())
+    }
+
+    /// Read the finished conversion and pass it to `callback` instead of keeping it, e.g.
+    /// from an EXTI handler that owns the driver, so the application loop doesn't have to
+    /// take the results.
+    pub fn on_drdy_interrupt_with(
+        &mut self,
+        callback: impl FnOnce(Timestamped<i16>),
+    ) -> Result<(), I2C::Error> {
+        callback(self.read_timestamped()?);
+        Ok(())
+    }
+
+    fn read_timestamped(&mut self) -> Result<Timestamped<i16>, I2C::Error> {
+        let value = self.read_data()?;
+        Ok(Timestamped {
+            timestamp: self.timestamp(),
+            value,
+        })
+    }
+
+    /// The result read by the last [Ads1119::on_drdy_interrupt], if it wasn't taken yet
+    pub fn take_result(&mut self) -> Option<i16> {
+        self.take_timestamped_result().map(|result| result.value)
+    }
+
+    /// Same as [Ads1119::take_result], with the time the result was read
+    pub fn take_timestamped_result(&mut self) -> Option<Timestamped<i16>> {
+        self.result.take()
+    }
+}
+
+/// Source of DRDY falling edges for [Ads1119::spawn_drdy_capture], e.g. a GPIO line
+/// requested for edge events
+#[cfg(feature = "std")]
+pub trait DrdyEdges {
+    type Error;
+
+    /// Wait up to `timeout` for DRDY to go low. Returns false on timeout.
+    fn wait_for_edge(&mut self, timeout: Duration) -> Result<bool, Self::Error>;
+}
+
+/// How often the capture thread checks whether it was stopped, when no edge comes
+#[cfg(feature = "std")]
+const CAPTURE_STOP_POLL: Duration = Duration::from_millis(100);
+
+#[cfg(feature = "std")]
+#[derive(thiserror::Error, Debug)]
+pub enum CaptureError<I2CE, EE> {
+    #[error("starting the conversions failed")]
+    Start(I2CE),
+    #[error("waiting for DRDY failed")]
+    Drdy(EE),
+}
+
+/// What the capture thread returns: the driver, the edge source, and why it stopped
+#[cfg(feature = "std")]
+pub type CaptureExit<I2C, D> = (
+    Ads1119<I2C>,
+    D,
+    Result<(), CaptureError<<I2C as embedded_hal::i2c::ErrorType>::Error, <D as DrdyEdges>::Error>>,
+);
+
+/// Handle of the thread started by [Ads1119::spawn_drdy_capture]
+#[cfg(feature = "std")]
+pub struct DrdyCaptureHandle<I2C, D>
+where
+    I2C: I2c,
+    D: DrdyEdges,
+{
+    thread: std::thread::JoinHandle<CaptureExit<I2C, D>>,
+    stop: CancellationToken,
+}
+
+#[cfg(feature = "std")]
+impl<I2C, D> DrdyCaptureHandle<I2C, D>
+where
+    I2C: I2c,
+    D: DrdyEdges,
+{
+    /// Ask the capture to stop; it does once the current wait for an edge returns, within 100 ms
+    pub fn stop(&self) {
+        self.stop.cancel();
+    }
+
+    /// Wait for the capture to stop and get the driver and the edge source back.
+    ///
+    /// Panics if the capture thread (i.e. the callback) panicked.
+    pub fn join(self) -> CaptureExit<I2C, D> {
+        self.thread.join().expect("capture thread panicked")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c + Send + 'static,
+    I2C::Error: Send,
+{
+    /// Move the driver to a new thread that starts converting with `config`, then reads
+    /// the result on every DRDY edge and passes it, or the read error, to `callback`.
+    ///
+    /// `config` should select [crate::ConversionMode::Continuous]; in single-shot mode only
+    /// one conversion is made. The capture runs until [DrdyCaptureHandle::stop], or until
+    /// starting the conversions or waiting for an edge fails.
+    pub fn spawn_drdy_capture<D, F>(
+        self,
+        config: &Config,
+        edges: D,
+        mut callback: F,
+    ) -> DrdyCaptureHandle<I2C, D>
+    where
+        D: DrdyEdges + Send + 'static,
+        D::Error: Send,
+        F: FnMut(Result<Timestamped<i16>, I2C::Error>) + Send + 'static,
+    {
+        let stop = CancellationToken::new();
+        let stopped = stop.clone();
+        let config = config.clone();
+        let thread = std::thread::spawn(move || {
+            let mut device = self;
+            let mut edges = edges;
+            let result = (|| {
+                device.start(&config).map_err(CaptureError::Start)?;
+                while !stopped.is_cancelled() {
+                    if edges
+                        .wait_for_edge(CAPTURE_STOP_POLL)
+                        .map_err(CaptureError::Drdy)?
+                    {
+                        callback(device.read_timestamped());
+                    }
+                }
+                Ok(())
+            })();
+            (device, edges, result)
+        });
+        DrdyCaptureHandle { thread, stop }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clock::Clock;
+    use crate::{CmdFlags, ConversionMode, InputSelection, RegSelectFlags};
+    use core::time::Duration;
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn results_are_read_on_interrupt() {
+        let config = Config::new(InputSelection::AN2SingleEnded)
+            .with_conversion_mode(ConversionMode::Continuous);
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, 0b1010_0010],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0, 1]),
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0, 2]),
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0, 3]),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        device.start(&config).unwrap();
+        assert_eq!(device.take_result(), None);
+
+        device.on_drdy_interrupt().unwrap();
+        assert_eq!(device.take_result(), Some(1));
+        assert_eq!(device.take_result(), None);
+
+        // the untaken result is replaced, and stamped on the given clock
+        struct FixedClock;
+
+        impl Clock for FixedClock {
+            fn now(&self) -> Duration {
+                Duration::from_millis(5)
+            }
+        }
+
+        device.set_clock(&FixedClock);
+        device.on_drdy_interrupt().unwrap();
+        device.on_drdy_interrupt().unwrap();
+        assert_eq!(
+            device.take_timestamped_result(),
+            Some(Timestamped {
+                timestamp: Duration::from_millis(5),
+                value: 3
+            })
+        );
+        device.destroy().done();
+    }
+
+    #[test]
+    fn capture_calls_back_on_every_edge() {
+        // edges, timeouts, then the line goes away
+        struct Edges(Vec<bool>);
+
+        impl DrdyEdges for Edges {
+            type Error = &'static str;
+
+            fn wait_for_edge(&mut self, _timeout: Duration) -> Result<bool, Self::Error> {
+                if self.0.is_empty() {
+                    return Err("line closed");
+                }
+                Ok(self.0.remove(0))
+            }
+        }
+
+        let config = Config::new(InputSelection::AN2SingleEnded)
+            .with_conversion_mode(ConversionMode::Continuous);
+        let device = Ads1119::new(
+            I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, 0b1010_0010],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0, 1]),
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0, 2])
+                    .with_error(ErrorKind::Other),
+            ]),
+            DEVICE_ADDRESS,
+        );
+
+        let (results, received) = std::sync::mpsc::channel();
+        let handle = device.spawn_drdy_capture(
+            &config,
+            Edges(vec![true, false, true]),
+            move |result: Result<Timestamped<i16>, _>| {
+                results.send(result.map(|result| result.value)).unwrap()
+            },
+        );
+        let (device, _, result) = handle.join();
+        assert!(matches!(result, Err(CaptureError::Drdy("line closed"))));
+        let received: Vec<_> = received.iter().collect();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0], Ok(1));
+        assert!(received[1].is_err());
+        device.destroy().done();
+    }
+}
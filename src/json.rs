@@ -0,0 +1,105 @@
+//! JSON Lines output of readings, one JSON object per line, e.g. for piping into `jq`.
+//!
+//! Enabled with the `json` feature.
+
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::scan::ScanReading;
+use crate::{rdata_to_voltage, Config, InputSelection};
+
+/// A reading as written by [JsonRecord::write_line]:
+///
+/// `{"input":"AN0SingleEnded","raw":16384,"volts":1.024,"timestamp":1700000000.25,"config":96}`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct JsonRecord {
+    pub input: InputSelection,
+    pub raw: i16,
+    /// The raw value converted with the gain it was read with
+    pub volts: f32,
+    /// Seconds since the Unix epoch
+    pub timestamp: f64,
+    /// The config register value the reading was taken with
+    pub config: u8,
+}
+
+impl JsonRecord {
+    /// Record of a reading taken with the given config at the given time
+    pub fn new(config: &Config, raw: i16, at: SystemTime) -> Self {
+        JsonRecord {
+            input: config.input.clone(),
+            raw,
+            volts: rdata_to_voltage(raw, config.gain),
+            timestamp: unix_seconds(at),
+            config: config.bits(),
+        }
+    }
+
+    /// Record of a scan reading taken at the given time
+    pub fn from_scan_reading(reading: &ScanReading, at: SystemTime) -> Self {
+        JsonRecord {
+            input: reading.entry.input.clone(),
+            raw: reading.raw,
+            volts: rdata_to_voltage(reading.raw, reading.entry.gain),
+            timestamp: unix_seconds(at),
+            config: reading.entry.config_bits(),
+        }
+    }
+
+    /// Write the record as a single line of JSON
+    pub fn write_line<W>(&self, mut writer: W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        serde_json::to_writer(&mut writer, self)?;
+        writeln!(writer)
+    }
+}
+
+// times before the epoch are clamped, they can only come from a misset clock
+fn unix_seconds(at: SystemTime) -> f64 {
+    at.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scan::ScanEntry;
+    use crate::Gain;
+    use std::time::Duration;
+
+    #[test]
+    fn writes_one_object_per_line() {
+        let at = UNIX_EPOCH + Duration::from_millis(1_700_000_000_250);
+        let mut out = Vec::new();
+        for (index, gain) in [Gain::One, Gain::Four].into_iter().enumerate() {
+            let reading = ScanReading {
+                index,
+                sequence: index as u64,
+                entry: ScanEntry::new(InputSelection::AN2SingleEnded).with_gain(gain),
+                raw: 0x4000,
+                timestamp: Duration::ZERO,
+            };
+            JsonRecord::from_scan_reading(&reading, at)
+                .write_line(&mut out)
+                .unwrap();
+        }
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["input"], "AN2SingleEnded");
+        assert_eq!(lines[0]["raw"], 0x4000);
+        assert_eq!(lines[0]["timestamp"], 1_700_000_000.25);
+        assert_eq!(lines[1]["config"], 0b1011_0000);
+        let ratio = lines[0]["volts"].as_f64().unwrap() / lines[1]["volts"].as_f64().unwrap();
+        assert!((ratio - 4.0).abs() < 0.0001);
+    }
+}
@@ -1,13 +1,174 @@
+//! The drivers are layered: [ll] sends the device's commands and reads and writes its
+//! registers, with no policy. [Ads1119] and [ads1219::Ads1219] build the high-level layer on
+//! the same code: one-shot reads with timeouts, scans, filters, calibration and so on.
+//!
+//! Without the default `std` feature, the crate is `no_std`: it provides the register types,
+//! the conversion functions, the [filter]s, the register-level and interrupt-driven parts of the
+//! blocking [Ads1119] driver and, with the `async` feature, the async driver.
+//! The blocking one-shot reads and the helpers built on them need `std`, except for
+//! [Ads1119::read_input_oneshot_with_delay], which waits with the given delay.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::ops::RangeInclusive;
+use core::time::Duration;
 use embedded_hal::i2c::I2c;
-use std::time::{Duration, Instant};
+
+use calibration::CalibrationTable;
+#[cfg(feature = "std")]
+use cancel::CancellationToken;
+use clock::{Clock, TimestampSource, Timestamped};
+#[cfg(feature = "std")]
+use clock::{StdClock, StdDelay};
+use divider::VoltageDivider;
+use embedded_hal::delay::DelayNs;
+use filter::ExponentialMovingAverage;
+use registers::{ConfigRegister, StatusRegister};
+use stats::DriverStats;
+#[cfg(not(feature = "std"))]
+use wait::AdaptivePoll;
+#[cfg(feature = "std")]
+use wait::{AdaptivePoll, WaitStrategy};
+
+#[cfg(feature = "std")]
+pub mod adc;
+pub mod ads1219;
+#[cfg(feature = "std")]
+pub mod alarms;
+#[cfg(feature = "async")]
+pub mod asynch;
+#[cfg(feature = "std")]
+pub mod average;
+#[cfg(feature = "std")]
+pub mod battery;
+#[cfg(feature = "std")]
+pub mod block;
+#[cfg(feature = "std")]
+pub mod bridge;
+pub mod calibration;
+#[cfg(feature = "std")]
+pub mod cancel;
+#[cfg(feature = "std")]
+pub mod capture;
+#[cfg(feature = "ccsds")]
+pub mod ccsds;
+pub mod clipping;
+pub mod clock;
+pub mod continuous;
+#[cfg(feature = "std")]
+pub mod current_loop;
+#[cfg(feature = "daemon")]
+pub mod daemon;
+pub mod divider;
+#[cfg(feature = "std")]
+pub mod duty_cycle;
+#[cfg(feature = "std")]
+pub mod expansion;
+mod family;
+pub mod filter;
+#[cfg(feature = "gpio-cdev")]
+pub mod gpio_cdev;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod interrupt;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod ll;
+#[cfg(feature = "std")]
+pub mod logger;
+#[cfg(feature = "std")]
+pub mod mains;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "std")]
+pub mod multi;
+#[cfg(feature = "std")]
+pub mod noise;
+#[cfg(feature = "power-guard")]
+pub mod power;
+#[cfg(feature = "std")]
+pub mod profile;
+#[cfg(feature = "std")]
+pub mod ratiometric;
+pub mod reading;
+#[cfg(feature = "std")]
+pub mod redundancy;
+pub mod registers;
+#[cfg(feature = "heapless")]
+pub mod ring_buffer;
+#[cfg(feature = "std")]
+pub mod rtd;
+#[cfg(feature = "std")]
+pub mod sampler;
+#[cfg(feature = "std")]
+pub mod scan;
+#[cfg(feature = "std")]
+pub mod scrub;
+#[cfg(feature = "std")]
+pub mod self_test;
+pub mod sequence;
+#[cfg(feature = "std")]
+pub mod shared;
+#[cfg(feature = "std")]
+pub mod shunt;
+#[cfg(feature = "sim")]
+pub mod sim;
+#[cfg(feature = "dsp")]
+pub mod spectrum;
+#[cfg(feature = "std")]
+pub mod statistics;
+pub mod stats;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+#[cfg(feature = "std")]
+pub mod thermistor;
+#[cfg(feature = "tokio")]
+pub mod tokio_adapter;
+pub mod units;
+pub mod wait;
+#[cfg(feature = "std")]
+pub mod wiring;
+
+/// The I2C addresses the device can be strapped to with its A0 and A1 pins.
+/// See 8.5.1.1 I2C Address
+pub const ADDRESSES: RangeInclusive<u8> = 0x40..=0x4F;
 
 const READ_INPUT_TIMEOUT: Duration = Duration::from_secs(1);
 const READ_INPUT_SLEEP: Duration = Duration::from_millis(10);
+/// Time allowed for the device to come out of a reset before it's accessed again
+const RESET_TIME: Duration = Duration::from_micros(500);
+/// Time a status check takes at least, two bytes on a 400 kHz bus with some overhead
+const MIN_CHECK_TIME: Duration = Duration::from_micros(100);
 
 pub struct Ads1119<I2C> {
     i2c: I2C,
     // I2C address
     address: u8,
+    calibrations: CalibrationTable,
+    // indexed by InputSelection::mux_index
+    dividers: [Option<VoltageDivider>; MUX_COUNT],
+    // indexed by InputSelection::mux_index
+    smoothing: [Option<ExponentialMovingAverage>; MUX_COUNT],
+    // read by on_drdy_interrupt, not yet taken
+    result: Option<Timestamped<i16>>,
+    // the last value written to the config register, if known
+    config: Option<u8>,
+    // sequence number of the next continuous or scan result, see [sequence]
+    #[cfg(feature = "std")]
+    sequence: u64,
+    stats: DriverStats,
+    clipping_margin: f32,
+    #[cfg(feature = "std")]
+    wait: Box<dyn WaitStrategy + Send>,
+    // None without std: the timeouts are then approximated from the delays
+    clock: Option<&'static (dyn Clock + Sync)>,
+    // None to timestamp the results on the clock
+    timestamps: Option<&'static (dyn TimestampSource + Sync)>,
+    #[cfg(feature = "std")]
+    delay: Box<dyn DelayNs + Send>,
+    #[cfg(feature = "std")]
+    cancel: Option<CancellationToken>,
+    #[cfg(feature = "std")]
+    profiles: profile::Profiles,
 }
 
 impl<I2C> Ads1119<I2C>
@@ -18,6 +179,28 @@ where
         Ads1119 {
             i2c,
             address: i2c_address,
+            calibrations: CalibrationTable::new(),
+            dividers: [None; MUX_COUNT],
+            smoothing: [None; MUX_COUNT],
+            result: None,
+            config: None,
+            #[cfg(feature = "std")]
+            sequence: 0,
+            stats: DriverStats::default(),
+            clipping_margin: clipping::DEFAULT_CLIPPING_MARGIN,
+            #[cfg(feature = "std")]
+            wait: Box::new(AdaptivePoll::default()),
+            #[cfg(feature = "std")]
+            clock: Some(&StdClock),
+            #[cfg(not(feature = "std"))]
+            clock: None,
+            timestamps: None,
+            #[cfg(feature = "std")]
+            delay: Box::new(StdDelay),
+            #[cfg(feature = "std")]
+            cancel: None,
+            #[cfg(feature = "std")]
+            profiles: profile::Profiles::new(),
         }
     }
 
@@ -38,26 +221,39 @@ where
     ///
     /// See [MuxFlags]
     pub fn read_config(&mut self) -> Result<u8, I2C::Error> {
-        let mut read_buffer = [0];
-        self.i2c
-            .write_read(
-                self.address,
-                // set the config register bit
-                &[CmdFlags::RREG | RegSelectFlags::CONFIG],
-                &mut read_buffer,
-            )
-            .and(Ok(read_buffer[0]))
+        family::read_register(&mut self.i2c, self.address, Register::Config)
+            .inspect_err(|_| self.stats.i2c_errors += 1)
+    }
+
+    /// Convert a raw conversion result to volts with the gain of the config last written by
+    /// the driver, see [rdata_to_voltage].
+    ///
+    /// Returns None if that config isn't known (e.g. before the first write or after a
+    /// failed one), or if it selects the external reference, whose voltage the driver
+    /// doesn't know (see [ratiometric]).
+    pub fn convert(&self, raw_data: i16) -> Option<f32> {
+        let config = Config::from_bits(self.config?);
+        match config.voltage_reference {
+            VoltageReference::Internal => Some(rdata_to_voltage(raw_data, config.gain)),
+            VoltageReference::External => None,
+        }
+    }
+
+    /// Read the config register and decode it, see [Config::from_bits]
+    pub fn read_config_decoded(&mut self) -> Result<Config, I2C::Error> {
+        self.read_config().map(Config::from_bits)
     }
 
     /// Write the config register with the given value. See [read_config] for u8 structure.
     ///
     /// See [MuxFlags]
     pub fn write_config(&mut self, value: u8) -> Result<(), I2C::Error> {
-        self.i2c.write(
-            self.address,
-            &[CmdFlags::WREG | RegSelectFlags::CONFIG, value],
-        ) //A0
-          // .write(self.address, &[CmdFlags::WREG | RegFlags::CONFIG, 0xA0]) //A2
+        // if the write fails, the device may or may not have the new value
+        self.config = None;
+        family::write_config(&mut self.i2c, self.address, value)
+            .inspect_err(|_| self.stats.i2c_errors += 1)?;
+        self.config = Some(value);
+        Ok(())
     }
 
     /// Read the status register.
@@ -69,27 +265,68 @@ where
     /// The only bit that matters is the MSB. If set, a new conversion is ready to be read
     /// with [read_data]. If it isn't set, the application should wait and check the status register again.
     pub fn read_status(&mut self) -> Result<u8, I2C::Error> {
-        let mut read_buffer = [0];
-        self.i2c
-            .write_read(
-                self.address,
-                &[CmdFlags::RREG | RegSelectFlags::STATUS],
-                &mut read_buffer,
-            )
-            .and(Ok(read_buffer[0]))
+        family::read_register(&mut self.i2c, self.address, Register::Status)
+            .inspect_err(|_| self.stats.i2c_errors += 1)
+    }
+
+    /// Check the status register once: true if a new conversion result is ready to be read.
+    /// See [STATUS_CONV_RDY]
+    pub fn is_conversion_ready(&mut self) -> Result<bool, I2C::Error> {
+        Ok(StatusRegister::from_bits(self.read_status()?).conversion_ready())
     }
 
-    /// In single-shot conversion mode (the only one currently supported), this starts a conversion.
+    /// In single-shot conversion mode, this starts a conversion.
     /// Before reading a result, use [read_status] to check the the conversion has finished.
+    ///
+    /// In continuous conversion mode, this starts (or restarts) the stream of conversions.
     /// See 8.5.3.3
     pub fn start_sync(&mut self) -> Result<(), I2C::Error> {
-        self.i2c.write(self.address, &[CmdFlags::START_SYNC])
+        family::command(&mut self.i2c, self.address, Command::StartSync)
+            .inspect_err(|_| self.stats.i2c_errors += 1)
+    }
+
+    /// Enter power-down mode. Any conversion in progress is finished first.
+    /// Issue [start_sync] to wake the device up again.
+    /// See 8.5.3.4
+    pub fn power_down(&mut self) -> Result<(), I2C::Error> {
+        family::command(&mut self.i2c, self.address, Command::PowerDown)
+            .inspect_err(|_| self.stats.i2c_errors += 1)
     }
 
     /// Resets the device to a default state.
     /// See 8.5.3.2
     pub fn reset(&mut self) -> Result<(), I2C::Error> {
-        self.i2c.write(self.address, &[CmdFlags::RESET])
+        self.config = None;
+        family::command(&mut self.i2c, self.address, Command::Reset)
+            .inspect_err(|_| self.stats.i2c_errors += 1)?;
+        self.config = Some(0);
+        Ok(())
+    }
+
+    /// Send a raw command byte, e.g. one the driver doesn't know. See 8.5.3
+    ///
+    /// The driver can't tell what the command does, so it forgets the config it last wrote
+    /// (see [Ads1119::convert]). I2C errors are counted in [Ads1119::stats].
+    pub fn send_command(&mut self, command: u8) -> Result<(), I2C::Error> {
+        self.config = None;
+        self.i2c
+            .write(self.address, &[command])
+            .inspect_err(|_| self.stats.i2c_errors += 1)
+    }
+
+    /// Read a register as is. See 8.5.3.6 RREG
+    pub fn read_register(&mut self, register: Register) -> Result<u8, I2C::Error> {
+        family::read_register(&mut self.i2c, self.address, register)
+            .inspect_err(|_| self.stats.i2c_errors += 1)
+    }
+
+    /// Write a register as is, e.g. bits the driver doesn't expose. See 8.5.3.7 WREG
+    ///
+    /// Unlike [Ads1119::write_config], this forgets the config the driver last wrote.
+    pub fn write_register(&mut self, register: Register, value: u8) -> Result<(), I2C::Error> {
+        self.config = None;
+        family::write_register(&mut self.i2c, self.address, register, value)
+            .inspect_err(|_| self.stats.i2c_errors += 1)
     }
 
     /// Reads data from the currently selected input.
@@ -99,10 +336,231 @@ where
     /// See 8.5.3.5 RDATA
     /// See 8.5.2 Data Format
     pub fn read_data(&mut self) -> Result<i16, I2C::Error> {
-        let mut read_buffer = [0u8, 0u8];
-        self.i2c
-            .write_read(self.address, &[CmdFlags::RDATA], &mut read_buffer)
-            .and(Ok(i16::from_be_bytes(read_buffer)))
+        self.read_data_raw().map(i16::from_be_bytes)
+    }
+
+    /// Reads data from the currently selected input, as the two bytes sent by the device
+    /// (MSB first). [Ads1119::read_data] is `i16::from_be_bytes` of them.
+    ///
+    /// See 8.5.3.5 RDATA
+    pub fn read_data_raw(&mut self) -> Result<[u8; 2], I2C::Error> {
+        let data = family::read_data(&mut self.i2c, self.address);
+        match data {
+            Ok(_) => self.stats.conversions += 1,
+            Err(_) => self.stats.i2c_errors += 1,
+        }
+        data
+    }
+
+    /// Snapshot of the counters kept by the driver since it was created or
+    /// [Ads1119::reset_stats]
+    pub fn stats(&self) -> DriverStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = DriverStats::default();
+    }
+
+    /// Reset the device with [Ads1119::reset], wait for it to come out of reset, then check
+    /// that it's in its documented default state: config register 0x00 and no conversion
+    /// result ready. See 8.6.2
+    ///
+    /// Returns [Ads1119Err::NotInDefaultState] with the registers read otherwise.
+    pub fn reset_and_verify_with_delay(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Ads1119Err<I2C::Error>> {
+        self.reset()?;
+        delay.delay_us(RESET_TIME.as_micros() as u32);
+        let config = self.read_config()?;
+        let status = self.read_status()?;
+        if config != 0 || StatusRegister::from_bits(status).conversion_ready() {
+            // the device didn't reset as expected, so its config is unknown
+            self.config = None;
+            return Err(Ads1119Err::NotInDefaultState { config, status });
+        }
+        Ok(())
+    }
+
+    /// Write the config register, then read it back to check that the device took it.
+    ///
+    /// Returns [Ads1119Err::ConfigMismatch] with both values otherwise.
+    pub fn write_config_verified(&mut self, value: u8) -> Result<(), Ads1119Err<I2C::Error>> {
+        self.write_config(value)?;
+        let read = self.read_config()?;
+        if read != value {
+            self.config = None;
+            return Err(Ads1119Err::ConfigMismatch {
+                written: value,
+                read,
+            });
+        }
+        Ok(())
+    }
+
+    /// Create the driver, reset the device with [Ads1119::reset_and_verify_with_delay] and
+    /// apply `config` with [Ads1119::write_config_verified].
+    ///
+    /// On error the bus is dropped with the driver; to keep it, make the calls after
+    /// [Ads1119::new] instead.
+    pub fn new_with_config_and_delay(
+        i2c: I2C,
+        i2c_address: u8,
+        config: &Config,
+        delay: &mut impl DelayNs,
+    ) -> Result<Self, Ads1119Err<I2C::Error>> {
+        let mut device = Ads1119::new(i2c, i2c_address);
+        device.reset_and_verify_with_delay(delay)?;
+        device.write_config_verified(config.bits())?;
+        Ok(device)
+    }
+
+    /// Use the given clock for the conversion timeouts and the sample timestamps. The default
+    /// is [clock::StdClock] on `std`; without one, the timeouts are approximated by adding up
+    /// the delays and the timestamps are zero.
+    pub fn set_clock(&mut self, clock: &'static (dyn Clock + Sync)) {
+        self.clock = Some(clock);
+    }
+
+    /// Timestamp the results with `source` instead of the clock, e.g. with
+    /// [clock::SyncedTimestamps] following vehicle time. The clock still runs the timeouts
+    /// and measures the intervals between samples.
+    pub fn set_timestamp_source(&mut self, source: &'static (dyn TimestampSource + Sync)) {
+        self.timestamps = Some(source);
+    }
+
+    /// Go back to timestamping the results with the clock
+    pub fn clear_timestamp_source(&mut self) {
+        self.timestamps = None;
+    }
+
+    /// The time on the clock, zero without one
+    pub(crate) fn now(&self) -> Duration {
+        self.clock.map_or(Duration::ZERO, |clock| clock.now())
+    }
+
+    /// The timestamp of a result read now, from the timestamp source or the clock
+    pub(crate) fn timestamp(&self) -> Duration {
+        self.timestamps
+            .map_or_else(|| self.now(), |source| source.timestamp())
+    }
+
+    /// Take the sequence number of a result, after `skipped` results lost before it
+    #[cfg(feature = "std")]
+    pub(crate) fn next_sequence(&mut self, skipped: u64) -> u64 {
+        let sequence = self.sequence.wrapping_add(skipped);
+        self.sequence = sequence.wrapping_add(1);
+        sequence
+    }
+
+    /// Same as [Ads1119::read_input_oneshot], waiting between status checks with `delay`.
+    ///
+    /// This is the one-shot read available without `std`, where it polls the status
+    /// register with [wait::AdaptivePoll]. On `std`, it uses the wait strategy set with
+    /// [Ads1119::set_wait_strategy].
+    pub fn read_input_oneshot_with_delay(
+        &mut self,
+        input: &InputSelection,
+        delay: &mut impl DelayNs,
+    ) -> Result<i16, Ads1119Err<I2C::Error>> {
+        self.write_config(input.bits())?;
+        self.start_sync()?;
+        self.wait_for_conversion_with_delay(READ_INPUT_TIMEOUT, delay)?;
+        Ok(self.read_data()?)
+    }
+
+    /// Poll the status register until a new conversion result is ready, waiting between
+    /// checks as the driver's [wait::WaitStrategy] says with `delay`, e.g. after starting a
+    /// conversion in a custom sequence or on an external trigger.
+    ///
+    /// Fails with [Ads1119Err::ConversionTimeout] after `timeout`, or
+    /// [Ads1119Err::Cancelled] (see [Ads1119::set_cancellation_token]).
+    pub fn wait_for_conversion_with_delay(
+        &mut self,
+        timeout: Duration,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Ads1119Err<I2C::Error>> {
+        let conversion_time = self
+            .config
+            .map(|config| DataRate::from_bits(config).conversion_time());
+        #[cfg(feature = "std")]
+        let wait = &mut *self.wait;
+        #[cfg(not(feature = "std"))]
+        let wait = &mut AdaptivePoll::default();
+        #[cfg(feature = "std")]
+        let cancelled = || {
+            self.cancel
+                .as_ref()
+                .is_some_and(CancellationToken::is_cancelled)
+        };
+        #[cfg(not(feature = "std"))]
+        let cancelled = || false;
+        let waiter = family::Waiter {
+            strategy: wait,
+            clock: self.clock.map(|clock| clock as &dyn Clock),
+            delay,
+            cancelled: &cancelled,
+        };
+        let result = family::wait_conversion_ready(
+            &mut self.i2c,
+            self.address,
+            waiter,
+            conversion_time,
+            timeout,
+        );
+        self.stats.record_wait(&result);
+        result.map(|_| ())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Choose how the blocking reads wait for conversions, see [WaitStrategy].
+    /// The default is [wait::AdaptivePoll], polling around the conversion time of the data rate.
+    ///
+    /// Strategies that use the conversion time (e.g. [wait::ComputedWait]) get it from the
+    /// data rate of the last config written by the driver, or the default one after
+    /// [Ads1119::reset].
+    pub fn set_wait_strategy<W>(&mut self, wait: W)
+    where
+        W: WaitStrategy + Send + 'static,
+    {
+        self.wait = Box::new(wait);
+    }
+
+    /// Wait between status checks with the given delay, instead of [clock::StdDelay]
+    /// sleeping the thread. See also [Ads1119::read_input_oneshot_with_delay].
+    pub fn set_delay(&mut self, delay: impl DelayNs + Send + 'static) {
+        self.delay = Box::new(delay);
+    }
+
+    /// Check `token` while waiting for conversions. Once it's cancelled, the blocking reads
+    /// fail with [Ads1119Err::Cancelled] (within one wait of the strategy, at most a conversion time by default)
+    /// and the [continuous::Samples] iterator ends.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancel = Some(token);
+    }
+
+    /// Same as [Ads1119::new_with_config_and_delay], waiting with [StdDelay]
+    pub fn new_with_config(
+        i2c: I2C,
+        i2c_address: u8,
+        config: &Config,
+    ) -> Result<Self, Ads1119Err<I2C::Error>> {
+        Ads1119::new_with_config_and_delay(i2c, i2c_address, config, &mut StdDelay)
+    }
+
+    /// Same as [Ads1119::reset_and_verify_with_delay], waiting with the driver's delay
+    /// (see [Ads1119::set_delay])
+    pub fn reset_and_verify(&mut self) -> Result<(), Ads1119Err<I2C::Error>> {
+        let mut delay = core::mem::replace(&mut self.delay, Box::new(StdDelay));
+        let result = self.reset_and_verify_with_delay(&mut &mut *delay);
+        self.delay = delay;
+        result
     }
 
     /// Read data from the given input with "one-shot" semantics.
@@ -118,33 +576,84 @@ where
         input: &InputSelection,
     ) -> Result<i16, Ads1119Err<I2C::Error>> {
         // write the config to set the input we want. Leave other fields unset (default)
-        self.write_config(input.bits())?;
-
-        // start a "one-shot" conversion on the selected input
-        self.start_sync()?;
+        self.read_oneshot(input.bits(), None)
+    }
 
-        let start_time = Instant::now();
-        // wait until the status register tells us there is data to read
+    /// Same as [Ads1119::read_input_oneshot], running the whole sequence (config, start,
+    /// wait, read) again after a timeout or an I2C error, up to `retries` more times.
+    ///
+    /// Each retry is counted in [DriverStats::retries]. Other errors, e.g.
+    /// [Ads1119Err::Cancelled], end the attempts right away. The error returned carries the
+    /// number of attempts made and the last error.
+    pub fn read_input_oneshot_with_retries(
+        &mut self,
+        input: &InputSelection,
+        retries: u32,
+    ) -> Result<i16, RetryError<I2C::Error>> {
+        let mut attempts = 0;
         loop {
-            let status = self.read_status()?;
-            if status & STATUS_CONV_RDY != 0 {
-                break;
+            attempts += 1;
+            match self.read_input_oneshot(input) {
+                Ok(value) => return Ok(value),
+                Err(Ads1119Err::ConversionTimeout(_) | Ads1119Err::I2CError { .. })
+                    if attempts <= retries =>
+                {
+                    self.stats.retries += 1;
+                }
+                Err(last) => return Err(RetryError { attempts, last }),
             }
+        }
+    }
 
-            // Check if the timeout duration has elapsed
-            if start_time.elapsed() >= READ_INPUT_TIMEOUT {
-                return Err(Ads1119Err::ConversionTimeout(
-                    READ_INPUT_TIMEOUT.as_millis(),
-                ));
-            }
+    /// Wait `duration` with the driver's delay (see [Ads1119::set_delay])
+    pub(crate) fn sleep(&mut self, duration: Duration) {
+        clock::delay_for(&mut *self.delay, duration);
+    }
 
-            // need to poll at least as fast as the data rate (default is 50ms (20 SPS))
-            std::thread::sleep(READ_INPUT_SLEEP)
+    /// Run a single-shot conversion using the given raw config register value.
+    ///
+    /// If `settle` is given, the driver waits that long between writing the config
+    /// and starting the conversion (e.g. to let an input settle after a mux/gain change).
+    pub(crate) fn read_oneshot(
+        &mut self,
+        config: u8,
+        settle: Option<Duration>,
+    ) -> Result<i16, Ads1119Err<I2C::Error>> {
+        self.write_config(config)?;
+
+        if let Some(settle) = settle {
+            self.sleep(settle);
         }
 
+        self.convert_oneshot()
+    }
+
+    /// Start a single-shot conversion with the config already in the device,
+    /// wait for it to finish and read the result.
+    pub(crate) fn convert_oneshot(&mut self) -> Result<i16, Ads1119Err<I2C::Error>> {
+        // start a "one-shot" conversion on the selected input
+        self.start_sync()?;
+
+        self.wait_conversion_ready()?;
+
         // read the conversion data
         Ok(self.read_data()?)
     }
+
+    /// Poll the status register until a new conversion result is ready,
+    /// failing with [Ads1119Err::ConversionTimeout] after [READ_INPUT_TIMEOUT].
+    pub(crate) fn wait_conversion_ready(&mut self) -> Result<(), Ads1119Err<I2C::Error>> {
+        self.wait_for_conversion(READ_INPUT_TIMEOUT)
+    }
+
+    /// Same as [Ads1119::wait_for_conversion_with_delay], waiting with the driver's delay
+    /// (see [Ads1119::set_delay])
+    pub fn wait_for_conversion(&mut self, timeout: Duration) -> Result<(), Ads1119Err<I2C::Error>> {
+        let mut delay = core::mem::replace(&mut self.delay, Box::new(StdDelay));
+        let result = self.wait_for_conversion_with_delay(timeout, &mut &mut *delay);
+        self.delay = delay;
+        result
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -152,6 +661,38 @@ pub enum Ads1119Err<I2CE> {
     #[error("conversion timed out after waiting {0}ms")]
     ConversionTimeout(u128),
 
+    #[error("cancelled while waiting for a conversion")]
+    Cancelled,
+
+    #[error("no profile registered under this name")]
+    UnknownProfile,
+
+    #[error("at least one sample is required")]
+    NoSamples,
+
+    #[error("calibration failed: the two reference points give the same reading or reference")]
+    DegenerateCalibration,
+
+    #[error("reading is outside of the sensor's valid range (open or shorted input?)")]
+    SensorOutOfRange,
+
+    #[error(
+        "device isn't in its default state after reset (config {config:#04x}, status {status:#010b})"
+    )]
+    NotInDefaultState { config: u8, status: u8 },
+
+    #[error("config register reads back {read:#04x} after writing {written:#04x}")]
+    ConfigMismatch { written: u8, read: u8 },
+
+    #[error("no {required} consecutive readings agreed in {conversions} conversions")]
+    Inconsistent { required: usize, conversions: usize },
+
+    #[error("no trigger after waiting {0}ms")]
+    TriggerTimeout(u128),
+
+    #[error("the voltage of the external reference isn't known")]
+    UnknownReference,
+
     #[error("I2C error")]
     I2CError {
         #[from]
@@ -159,20 +700,110 @@ pub enum Ads1119Err<I2CE> {
     },
 }
 
+/// Error of [Ads1119::read_input_oneshot_with_retries] once it gave up
+#[derive(thiserror::Error, Debug)]
+#[error("reading failed after {attempts} attempts")]
+pub struct RetryError<I2CE> {
+    /// Attempts made, the first one included
+    pub attempts: u32,
+    /// Error of the last attempt
+    #[source]
+    pub last: Ads1119Err<I2CE>,
+}
+
 /// Interpret the raw data read from one of the inputs as a voltage
 /// Currently, this function assumes the reference voltage is the internal 2.048V source
 /// See 8.3.3 Voltage Reference
 ///     8.5.2 Data Format
 pub fn single_ended_rdata_to_scaled_voltage(raw_data: i16) -> f32 {
+    // Note that casting i16 to f32 is lossless and safe
+    single_ended_code_to_voltage(raw_data as f32)
+}
+
+/// Interpret raw data as a voltage, for any input (single-ended or differential) and gain.
+/// Assumes the internal 2.048V reference, so the full-scale range is ±2.048V / gain.
+/// See 8.5.2 Data Format
+pub fn rdata_to_voltage(raw_data: i16, gain: Gain) -> f32 {
+    single_ended_code_to_voltage(raw_data as f32) / gain.factor()
+}
+
+/// Same as [single_ended_rdata_to_scaled_voltage], for codes that aren't integers (e.g. averages)
+pub(crate) fn single_ended_code_to_voltage(code: f32) -> f32 {
     // Positive value, directly scale based on the ADS1119's configuration
     // In this case, the reference voltage is 2.048V
-    const REFERENCE_VOLTAGE: f32 = 2.048;
-
     // Scale the voltage to the desired range (e.g., 0V to 2.048V)
-    // Note that casting i16 to f32 is lossless and safe
-    (raw_data as f32 / 0x7FFF as f32) * REFERENCE_VOLTAGE
+    (code / 0x7FFF as f32) * INTERNAL_REFERENCE_VOLTAGE
 }
-/// Command Flags
+
+/// Voltage of the internal reference
+/// See 8.3.3 Voltage Reference
+pub(crate) const INTERNAL_REFERENCE_VOLTAGE: f32 = 2.048;
+
+/// Commands of the device, see [Command::encode] for their bytes.
+/// See 8.5.3
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Reset,
+    StartSync,
+    PowerDown,
+    /// RDATA, followed by a read of the conversion result
+    ReadData,
+    /// RREG, followed by a read of the register
+    ReadRegister(Register),
+    /// WREG, followed by the value to write. Only the config register is writable, so
+    /// [Command::decode] never gives the status register
+    WriteRegister(Register),
+}
+
+impl Command {
+    /// The command byte sent to the device
+    pub fn encode(&self) -> u8 {
+        match self {
+            Command::Reset => CmdFlags::RESET,
+            Command::StartSync => CmdFlags::START_SYNC,
+            Command::PowerDown => CmdFlags::POWER_DOWN,
+            Command::ReadData => CmdFlags::RDATA,
+            Command::ReadRegister(register) => CmdFlags::RREG | register.encode(),
+            Command::WriteRegister(register) => CmdFlags::WREG | register.encode(),
+        }
+    }
+
+    /// The command a byte encodes, or None if it isn't one of [Command::encode]'s.
+    /// The don't-care bits of the datasheet's encodings must be 0.
+    pub fn decode(byte: u8) -> Option<Self> {
+        [
+            Command::Reset,
+            Command::StartSync,
+            Command::PowerDown,
+            Command::ReadData,
+            Command::ReadRegister(Register::Config),
+            Command::ReadRegister(Register::Status),
+            Command::WriteRegister(Register::Config),
+        ]
+        .into_iter()
+        .find(|command| command.encode() == byte)
+    }
+}
+
+/// Registers of the device, see [Command::ReadRegister].
+/// See 8.6.1 - Table 8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+    Config,
+    Status,
+}
+
+impl Register {
+    /// The register selection bits of the RREG and WREG commands
+    pub fn encode(&self) -> u8 {
+        match self {
+            Register::Config => RegSelectFlags::CONFIG,
+            Register::Status => RegSelectFlags::STATUS,
+        }
+    }
+}
+
+/// Command bytes, see [Command] for the typed version
 /// See 8.5.3
 pub struct CmdFlags;
 impl CmdFlags {
@@ -188,26 +819,226 @@ impl CmdFlags {
 /// See 8.6.2.1 Configuration Register
 /// See 8.3.1 Multiplexer
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InputSelection {
+    /// AINP = AIN0, AINN = AIN1
+    AN0AN1Differential,
+    /// AINP = AIN2, AINN = AIN3
+    AN2AN3Differential,
+    /// AINP = AIN1, AINN = AIN2
+    AN1AN2Differential,
     AN0SingleEnded,
     AN1SingleEnded,
     AN2SingleEnded,
     AN3SingleEnded,
+    /// AINP and AINN shorted to AVDD / 2, e.g. for offset and noise measurements
+    ShortedToMidSupply,
 }
 
 impl InputSelection {
     pub fn bits(&self) -> u8 {
+        ConfigRegister::new().with_input(self.clone()).into_bits()
+    }
+
+    /// The input selected by the MUX field of a config register value; the other bits are ignored
+    pub fn from_bits(bits: u8) -> Self {
+        ConfigRegister::from_bits(bits).input()
+    }
+
+    /// Position of the input in the 3-bit MUX field, from 0 to [MUX_COUNT] - 1
+    pub(crate) fn mux_index(&self) -> usize {
+        self.clone().into_field() as usize
+    }
+}
+
+/// Number of possible values of the MUX field of the config register
+pub(crate) const MUX_COUNT: usize = 8;
+
+/// PGA gain selection
+/// See 8.6.2.1 Configuration Register
+/// See 8.3.2 Gain
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gain {
+    #[default]
+    One,
+    Four,
+}
+
+impl Gain {
+    pub fn bits(&self) -> u8 {
+        ConfigRegister::new().with_gain(*self).into_bits()
+    }
+
+    /// The gain selected by the GAIN bit of a config register value; the other bits are ignored
+    pub fn from_bits(bits: u8) -> Self {
+        ConfigRegister::from_bits(bits).gain()
+    }
+
+    /// The gain as a multiplier
+    pub fn factor(&self) -> f32 {
         match self {
-            InputSelection::AN0SingleEnded => 0b0110_0000,
-            InputSelection::AN1SingleEnded => 0b1000_0000,
-            InputSelection::AN2SingleEnded => 0b1010_0000,
-            InputSelection::AN3SingleEnded => 0b1100_0000,
+            Gain::One => 1.0,
+            Gain::Four => 4.0,
         }
     }
 }
 
+/// Data rate selection, in samples per second
+/// See 8.6.2.1 Configuration Register
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataRate {
+    #[default]
+    Sps20,
+    Sps90,
+    Sps330,
+    Sps1000,
+}
+
+impl DataRate {
+    pub fn bits(&self) -> u8 {
+        ConfigRegister::new().with_data_rate(*self).into_bits()
+    }
+
+    /// The data rate selected by the DR field of a config register value; the other bits are ignored
+    pub fn from_bits(bits: u8) -> Self {
+        ConfigRegister::from_bits(bits).data_rate()
+    }
+
+    /// Samples per second
+    pub fn sps(&self) -> u32 {
+        match self {
+            DataRate::Sps20 => 20,
+            DataRate::Sps90 => 90,
+            DataRate::Sps330 => 330,
+            DataRate::Sps1000 => 1000,
+        }
+    }
+
+    /// Nominal time of one conversion, the inverse of the data rate
+    pub fn conversion_time(&self) -> Duration {
+        Duration::from_secs(1) / self.sps()
+    }
+}
+
+/// Conversion mode selection
+/// See 8.6.2.1 Configuration Register
+/// See 8.4.2 Conversion Modes
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConversionMode {
+    #[default]
+    SingleShot,
+    Continuous,
+}
+
+impl ConversionMode {
+    pub fn bits(&self) -> u8 {
+        ConfigRegister::new()
+            .with_conversion_mode(*self)
+            .into_bits()
+    }
+
+    /// The mode selected by the CM bit of a config register value; the other bits are ignored
+    pub fn from_bits(bits: u8) -> Self {
+        ConfigRegister::from_bits(bits).conversion_mode()
+    }
+}
+
+/// Voltage reference selection
+/// See 8.6.2.1 Configuration Register
+/// See 8.3.3 Voltage Reference
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoltageReference {
+    /// Internal 2.048V reference
+    #[default]
+    Internal,
+    /// External reference, applied between the REFP and REFN pins
+    External,
+}
+
+impl VoltageReference {
+    pub fn bits(&self) -> u8 {
+        ConfigRegister::new()
+            .with_voltage_reference(*self)
+            .into_bits()
+    }
+
+    /// The reference selected by the VREF bit of a config register value; the other bits
+    /// are ignored
+    pub fn from_bits(bits: u8) -> Self {
+        ConfigRegister::from_bits(bits).voltage_reference()
+    }
+}
+
+/// Value of the config register, built from its individual fields.
+/// See 8.6.2.1 Configuration Register
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config {
+    pub input: InputSelection,
+    pub gain: Gain,
+    pub data_rate: DataRate,
+    pub conversion_mode: ConversionMode,
+    pub voltage_reference: VoltageReference,
+}
+
+impl Config {
+    /// Create a config for the given input, leaving the other fields at their defaults
+    pub fn new(input: InputSelection) -> Self {
+        Config {
+            input,
+            gain: Gain::default(),
+            data_rate: DataRate::default(),
+            conversion_mode: ConversionMode::default(),
+            voltage_reference: VoltageReference::default(),
+        }
+    }
+
+    pub fn with_gain(mut self, gain: Gain) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn with_data_rate(mut self, data_rate: DataRate) -> Self {
+        self.data_rate = data_rate;
+        self
+    }
+
+    pub fn with_conversion_mode(mut self, conversion_mode: ConversionMode) -> Self {
+        self.conversion_mode = conversion_mode;
+        self
+    }
+
+    pub fn with_voltage_reference(mut self, voltage_reference: VoltageReference) -> Self {
+        self.voltage_reference = voltage_reference;
+        self
+    }
+
+    pub fn bits(&self) -> u8 {
+        ConfigRegister::from(self).into_bits()
+    }
+
+    /// Decode a config register value, e.g. from [Ads1119::read_config].
+    ///
+    /// Every field of the ADS1119 config register is fully decoded (there are no reserved
+    /// encodings), so any value is a valid config and `Config::from_bits(bits).bits() == bits`.
+    pub fn from_bits(bits: u8) -> Self {
+        ConfigRegister::from_bits(bits).into()
+    }
+}
+
+/// See [Config::from_bits]. The blanket `TryFrom<u8>` that comes with it never fails.
+impl From<u8> for Config {
+    fn from(bits: u8) -> Self {
+        Config::from_bits(bits)
+    }
+}
+
 /// Register flags meant to be to combined with eh RREG command to select
-/// the correct register
+/// the correct register, see [Register] for the typed version
 /// See 8.5.3 (RREG)
 /// See 8.6.1 - Table 8 (Register column)
 pub struct RegSelectFlags;
@@ -262,6 +1093,68 @@ mod test {
         assert!(dbg!((single_ended_rdata_to_scaled_voltage(data as i16) - -V_MAX).abs()) < EPS);
     }
 
+    #[test]
+    fn rdata_to_voltage_with_gain() {
+        let data: u16 = 0b0111_1111_1111_1111;
+        assert!((rdata_to_voltage(data as i16, Gain::One) - V_MAX).abs() < EPS);
+        assert!((rdata_to_voltage(data as i16, Gain::Four) - V_MAX / 4.0).abs() < EPS);
+        assert!((rdata_to_voltage(i16::MIN, Gain::Four) + V_MAX / 4.0).abs() < EPS);
+    }
+
+    #[test]
+    fn input_selection_from_bits() {
+        for index in 0..MUX_COUNT as u8 {
+            let input = InputSelection::from_bits((index << 5) | 0b0001_0011);
+            assert_eq!(input.mux_index(), index as usize);
+        }
+    }
+
+    #[test]
+    fn convert_uses_the_written_config() {
+        let gain_four = Config::new(InputSelection::AN0SingleEnded).with_gain(Gain::Four);
+        let external = gain_four
+            .clone()
+            .with_voltage_reference(VoltageReference::External);
+        let mut device = new_ads1119(&[
+            test_support::expect_write_config(DEVICE_ADDRESS, gain_four.bits()),
+            test_support::expect_write_config(DEVICE_ADDRESS, external.bits()),
+            test_support::expect_reset(DEVICE_ADDRESS),
+        ]);
+        assert_eq!(device.convert(0x7FFF), None);
+        device.write_config(gain_four.bits()).unwrap();
+        assert!((device.convert(0x7FFF).unwrap() - V_MAX / 4.0).abs() < EPS);
+        device.write_config(external.bits()).unwrap();
+        assert_eq!(device.convert(0x7FFF), None);
+        device.reset().unwrap();
+        assert!((device.convert(0x7FFF).unwrap() - V_MAX).abs() < EPS);
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn config_from_bits_round_trips() {
+        for bits in 0..=u8::MAX {
+            assert_eq!(Config::from_bits(bits).bits(), bits);
+        }
+        let config = Config::new(InputSelection::AN3SingleEnded)
+            .with_gain(Gain::Four)
+            .with_data_rate(DataRate::Sps330)
+            .with_conversion_mode(ConversionMode::Continuous)
+            .with_voltage_reference(VoltageReference::External);
+        assert_eq!(Config::from(config.bits()), config);
+    }
+
+    #[test]
+    fn commands_encode_to_the_datasheet_bytes() {
+        assert_eq!(Command::StartSync.encode(), 0x08);
+        assert_eq!(Command::ReadRegister(Register::Status).encode(), 0x24);
+        assert_eq!(Command::WriteRegister(Register::Config).encode(), 0x40);
+        let decoded = (0..=u8::MAX).filter_map(Command::decode).count();
+        assert_eq!(decoded, 7);
+        assert_eq!(Command::decode(0x25), None);
+        // the status register is read-only
+        assert_eq!(Command::decode(0x44), None);
+    }
+
     #[test]
     fn rdata_to_voltage_gt_max_neg() {
         // one bit greater than most negative value
@@ -292,7 +1185,7 @@ mod test {
         let destroy_closure = || {
             destroy_ads1119(device);
         };
-        if panic::catch_unwind(destroy_closure).is_err() {};
+        if panic::catch_unwind(panic::AssertUnwindSafe(destroy_closure)).is_err() {};
         panic::set_hook(prev_hook);
     }
 
@@ -376,6 +1269,313 @@ mod test {
         destroy_ads1119(device);
     }
 
+    #[test]
+    fn computed_wait_checks_status_once() {
+        let config = Config::new(InputSelection::AN1SingleEnded).with_data_rate(DataRate::Sps1000);
+        let mut device = new_ads1119(&test_support::expect_oneshot_read_config(
+            DEVICE_ADDRESS,
+            config.bits(),
+            1234,
+        ));
+        device.set_wait_strategy(wait::ComputedWait);
+        let start = std::time::Instant::now();
+        assert_eq!(device.read_oneshot(config.bits(), None).unwrap(), 1234);
+        assert!(start.elapsed() >= Duration::from_micros(1100));
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn data_rate_conversion_time() {
+        assert_eq!(DataRate::Sps20.conversion_time(), Duration::from_millis(50));
+        assert_eq!(
+            DataRate::Sps1000.conversion_time(),
+            Duration::from_millis(1)
+        );
+        for rate in [
+            DataRate::Sps20,
+            DataRate::Sps90,
+            DataRate::Sps330,
+            DataRate::Sps1000,
+        ] {
+            assert_eq!(DataRate::from_bits(rate.bits() | 0b1111_0011), rate);
+        }
+    }
+
+    #[test]
+    fn timeout_follows_the_clock() {
+        static CLOCK: test_support::SteppingClock =
+            test_support::SteppingClock::new(Duration::from_millis(100));
+
+        let input = InputSelection::AN3SingleEnded;
+        let mut transactions = vec![
+            test_support::expect_write_config(DEVICE_ADDRESS, input.bits()),
+            test_support::expect_start_sync(DEVICE_ADDRESS),
+        ];
+        // the clock passes the timeout on the 10th check
+        for _ in 0..10 {
+            transactions.push(test_support::expect_read_status(
+                DEVICE_ADDRESS,
+                NOT_READY_STATUS,
+            ));
+        }
+        let mut device = new_ads1119(&transactions);
+        device.set_wait_strategy(wait::BusyPoll);
+        device.set_clock(&CLOCK);
+        assert!(matches!(
+            device.read_input_oneshot(&input),
+            Err(ConversionTimeout(_))
+        ));
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn drdy_pin_waits_until_the_timeout() {
+        use embedded_hal_mock::eh1::pin::{Mock as PinMock, State, Transaction as PinTransaction};
+
+        static CLOCK: test_support::SteppingClock =
+            test_support::SteppingClock::new(Duration::from_millis(1));
+
+        let transactions = [
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+        ];
+        // DRDY stuck high: polled until the 2 ms timeout, then until what's left of it
+        let pin = PinMock::new(&vec![PinTransaction::get(State::High); 3]);
+        let mut device = new_ads1119(&transactions);
+        let delay = test_support::RecordingDelay::default();
+        device.set_delay(delay.clone());
+        device.set_clock(&CLOCK);
+        device.set_wait_strategy(
+            wait::DrdyPin::new(pin.clone()).with_pin_poll(Duration::from_millis(1)),
+        );
+        assert!(matches!(
+            device.wait_for_conversion(Duration::from_millis(2)),
+            Err(ConversionTimeout(2))
+        ));
+        assert_eq!(delay.delays(), [1_000_000; 3]);
+        destroy_ads1119(device);
+        let mut pin = pin;
+        pin.done();
+    }
+
+    #[test]
+    fn waits_with_the_given_delay() {
+        let input = InputSelection::AN1SingleEnded;
+        let transactions = [
+            test_support::expect_write_config(DEVICE_ADDRESS, input.bits()),
+            test_support::expect_start_sync(DEVICE_ADDRESS),
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            test_support::expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            test_support::expect_read_data(DEVICE_ADDRESS, 42),
+        ];
+        let mut device = new_ads1119(&transactions);
+        let mut delay = test_support::RecordingDelay::default();
+        let start = std::time::Instant::now();
+        assert_eq!(
+            device
+                .read_input_oneshot_with_delay(&input, &mut delay)
+                .unwrap(),
+            42
+        );
+        assert!(start.elapsed() < Duration::from_millis(20));
+        // the default strategy closes in on the 50 ms conversion time of 20 SPS
+        assert_eq!(delay.delays(), [45_000_000, 2_500_000, 5_000_000]);
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn readiness_can_be_checked_and_waited_for() {
+        let mut device = new_ads1119(&[
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            test_support::expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+        ]);
+        assert!(!device.is_conversion_ready().unwrap());
+        let mut delay = test_support::RecordingDelay::default();
+        device
+            .wait_for_conversion_with_delay(Duration::from_millis(100), &mut delay)
+            .unwrap();
+        assert_eq!(delay.delays(), [10_000_000]);
+        assert_eq!(device.stats().waits, 1);
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn timeout_without_clock_adds_up_the_delays() {
+        // 99 delays of 10 ms, plus 100 checks of at least 100 µs
+        let transactions: Vec<_> = (0..100)
+            .map(|_| test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS))
+            .collect();
+        let mut i2c = I2cMock::new(&transactions);
+        let mut delay = test_support::RecordingDelay::default();
+        let waiter = family::Waiter {
+            strategy: &mut wait::FixedSleep::default(),
+            clock: None,
+            delay: &mut delay,
+            cancelled: &|| false,
+        };
+        let result = family::wait_conversion_ready(
+            &mut i2c,
+            DEVICE_ADDRESS,
+            waiter,
+            None,
+            READ_INPUT_TIMEOUT,
+        );
+        assert!(matches!(result, Err(ConversionTimeout(1000))));
+        assert_eq!(delay.delays(), [10_000_000; 99]);
+        i2c.done();
+    }
+
+    #[test]
+    fn cancelled_read_stops_polling() {
+        // cancels the token on its first delay
+        struct CancellingDelay(cancel::CancellationToken);
+
+        impl DelayNs for CancellingDelay {
+            fn delay_ns(&mut self, _ns: u32) {
+                self.0.cancel();
+            }
+        }
+
+        let input = InputSelection::AN2SingleEnded;
+        let transactions = [
+            test_support::expect_write_config(DEVICE_ADDRESS, input.bits()),
+            test_support::expect_start_sync(DEVICE_ADDRESS),
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+        ];
+        let mut device = new_ads1119(&transactions);
+        let token = cancel::CancellationToken::new();
+        device.set_cancellation_token(token.clone());
+        device.set_delay(CancellingDelay(token));
+        assert!(matches!(
+            device.read_input_oneshot(&input),
+            Err(Ads1119Err::Cancelled)
+        ));
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn raw_data_is_the_bytes_sent() {
+        let mut device = new_ads1119(&[
+            test_support::expect_read_data(DEVICE_ADDRESS, -2),
+            test_support::expect_read_data(DEVICE_ADDRESS, -2),
+        ]);
+        assert_eq!(device.read_data_raw().unwrap(), [0xFF, 0xFE]);
+        assert_eq!(device.read_data().unwrap(), -2);
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn reset_and_verify_checks_the_defaults() {
+        let mut device = new_ads1119(&[
+            test_support::expect_reset(DEVICE_ADDRESS),
+            test_support::expect_read_config(DEVICE_ADDRESS, 0),
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            test_support::expect_reset(DEVICE_ADDRESS),
+            test_support::expect_read_config(DEVICE_ADDRESS, 0x60),
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+        ]);
+        let mut delay = test_support::RecordingDelay::default();
+        device.reset_and_verify_with_delay(&mut delay).unwrap();
+        assert_eq!(delay.delays(), [500_000]);
+        assert!(matches!(
+            device.reset_and_verify(),
+            Err(Ads1119Err::NotInDefaultState {
+                config: 0x60,
+                status: NOT_READY_STATUS
+            })
+        ));
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn new_with_config_resets_and_applies_the_config() {
+        let config = Config::new(InputSelection::AN2SingleEnded).with_gain(Gain::Four);
+        let mut transactions = vec![
+            test_support::expect_reset(DEVICE_ADDRESS),
+            test_support::expect_read_config(DEVICE_ADDRESS, 0),
+            test_support::expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            test_support::expect_write_config(DEVICE_ADDRESS, config.bits()),
+            test_support::expect_read_config(DEVICE_ADDRESS, config.bits()),
+        ];
+        let i2c = I2cMock::new(&transactions);
+        let mut delay = test_support::RecordingDelay::default();
+        let device =
+            Ads1119::new_with_config_and_delay(i2c, DEVICE_ADDRESS, &config, &mut delay).unwrap();
+        assert_eq!(
+            device.convert(0x4000),
+            Some(rdata_to_voltage(0x4000, Gain::Four))
+        );
+        destroy_ads1119(device);
+
+        transactions[4] = test_support::expect_read_config(DEVICE_ADDRESS, 0);
+        let mut i2c = I2cMock::new(&transactions);
+        assert!(matches!(
+            Ads1119::new_with_config(i2c.clone(), DEVICE_ADDRESS, &config),
+            Err(Ads1119Err::ConfigMismatch {
+                written: 0b1011_0000,
+                read: 0
+            })
+        ));
+        i2c.done();
+    }
+
+    #[test]
+    fn test_raw_pass_through() {
+        let transactions = [
+            test_support::expect_write_config(DEVICE_ADDRESS, 0b1000_0000),
+            I2cTransaction::write(DEVICE_ADDRESS, vec![0b0000_0001]),
+            I2cTransaction::write(
+                DEVICE_ADDRESS,
+                vec![CmdFlags::WREG | RegSelectFlags::STATUS, 0x12],
+            ),
+            test_support::expect_read_status(DEVICE_ADDRESS, 0x34)
+                .with_error(embedded_hal::i2c::ErrorKind::Other),
+        ];
+        let mut device = new_ads1119(&transactions);
+        device.write_config(0b1000_0000).unwrap();
+        device.send_command(0b0000_0001).unwrap();
+        assert_eq!(device.convert(0), None);
+        device.write_register(Register::Status, 0x12).unwrap();
+        assert!(device.read_register(Register::Status).is_err());
+        assert_eq!(device.stats().i2c_errors, 1);
+        destroy_ads1119(device);
+    }
+
+    #[test]
+    fn test_read_input_oneshot_with_retries() {
+        let input = InputSelection::AN2SingleEnded;
+        let failed_start = || {
+            [
+                test_support::expect_write_config(DEVICE_ADDRESS, input.bits()),
+                test_support::expect_start_sync(DEVICE_ADDRESS)
+                    .with_error(embedded_hal::i2c::ErrorKind::Other),
+            ]
+        };
+        let mut transactions = failed_start().to_vec();
+        transactions.extend(test_support::expect_oneshot_read(
+            DEVICE_ADDRESS,
+            &input,
+            42,
+        ));
+        transactions.extend(failed_start());
+        transactions.extend(failed_start());
+        let mut device = new_ads1119(&transactions);
+
+        assert_eq!(
+            device.read_input_oneshot_with_retries(&input, 1).unwrap(),
+            42
+        );
+        let error = device
+            .read_input_oneshot_with_retries(&input, 1)
+            .unwrap_err();
+        assert_eq!(error.attempts, 2);
+        assert!(matches!(error.last, Ads1119Err::I2CError { .. }));
+        assert_eq!(device.stats().retries, 2);
+        destroy_ads1119(device);
+    }
+
     #[test]
     fn test_read_input_oneshot_timeout() {
         let input = InputSelection::AN0SingleEnded;
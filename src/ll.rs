@@ -0,0 +1,109 @@
+//! Low-level layer: the commands and registers of the ADS1119 and ADS1219, without policy.
+//!
+//! [Ll] sends exactly the command it's asked to: it doesn't cache the config, wait, time
+//! out, retry or keep statistics. Build custom acquisition schemes on it without the
+//! high-level drivers' timeouts getting in the way, on a bus of its own, or borrowing a
+//! driver's with [Ads1119::ll].
+
+use embedded_hal::i2c::I2c;
+
+use crate::registers::StatusRegister;
+use crate::{family, Ads1119, Command, Register};
+
+/// Register-level access to a device of the family at an I2C address
+pub struct Ll<I2C> {
+    i2c: I2C,
+    address: u8,
+}
+
+impl<I2C> Ll<I2C>
+where
+    I2C: I2c,
+{
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Ll { i2c, address }
+    }
+
+    /// Get the bus back
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Send a command with no argument or response: [Command::Reset], [Command::StartSync]
+    /// or [Command::PowerDown]. See 8.5.3
+    pub fn command(&mut self, command: Command) -> Result<(), I2C::Error> {
+        family::command(&mut self.i2c, self.address, command)
+    }
+
+    /// See 8.5.3.6 RREG
+    pub fn read_register(&mut self, register: Register) -> Result<u8, I2C::Error> {
+        family::read_register(&mut self.i2c, self.address, register)
+    }
+
+    /// See 8.5.3.7 WREG. The datasheet only documents writing [Register::Config].
+    pub fn write_register(&mut self, register: Register, value: u8) -> Result<(), I2C::Error> {
+        family::write_register(&mut self.i2c, self.address, register, value)
+    }
+
+    /// Read the `N` bytes of the conversion result, MSB first: 2 for the ADS1119, 3 for the
+    /// ADS1219. See 8.5.3.5 RDATA
+    pub fn read_data<const N: usize>(&mut self) -> Result<[u8; N], I2C::Error> {
+        family::read_data(&mut self.i2c, self.address)
+    }
+
+    /// Check the status register once, see [StatusRegister::conversion_ready]
+    pub fn is_conversion_ready(&mut self) -> Result<bool, I2C::Error> {
+        Ok(StatusRegister::from_bits(self.read_register(Register::Status)?).conversion_ready())
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Low-level access to the device through the driver's bus.
+    ///
+    /// The driver can't tell what's done through it, so it forgets the config it last
+    /// wrote (see [Ads1119::convert]); nothing is counted in [Ads1119::stats].
+    pub fn ll(&mut self) -> Ll<&mut I2C> {
+        self.config = None;
+        Ll::new(&mut self.i2c, self.address)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{expect_read_config, expect_write_config};
+    use crate::test_support::{expect_read_data, expect_read_status, expect_start_sync};
+    use crate::STATUS_CONV_RDY;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn ll_sends_the_commands_as_is() {
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                expect_write_config(DEVICE_ADDRESS, 0x60),
+                expect_read_config(DEVICE_ADDRESS, 0x60),
+                expect_start_sync(DEVICE_ADDRESS),
+                expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+                expect_read_data(DEVICE_ADDRESS, -2),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        device.write_config(0x60).unwrap();
+        let mut ll = device.ll();
+        assert_eq!(ll.read_register(Register::Config).unwrap(), 0x60);
+        ll.command(Command::StartSync).unwrap();
+        assert!(ll.is_conversion_ready().unwrap());
+        assert_eq!(ll.read_data::<2>().unwrap(), [0xFF, 0xFE]);
+        assert_eq!(device.convert(0), None);
+        device.destroy().done();
+    }
+}
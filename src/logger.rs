@@ -0,0 +1,180 @@
+//! CSV logging of readings.
+//!
+//! Enabled with the `std` feature.
+
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use embedded_hal::i2c::I2c;
+
+use crate::{single_ended_rdata_to_scaled_voltage, Ads1119, Ads1119Err, InputSelection};
+
+/// A value logged for each reading, after the timestamp and input columns
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Column {
+    /// The raw ADC code
+    Raw,
+    /// The code converted to volts, see [single_ended_rdata_to_scaled_voltage]
+    Volts,
+    /// The voltage with the input's calibration and voltage divider applied,
+    /// like [Ads1119::read_input_voltage]
+    Calibrated,
+}
+
+impl Column {
+    fn header(&self) -> &'static str {
+        match self {
+            Column::Raw => "raw",
+            Column::Volts => "volts",
+            Column::Calibrated => "calibrated",
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LogError<I2CE> {
+    #[error("reading the device failed")]
+    Device(#[from] Ads1119Err<I2CE>),
+    #[error("writing the log failed")]
+    Io(#[from] io::Error),
+}
+
+/// Writes readings as CSV rows.
+///
+/// Each row starts with the timestamp, in seconds since the Unix epoch, and the input, followed
+/// by the configured columns. The header row is written before the first reading.
+pub struct CsvLogger<W> {
+    writer: W,
+    columns: Vec<Column>,
+    header_written: bool,
+}
+
+impl<W> CsvLogger<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W, columns: &[Column]) -> Self {
+        CsvLogger {
+            writer,
+            columns: columns.to_vec(),
+            header_written: false,
+        }
+    }
+
+    /// Destroy the logger and return its writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Read the given input with [Ads1119::read_input_oneshot] and log it, timestamped now
+    pub fn log_input<I2C>(
+        &mut self,
+        device: &mut Ads1119<I2C>,
+        input: &InputSelection,
+    ) -> Result<(), LogError<I2C::Error>>
+    where
+        I2C: I2c,
+    {
+        let raw = device.read_input_oneshot(input)?;
+        let volts = single_ended_rdata_to_scaled_voltage(raw);
+        let calibrated = device.apply_divider(input, device.apply_calibration(input, volts));
+        Ok(self.write_row(SystemTime::now(), input, raw, volts, calibrated)?)
+    }
+
+    /// Log a reading taken at the given time
+    pub fn write_row(
+        &mut self,
+        at: SystemTime,
+        input: &InputSelection,
+        raw: i16,
+        volts: f32,
+        calibrated: f32,
+    ) -> io::Result<()> {
+        if !self.header_written {
+            write!(self.writer, "timestamp,input")?;
+            for column in &self.columns {
+                write!(self.writer, ",{}", column.header())?;
+            }
+            writeln!(self.writer)?;
+            self.header_written = true;
+        }
+
+        // times before the epoch are clamped, they can only come from a misset clock
+        let timestamp = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+        write!(self.writer, "{:.3},{:?}", timestamp.as_secs_f64(), input)?;
+        for column in &self.columns {
+            match column {
+                Column::Raw => write!(self.writer, ",{}", raw)?,
+                Column::Volts => write!(self.writer, ",{}", volts)?,
+                Column::Calibrated => write!(self.writer, ",{}", calibrated)?,
+            }
+        }
+        writeln!(self.writer)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use std::time::Duration;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn writes_header_then_rows() {
+        let mut logger = CsvLogger::new(Vec::new(), &[Column::Raw, Column::Calibrated]);
+        let at = UNIX_EPOCH + Duration::from_millis(1_700_000_000_250);
+        logger
+            .write_row(at, &InputSelection::AN1SingleEnded, 100, 0.5, 1.0)
+            .unwrap();
+        logger
+            .write_row(at, &InputSelection::AN2SingleEnded, -3, 0.0, 0.25)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(logger.into_inner()).unwrap(),
+            "timestamp,input,raw,calibrated\n\
+             1700000000.250,AN1SingleEnded,100,1\n\
+             1700000000.250,AN2SingleEnded,-3,0.25\n"
+        );
+    }
+
+    #[test]
+    fn can_log_input() {
+        let input = InputSelection::AN0SingleEnded;
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                // half scale, 1.024V
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0x40, 0x00]),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        device.set_calibration(&input, 0.0, 0.5);
+        let mut logger = CsvLogger::new(Vec::new(), &[Column::Volts, Column::Calibrated]);
+        logger.log_input(&mut device, &input).unwrap();
+
+        let csv = String::from_utf8(logger.into_inner()).unwrap();
+        let fields: Vec<&str> = csv.lines().nth(1).unwrap().split(',').collect();
+        assert_eq!(fields[1], "AN0SingleEnded");
+        let volts: f32 = fields[2].parse().unwrap();
+        let calibrated: f32 = fields[3].parse().unwrap();
+        assert!((volts - 1.024).abs() < 0.001);
+        assert!((calibrated - volts * 0.5).abs() < 0.0001);
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,252 @@
+//! Rejection of mains hum (50 or 60 Hz), which high-impedance sensors and long leads pick up.
+//!
+//! Two ways, chosen with [MainsRejection]:
+//! - [MainsRejection::DigitalFilter]: at 20 SPS the device's digital filter has notches at
+//!   both 50 and 60 Hz, so a single conversion is free of hum. See 8.3.5 Digital Filter
+//! - [MainsRejection::Averaging]: at the other data rates, a continuous-mode run of
+//!   conversions spanning a whole number of mains cycles is averaged, so the hum averages
+//!   out. The run is the shortest one that fits, see [MainsWindow::for_rate].
+
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    single_ended_code_to_voltage, Ads1119, Ads1119Err, Config, ConversionMode, DataRate,
+    VoltageReference,
+};
+
+/// Frequency of the mains supply
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineFrequency {
+    Hz50,
+    Hz60,
+}
+
+impl LineFrequency {
+    pub fn hz(&self) -> u32 {
+        match self {
+            LineFrequency::Hz50 => 50,
+            LineFrequency::Hz60 => 60,
+        }
+    }
+}
+
+/// How [Ads1119::read_mains_rejected] suppresses hum
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MainsRejection {
+    /// Convert once at 20 SPS, whatever the data rate of the config
+    DigitalFilter,
+    /// Average conversions at the data rate of the config over whole cycles of this frequency
+    Averaging(LineFrequency),
+}
+
+/// A run of conversions lasting exactly a whole number of mains cycles
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MainsWindow {
+    pub samples: usize,
+    pub cycles: u32,
+}
+
+impl MainsWindow {
+    /// The shortest window at `data_rate`: `samples / sps == cycles / line_hz`, both whole.
+    ///
+    /// E.g. 20 conversions over 1 cycle at 1000 SPS and 50 Hz, or 50 conversions over 3
+    /// cycles at 1000 SPS and 60 Hz.
+    pub fn for_rate(data_rate: DataRate, line: LineFrequency) -> Self {
+        let sps = data_rate.sps();
+        let line_hz = line.hz();
+        let divisor = gcd(sps, line_hz);
+        MainsWindow {
+            samples: (sps / divisor) as usize,
+            cycles: line_hz / divisor,
+        }
+    }
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Result of [Ads1119::read_mains_rejected]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MainsRejectedReading {
+    /// Mean of the conversions, in raw ADC codes
+    pub mean: f32,
+    /// The conversions averaged, None for a single conversion with
+    /// [MainsRejection::DigitalFilter]
+    pub window: Option<MainsWindow>,
+    /// The config the conversions ran with
+    pub config: Config,
+}
+
+impl MainsRejectedReading {
+    /// The mean converted with the gain of the config. None with the external reference,
+    /// whose voltage the driver doesn't know (see [crate::ratiometric]).
+    pub fn volts(&self) -> Option<f32> {
+        match self.config.voltage_reference {
+            VoltageReference::Internal => {
+                Some(single_ended_code_to_voltage(self.mean) / self.config.gain.factor())
+            }
+            VoltageReference::External => None,
+        }
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Read the input of `config` with mains hum suppressed, see [crate::mains].
+    ///
+    /// With [MainsRejection::Averaging], the device runs in continuous mode for the
+    /// window, then the config is written back in single-shot mode, which stops it.
+    /// The same exclusive-access precondition as [Ads1119::read_input_oneshot] applies.
+    pub fn read_mains_rejected(
+        &mut self,
+        config: &Config,
+        rejection: MainsRejection,
+    ) -> Result<MainsRejectedReading, Ads1119Err<I2C::Error>> {
+        let config = config
+            .clone()
+            .with_conversion_mode(ConversionMode::SingleShot);
+        match rejection {
+            MainsRejection::DigitalFilter => {
+                let config = config.with_data_rate(DataRate::Sps20);
+                let raw = self.read_oneshot(config.bits(), None)?;
+                Ok(MainsRejectedReading {
+                    mean: raw.into(),
+                    window: None,
+                    config,
+                })
+            }
+            MainsRejection::Averaging(line) => {
+                let window = MainsWindow::for_rate(config.data_rate, line);
+                self.start_continuous(&config)?;
+                let sum = self.sum_samples(window.samples);
+                // stop the conversions even if a sample failed
+                let stopped = self.write_config(config.bits());
+                let sum = sum?;
+                stopped?;
+                Ok(MainsRejectedReading {
+                    mean: (sum as f64 / window.samples as f64) as f32,
+                    window: Some(window),
+                    config,
+                })
+            }
+        }
+    }
+
+    fn sum_samples(&mut self, n: usize) -> Result<i64, Ads1119Err<I2C::Error>> {
+        let mut sum = 0i64;
+        let mut samples = self.samples();
+        for _ in 0..n {
+            // the iterator only ends when cancelled
+            let sample = samples.next().ok_or(Ads1119Err::Cancelled)??;
+            sum += i64::from(sample);
+        }
+        Ok(sum)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{
+        expect_oneshot_read_config, expect_read_data, expect_read_status, expect_start_sync,
+        expect_write_config,
+    };
+    use crate::{rdata_to_voltage, Gain, InputSelection, STATUS_CONV_RDY};
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn windows_span_whole_cycles() {
+        let window = |rate, line| {
+            let window = MainsWindow::for_rate(rate, line);
+            (window.samples, window.cycles)
+        };
+        assert_eq!(window(DataRate::Sps1000, LineFrequency::Hz50), (20, 1));
+        assert_eq!(window(DataRate::Sps1000, LineFrequency::Hz60), (50, 3));
+        assert_eq!(window(DataRate::Sps330, LineFrequency::Hz50), (33, 5));
+        assert_eq!(window(DataRate::Sps330, LineFrequency::Hz60), (11, 2));
+        assert_eq!(window(DataRate::Sps90, LineFrequency::Hz50), (9, 5));
+        assert_eq!(window(DataRate::Sps20, LineFrequency::Hz60), (1, 3));
+    }
+
+    #[test]
+    fn averages_a_window_in_continuous_mode() {
+        let config = Config::new(InputSelection::AN1SingleEnded).with_data_rate(DataRate::Sps90);
+        let continuous = config
+            .clone()
+            .with_conversion_mode(ConversionMode::Continuous);
+        let mut transactions = vec![
+            expect_write_config(DEVICE_ADDRESS, continuous.bits()),
+            expect_start_sync(DEVICE_ADDRESS),
+        ];
+        // 90 SPS and 60 Hz: 3 conversions over 2 cycles
+        for value in [100, 200, 600] {
+            transactions.push(expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY));
+            transactions.push(expect_read_data(DEVICE_ADDRESS, value));
+        }
+        transactions.push(expect_write_config(DEVICE_ADDRESS, config.bits()));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let reading = device
+            .read_mains_rejected(&config, MainsRejection::Averaging(LineFrequency::Hz60))
+            .unwrap();
+        assert_eq!(reading.mean, 300.0);
+        assert_eq!(
+            reading.window,
+            Some(MainsWindow {
+                samples: 3,
+                cycles: 2
+            })
+        );
+        device.destroy().done();
+    }
+
+    #[test]
+    fn failed_sample_stops_the_conversions() {
+        let config = Config::new(InputSelection::AN1SingleEnded).with_data_rate(DataRate::Sps90);
+        let continuous = config
+            .clone()
+            .with_conversion_mode(ConversionMode::Continuous);
+        let transactions = [
+            expect_write_config(DEVICE_ADDRESS, continuous.bits()),
+            expect_start_sync(DEVICE_ADDRESS),
+            expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            expect_read_data(DEVICE_ADDRESS, 100).with_error(ErrorKind::Other),
+            // written back even though the reading failed
+            expect_write_config(DEVICE_ADDRESS, config.bits()),
+        ];
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        assert!(matches!(
+            device.read_mains_rejected(&config, MainsRejection::Averaging(LineFrequency::Hz60)),
+            Err(Ads1119Err::I2CError { .. })
+        ));
+        device.destroy().done();
+    }
+
+    #[test]
+    fn digital_filter_converts_once_at_20_sps() {
+        let config = Config::new(InputSelection::AN0SingleEnded)
+            .with_gain(Gain::Four)
+            .with_data_rate(DataRate::Sps1000);
+        let transactions = expect_oneshot_read_config(DEVICE_ADDRESS, 0b0111_0000, 0x4000);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let reading = device
+            .read_mains_rejected(&config, MainsRejection::DigitalFilter)
+            .unwrap();
+        assert_eq!(reading.config.data_rate, DataRate::Sps20);
+        let volts = reading.volts().unwrap();
+        assert!((volts - rdata_to_voltage(0x4000, Gain::Four)).abs() < 1e-6);
+        device.destroy().done();
+    }
+}
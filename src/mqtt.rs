@@ -0,0 +1,170 @@
+//! Publishing readings to an MQTT broker.
+//!
+//! Enabled with the `mqtt` feature.
+
+use std::time::{Duration, SystemTime};
+
+use embedded_hal::i2c::I2c;
+use rumqttc::{Client, ClientError, QoS};
+
+use crate::json::JsonRecord;
+use crate::scan::{ScanPlan, ScanReading};
+use crate::{Ads1119, Ads1119Err};
+
+#[derive(thiserror::Error, Debug)]
+pub enum PublishError<I2CE> {
+    #[error("reading the device failed")]
+    Device(#[from] Ads1119Err<I2CE>),
+    #[error("publishing failed")]
+    Mqtt(#[from] ClientError),
+    #[error("serializing the reading failed")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Publishes the readings of a [ScanPlan] at a fixed interval.
+///
+/// Each reading is published as a [JsonRecord] to `<topic>/<input>`,
+/// e.g. `sensors/adc/AN0SingleEnded`.
+///
+/// The publisher only queues messages on the [Client]; the `Connection` returned along with it
+/// must be polled (typically from its own thread) for them to reach the broker.
+pub struct MqttPublisher {
+    client: Client,
+    topic: String,
+    qos: QoS,
+    interval: Duration,
+}
+
+impl MqttPublisher {
+    /// Create a publisher under the given topic prefix, with QoS 1 and a 1s interval
+    pub fn new(client: Client, topic: impl Into<String>) -> Self {
+        MqttPublisher {
+            client,
+            topic: topic.into(),
+            qos: QoS::AtLeastOnce,
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    pub fn with_qos(mut self, qos: QoS) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Time from the start of one scan to the start of the next in [MqttPublisher::run]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Publish a reading taken at the given time
+    pub fn publish_reading<E>(
+        &self,
+        reading: &ScanReading,
+        at: SystemTime,
+    ) -> Result<(), PublishError<E>> {
+        let payload = serde_json::to_vec(&JsonRecord::from_scan_reading(reading, at))?;
+        let topic = format!("{}/{:?}", self.topic, reading.entry.input);
+        self.client.publish(topic, self.qos, false, payload)?;
+        Ok(())
+    }
+
+    /// Run the plan once with [Ads1119::run_scan] and publish its readings
+    pub fn publish_scan<I2C>(
+        &self,
+        device: &mut Ads1119<I2C>,
+        plan: &ScanPlan,
+    ) -> Result<(), PublishError<I2C::Error>>
+    where
+        I2C: I2c,
+    {
+        let readings = device.run_scan(plan)?;
+        let at = SystemTime::now();
+        for reading in &readings {
+            self.publish_reading(reading, at)?;
+        }
+        Ok(())
+    }
+
+    /// Publish a scan every interval, until an error occurs
+    pub fn run<I2C>(
+        &self,
+        device: &mut Ads1119<I2C>,
+        plan: &ScanPlan,
+    ) -> Result<(), PublishError<I2C::Error>>
+    where
+        I2C: I2c,
+    {
+        loop {
+            let started = device.now();
+            self.publish_scan(device, plan)?;
+            let elapsed = device.now().saturating_sub(started);
+            if let Some(remaining) = self.interval.checked_sub(elapsed) {
+                device.sleep(remaining);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scan::ScanEntry;
+    use crate::{CmdFlags, InputSelection, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use rumqttc::Request;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn publishes_each_reading_under_its_input() {
+        let plan = ScanPlan::new()
+            .with_entry(ScanEntry::new(InputSelection::AN0SingleEnded))
+            .with_entry(ScanEntry::new(InputSelection::AN3SingleEnded));
+        let mut transactions = vec![];
+        for (entry, value) in plan.entries().iter().zip([100i16, -100]) {
+            transactions.extend([
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, entry.config_bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RDATA],
+                    value.to_be_bytes().to_vec(),
+                ),
+            ]);
+        }
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let (tx, rx) = flume::unbounded();
+        let publisher = MqttPublisher::new(Client::from_sender(tx), "sensors/adc");
+        publisher.publish_scan(&mut device, &plan).unwrap();
+
+        let published: Vec<(String, i64)> = rx
+            .drain()
+            .map(|request| match request {
+                Request::Publish(publish) => {
+                    let record: serde_json::Value =
+                        serde_json::from_slice(&publish.payload).unwrap();
+                    (publish.topic, record["raw"].as_i64().unwrap())
+                }
+                other => panic!("unexpected request {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            published,
+            vec![
+                ("sensors/adc/AN0SingleEnded".to_string(), 100),
+                ("sensors/adc/AN3SingleEnded".to_string(), -100),
+            ]
+        );
+        device.destroy().done();
+    }
+}
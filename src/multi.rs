@@ -0,0 +1,254 @@
+//! Synchronized sampling across several devices.
+//!
+//! [capture_synchronized] starts a single-shot conversion on each device with START/SYNC
+//! commands sent back-to-back, then collects the results. The conversions start
+//! [SyncCapture::start_skew] apart, the time it takes to send the commands, so the
+//! readings are as close to simultaneous as one bus allows.
+//!
+//! The devices are drivers on the same bus, each with its own handle to it (e.g. from
+//! `embedded-hal-bus`). Their clocks may differ, so all the times of a capture are taken on
+//! the first device's [crate::clock::Clock], and the readings stamped with its timestamps.
+
+use embedded_hal::i2c::I2c;
+use std::time::Duration;
+
+use crate::clock::Timestamped;
+use crate::{Ads1119, Ads1119Err, Config, READ_INPUT_TIMEOUT, RESET_TIME};
+
+/// I2C general call address, which every device on the bus listens to
+pub const GENERAL_CALL_ADDRESS: u8 = 0x00;
+
+/// Second byte of a general call resetting the devices that support it, the ADS1119
+/// included. See 8.5.1.2 I2C General Call
+pub const GENERAL_CALL_RESET: u8 = 0x06;
+
+/// How [capture_synchronized] prepares and waits for the conversions
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncOptions {
+    /// Reset every device with a general call first, so they start from the same state
+    pub general_call_reset: bool,
+    /// How long to wait for each device's conversion, once they're all started
+    pub timeout: Duration,
+}
+
+impl SyncOptions {
+    pub fn new() -> Self {
+        SyncOptions {
+            general_call_reset: false,
+            timeout: READ_INPUT_TIMEOUT,
+        }
+    }
+
+    pub fn with_general_call_reset(mut self) -> Self {
+        self.general_call_reset = true;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        SyncOptions::new()
+    }
+}
+
+/// The result of one device in a [SyncCapture]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncReading {
+    /// I2C address of the device
+    pub address: u8,
+    /// When the START/SYNC command of this device was sent
+    pub started: Duration,
+    /// The conversion result and when it was read
+    pub reading: Timestamped<i16>,
+}
+
+/// The results of [capture_synchronized], in the order of the devices
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyncCapture {
+    pub readings: Vec<SyncReading>,
+}
+
+impl SyncCapture {
+    /// Time between the first and the last START/SYNC command, the bound on how far apart
+    /// the conversions of the devices started
+    pub fn start_skew(&self) -> Duration {
+        let first = self.readings.first().map(|reading| reading.started);
+        let last = self.readings.last().map(|reading| reading.started);
+        match (first, last) {
+            (Some(first), Some(last)) => last.saturating_sub(first),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Whether the conversions started at most `max_skew` apart
+    pub fn is_within(&self, max_skew: Duration) -> bool {
+        self.start_skew() <= max_skew
+    }
+
+    /// The conversion results, in the order of the devices
+    pub fn values(&self) -> impl Iterator<Item = i16> + '_ {
+        self.readings.iter().map(|reading| reading.reading.value)
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Reset every device on the bus that supports the I2C general call, this one included.
+    ///
+    /// The driver only knows its own device came back with the default config; drivers
+    /// of other devices on the bus still assume the config they last wrote.
+    pub fn general_call_reset(&mut self) -> Result<(), I2C::Error> {
+        self.config = None;
+        self.i2c
+            .write(GENERAL_CALL_ADDRESS, &[GENERAL_CALL_RESET])
+            .inspect_err(|_| self.stats.i2c_errors += 1)?;
+        self.config = Some(0);
+        Ok(())
+    }
+}
+
+/// Start a conversion with `config` on each of the devices back-to-back, then read the results.
+///
+/// The config is written to every device first (skipped where the driver knows it's
+/// already set), so that only the START/SYNC commands separate the conversions. With
+/// [SyncOptions::general_call_reset], the devices are reset together beforehand.
+///
+/// Fails with [Ads1119Err::NoSamples] if there's no device, or with the first error of a
+/// device; the conversions already started are then left running.
+pub fn capture_synchronized<I2C>(
+    devices: &mut [Ads1119<I2C>],
+    config: &Config,
+    options: &SyncOptions,
+) -> Result<SyncCapture, Ads1119Err<I2C::Error>>
+where
+    I2C: I2c,
+{
+    let Some((first, others)) = devices.split_first_mut() else {
+        return Err(Ads1119Err::NoSamples);
+    };
+    if options.general_call_reset {
+        first.general_call_reset()?;
+        for device in others.iter_mut() {
+            device.config = Some(0);
+        }
+        first.delay.delay_us(RESET_TIME.as_micros() as u32);
+    }
+
+    let bits = config.bits();
+    for device in devices.iter_mut() {
+        if device.config != Some(bits) {
+            device.write_config(bits)?;
+        }
+    }
+
+    let mut started = Vec::with_capacity(devices.len());
+    for index in 0..devices.len() {
+        devices[index].start_sync()?;
+        started.push(devices[0].now());
+    }
+
+    let mut readings = Vec::with_capacity(devices.len());
+    for (index, started) in started.into_iter().enumerate() {
+        let device = &mut devices[index];
+        device.wait_for_conversion(options.timeout)?;
+        let value = device.read_data()?;
+        readings.push(SyncReading {
+            address: device.address,
+            started,
+            reading: Timestamped {
+                timestamp: devices[0].timestamp(),
+                value,
+            },
+        });
+    }
+    Ok(SyncCapture { readings })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{
+        expect_read_data, expect_read_status, expect_start_sync, expect_write_config,
+    };
+    use crate::{InputSelection, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    fn device(address: u8, transactions: &[I2cTransaction]) -> Ads1119<I2cMock> {
+        Ads1119::new(I2cMock::new(transactions), address)
+    }
+
+    #[test]
+    fn capture_starts_every_device_before_reading() {
+        let config = Config::new(InputSelection::AN1SingleEnded);
+        let mut devices = [
+            device(
+                0x40,
+                &[
+                    I2cTransaction::write(GENERAL_CALL_ADDRESS, vec![GENERAL_CALL_RESET]),
+                    expect_write_config(0x40, config.bits()),
+                    expect_start_sync(0x40),
+                    expect_read_status(0x40, STATUS_CONV_RDY),
+                    expect_read_data(0x40, 100),
+                ],
+            ),
+            device(
+                0x41,
+                &[
+                    expect_write_config(0x41, config.bits()),
+                    expect_start_sync(0x41),
+                    expect_read_status(0x41, STATUS_CONV_RDY),
+                    expect_read_data(0x41, -200),
+                ],
+            ),
+        ];
+        let capture = capture_synchronized(
+            &mut devices,
+            &config,
+            &SyncOptions::new().with_general_call_reset(),
+        )
+        .unwrap();
+        assert_eq!(capture.values().collect::<Vec<_>>(), [100, -200]);
+        assert_eq!(capture.readings[1].address, 0x41);
+        assert!(capture.readings[1].started >= capture.readings[0].started);
+        assert!(capture.is_within(capture.start_skew()));
+        for device in devices {
+            device.destroy().done();
+        }
+    }
+
+    #[test]
+    fn capture_skips_known_configs() {
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        let mut device = device(
+            0x40,
+            &[
+                expect_start_sync(0x40),
+                expect_read_status(0x40, STATUS_CONV_RDY),
+                expect_read_data(0x40, 7),
+            ],
+        );
+        device.config = Some(config.bits());
+        let mut devices = [device];
+        let capture = capture_synchronized(&mut devices, &config, &SyncOptions::new()).unwrap();
+        assert_eq!(capture.start_skew(), Duration::ZERO);
+        let [device] = devices;
+        device.destroy().done();
+    }
+
+    #[test]
+    fn capture_needs_a_device() {
+        let mut devices: [Ads1119<I2cMock>; 0] = [];
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        assert!(matches!(
+            capture_synchronized(&mut devices, &config, &SyncOptions::new()),
+            Err(Ads1119Err::NoSamples)
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    statistics::Statistics, Ads1119, Ads1119Err, Config, ConversionMode, VoltageReference,
+    INTERNAL_REFERENCE_VOLTAGE,
+};
+
+/// Noise performance measured by [Ads1119::measure_noise].
+///
+/// The resolutions follow the definitions used by the noise tables of the datasheet.
+/// See 7.1 Noise Performance
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoiseReport {
+    /// Statistics of the captured samples, in raw ADC codes
+    pub statistics: Statistics,
+    /// RMS noise, in volts
+    pub rms_noise: f32,
+    /// Peak-to-peak noise, in volts
+    pub peak_to_peak_noise: f32,
+    /// Effective number of bits: log2(full-scale range / RMS noise), at most 16
+    pub effective_resolution: f32,
+    /// Noise-free bits: log2(full-scale range / peak-to-peak noise), at most 16
+    pub noise_free_resolution: f32,
+}
+
+impl NoiseReport {
+    /// Compute the report from samples captured with the given config, whose reference is
+    /// `reference_voltage` volts (2.048 V for the internal one)
+    pub fn from_statistics(
+        statistics: Statistics,
+        config: &Config,
+        reference_voltage: f32,
+    ) -> Self {
+        let full_scale_range = 2.0 * reference_voltage / config.gain.factor();
+        let lsb = full_scale_range / 65536.0;
+        let rms_noise = statistics.std_dev * lsb;
+        let peak_to_peak_noise = statistics.peak_to_peak() as f32 * lsb;
+
+        NoiseReport {
+            statistics,
+            rms_noise,
+            peak_to_peak_noise,
+            effective_resolution: resolution_bits(full_scale_range, rms_noise),
+            noise_free_resolution: resolution_bits(full_scale_range, peak_to_peak_noise),
+        }
+    }
+}
+
+// A noiseless capture would give infinite resolution, cap it at the converter's 16 bits
+fn resolution_bits(full_scale_range: f32, noise: f32) -> f32 {
+    if noise <= 0.0 {
+        return 16.0;
+    }
+    (full_scale_range / noise).log2().min(16.0)
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Capture `n` single-shot conversions with the given config and report the noise.
+    ///
+    /// The input should be shorted or held at a stable DC level (e.g.
+    /// [crate::InputSelection::ShortedToMidSupply]) so that all variation is noise.
+    /// The conversion mode of `config` is ignored.
+    /// Returns [Ads1119Err::NoSamples] if `n` is 0, or [Ads1119Err::UnknownReference] if
+    /// `config` selects the external reference, see [Ads1119::measure_noise_with_reference].
+    pub fn measure_noise(
+        &mut self,
+        config: &Config,
+        n: usize,
+    ) -> Result<NoiseReport, Ads1119Err<I2C::Error>> {
+        match config.voltage_reference {
+            VoltageReference::Internal => {
+                self.measure_noise_with_reference(config, n, INTERNAL_REFERENCE_VOLTAGE)
+            }
+            VoltageReference::External => Err(Ads1119Err::UnknownReference),
+        }
+    }
+
+    /// Same as [Ads1119::measure_noise], with the reference selected by `config` at
+    /// `reference_voltage` volts, e.g. an external one
+    pub fn measure_noise_with_reference(
+        &mut self,
+        config: &Config,
+        n: usize,
+        reference_voltage: f32,
+    ) -> Result<NoiseReport, Ads1119Err<I2C::Error>> {
+        let config = config
+            .clone()
+            .with_conversion_mode(ConversionMode::SingleShot);
+        let samples = self.read_samples(config.bits(), n)?;
+        let statistics = Statistics::from_samples(&samples).ok_or(Ads1119Err::NoSamples)?;
+        Ok(NoiseReport::from_statistics(
+            statistics,
+            &config,
+            reference_voltage,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Gain, InputSelection};
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    #[test]
+    fn noise_report_scales_with_gain() {
+        let statistics = Statistics::from_samples(&[-1, 1, -1, 1]).unwrap();
+        let config = Config::new(InputSelection::ShortedToMidSupply);
+
+        let report = NoiseReport::from_statistics(statistics, &config, INTERNAL_REFERENCE_VOLTAGE);
+        // one LSB of RMS noise over a 65536 LSB range
+        assert!((report.rms_noise - 62.5e-6).abs() < 1e-9);
+        assert!((report.effective_resolution - 16.0).abs() < 0.0001);
+        assert!((report.noise_free_resolution - 15.0).abs() < 0.0001);
+
+        let report = NoiseReport::from_statistics(
+            statistics,
+            &config.clone().with_gain(Gain::Four),
+            INTERNAL_REFERENCE_VOLTAGE,
+        );
+        assert!((report.rms_noise - 15.625e-6).abs() < 1e-9);
+        assert!((report.effective_resolution - 16.0).abs() < 0.0001);
+
+        // twice the reference, twice the LSB
+        let report = NoiseReport::from_statistics(statistics, &config, 4.096);
+        assert!((report.rms_noise - 125e-6).abs() < 1e-9);
+        assert!((report.effective_resolution - 16.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn external_reference_needs_its_voltage() {
+        let mut device = Ads1119::new(I2cMock::new(&[]), 0b0000_0000);
+        let config = Config::new(InputSelection::ShortedToMidSupply)
+            .with_voltage_reference(VoltageReference::External);
+        assert!(matches!(
+            device.measure_noise(&config, 4),
+            Err(Ads1119Err::UnknownReference)
+        ));
+        device.destroy().done();
+    }
+
+    #[test]
+    fn noiseless_capture_is_capped() {
+        let statistics = Statistics::from_samples(&[5, 5, 5]).unwrap();
+        let report = NoiseReport::from_statistics(
+            statistics,
+            &Config::new(InputSelection::AN0SingleEnded),
+            INTERNAL_REFERENCE_VOLTAGE,
+        );
+        assert_eq!(report.rms_noise, 0.0);
+        assert_eq!(report.effective_resolution, 16.0);
+        assert_eq!(report.noise_free_resolution, 16.0);
+    }
+}
@@ -0,0 +1,111 @@
+//! Scoped power management, so battery-powered applications can't forget to power the
+//! device down.
+//!
+//! Enabled with the `power-guard` feature.
+
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::i2c::I2c;
+
+use crate::Ads1119;
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Use the device until the returned guard goes out of scope, at which point it's
+    /// powered down (see [Ads1119::power_down]).
+    ///
+    /// The device wakes up on the first START/SYNC, as usual.
+    pub fn powered_session(&mut self) -> PoweredSession<'_, I2C> {
+        PoweredSession {
+            device: self,
+            finished: false,
+        }
+    }
+}
+
+/// Guard returned by [Ads1119::powered_session], giving access to the driver.
+///
+/// Dropping it powers the device down and ignores any error; use [PoweredSession::finish]
+/// to get the error instead.
+pub struct PoweredSession<'a, I2C>
+where
+    I2C: I2c,
+{
+    device: &'a mut Ads1119<I2C>,
+    finished: bool,
+}
+
+impl<I2C> PoweredSession<'_, I2C>
+where
+    I2C: I2c,
+{
+    /// End the session, powering the device down
+    pub fn finish(mut self) -> Result<(), I2C::Error> {
+        self.finished = true;
+        self.device.power_down()
+    }
+}
+
+impl<I2C> Deref for PoweredSession<'_, I2C>
+where
+    I2C: I2c,
+{
+    type Target = Ads1119<I2C>;
+
+    fn deref(&self) -> &Self::Target {
+        self.device
+    }
+}
+
+impl<I2C> DerefMut for PoweredSession<'_, I2C>
+where
+    I2C: I2c,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.device
+    }
+}
+
+impl<I2C> Drop for PoweredSession<'_, I2C>
+where
+    I2C: I2c,
+{
+    fn drop(&mut self) {
+        if !self.finished {
+            // nothing to report the error to
+            let _ = self.device.power_down();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{expect_oneshot_read, expect_power_down};
+    use crate::InputSelection;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn powers_down_at_end_of_scope() {
+        let input = InputSelection::AN0SingleEnded;
+        let mut transactions = expect_oneshot_read(DEVICE_ADDRESS, &input, 10);
+        transactions.push(expect_power_down(DEVICE_ADDRESS));
+        transactions.extend(expect_oneshot_read(DEVICE_ADDRESS, &input, 20));
+        transactions.push(expect_power_down(DEVICE_ADDRESS));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        {
+            let mut session = device.powered_session();
+            assert_eq!(session.read_input_oneshot(&input).unwrap(), 10);
+        }
+        let mut session = device.powered_session();
+        assert_eq!(session.read_input_oneshot(&input).unwrap(), 20);
+        session.finish().unwrap();
+
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,236 @@
+//! Named channel profiles, so the config, calibration and divider of each channel are
+//! registered once and read by name:
+//!
+//! ```ignore
+//! device.add_profile("battery", Profile::new(config).with_divider(VoltageDivider::new(100e3, 10e3)));
+//! let volts = device.read_profile("battery")?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use embedded_hal::i2c::I2c;
+
+use crate::calibration::Calibration;
+use crate::divider::VoltageDivider;
+use crate::filter::{ExponentialMovingAverage, Filter};
+use crate::ratiometric::rdata_to_ratio;
+use crate::{
+    Ads1119, Ads1119Err, Config, ConversionMode, VoltageReference, INTERNAL_REFERENCE_VOLTAGE,
+};
+
+/// How to read one channel, see [Ads1119::add_profile]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub config: Config,
+    /// Voltage of the external reference, for configs that select it
+    pub reference_voltage: Option<f32>,
+    pub calibration: Option<Calibration>,
+    pub divider: Option<VoltageDivider>,
+    /// Time to wait after writing the config and before starting the conversion
+    pub settle: Option<Duration>,
+    /// Smoothing of the voltages returned by [Ads1119::read_profile], with its state
+    pub smoothing: Option<ExponentialMovingAverage>,
+}
+
+impl Profile {
+    /// Profile reading with the given config, without calibration, divider or settle delay
+    pub fn new(config: Config) -> Self {
+        Profile {
+            config,
+            reference_voltage: None,
+            calibration: None,
+            divider: None,
+            settle: None,
+            smoothing: None,
+        }
+    }
+
+    /// Convert with an external reference of `volts`, for configs that select it
+    pub fn with_reference_voltage(mut self, volts: f32) -> Self {
+        self.reference_voltage = Some(volts);
+        self
+    }
+
+    pub fn with_calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    pub fn with_divider(mut self, divider: VoltageDivider) -> Self {
+        self.divider = Some(divider);
+        self
+    }
+
+    pub fn with_settle(mut self, settle: Duration) -> Self {
+        self.settle = Some(settle);
+        self
+    }
+
+    /// Smooth the voltages read with an [ExponentialMovingAverage] of the given alpha
+    pub fn with_smoothing(mut self, alpha: f32) -> Self {
+        self.smoothing = Some(ExponentialMovingAverage::new(alpha));
+        self
+    }
+
+    /// Convert a raw conversion result of this profile to volts: with the gain and the
+    /// reference of the config, then the calibration, then the divider.
+    ///
+    /// Returns None if the config selects the external reference and the profile doesn't
+    /// know its voltage (see [Profile::with_reference_voltage]).
+    pub fn to_volts(&self, raw: i16) -> Option<f32> {
+        let volts = rdata_to_ratio(raw, self.config.gain) * self.reference()?;
+        let volts = self
+            .calibration
+            .map_or(volts, |calibration| calibration.apply(volts));
+        Some(self.divider.map_or(volts, |divider| divider.apply(volts)))
+    }
+
+    /// The voltage of the reference selected by the config, if known
+    fn reference(&self) -> Option<f32> {
+        match self.config.voltage_reference {
+            VoltageReference::Internal => Some(INTERNAL_REFERENCE_VOLTAGE),
+            VoltageReference::External => self.reference_voltage,
+        }
+    }
+}
+
+/// The profiles registered with a driver, by name
+pub(crate) type Profiles = BTreeMap<String, Profile>;
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Register a profile under the given name, replacing any previous one
+    pub fn add_profile(&mut self, name: impl Into<String>, profile: Profile) {
+        self.profiles.insert(name.into(), profile);
+    }
+
+    /// Remove the profile of the given name, returning it
+    pub fn remove_profile(&mut self, name: &str) -> Option<Profile> {
+        self.profiles.remove(name)
+    }
+
+    /// The profile registered under the given name, if any
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.get(name)
+    }
+
+    /// Read the channel of the given profile with a single-shot conversion and return it
+    /// in volts, see [Profile::to_volts], smoothed if the profile has smoothing.
+    ///
+    /// Only the profile's calibration and divider are applied, not the ones registered for
+    /// the input with [Ads1119::set_calibration] and [Ads1119::set_divider].
+    /// The conversion mode of the profile's config is ignored. Returns
+    /// [Ads1119Err::UnknownProfile] if no profile has this name, or
+    /// [Ads1119Err::UnknownReference] if its config selects the external reference
+    /// without its voltage, before converting.
+    pub fn read_profile(&mut self, name: &str) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let profile = self.profiles.get(name).ok_or(Ads1119Err::UnknownProfile)?;
+        if profile.reference().is_none() {
+            return Err(Ads1119Err::UnknownReference);
+        }
+        let config = profile
+            .config
+            .clone()
+            .with_conversion_mode(ConversionMode::SingleShot);
+        let settle = profile.settle;
+        let raw = self.read_oneshot(config.bits(), settle)?;
+        let profile = self
+            .profiles
+            .get_mut(name)
+            .ok_or(Ads1119Err::UnknownProfile)?;
+        let volts = profile.to_volts(raw).ok_or(Ads1119Err::UnknownReference)?;
+        Ok(profile
+            .smoothing
+            .as_mut()
+            .map_or(volts, |filter| filter.update(volts)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_oneshot_read_config;
+    use crate::{DataRate, Gain, InputSelection, VoltageReference};
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn reads_profiles_by_name() {
+        let battery = Profile::new(
+            Config::new(InputSelection::AN0SingleEnded)
+                .with_data_rate(DataRate::Sps90)
+                .with_conversion_mode(ConversionMode::Continuous),
+        )
+        .with_calibration(Calibration::new(0.0, 1.5))
+        .with_divider(VoltageDivider::new(30e3, 10e3));
+        let bridge =
+            Profile::new(Config::new(InputSelection::AN1AN2Differential).with_gain(Gain::Four));
+        let mut transactions = expect_oneshot_read_config(DEVICE_ADDRESS, 0b0110_0100, 0x4000);
+        transactions.extend(expect_oneshot_read_config(
+            DEVICE_ADDRESS,
+            0b0101_0000,
+            -0x4000,
+        ));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.add_profile("battery", battery);
+        device.add_profile("bridge", bridge);
+
+        // 1.024 V, times 1.5 by the calibration and 4 by the divider
+        let volts = device.read_profile("battery").unwrap();
+        assert!((volts - 6.144).abs() < 1e-3);
+        let volts = device.read_profile("bridge").unwrap();
+        assert!((volts + 0.256).abs() < 1e-4);
+        assert!(matches!(
+            device.read_profile("missing"),
+            Err(Ads1119Err::UnknownProfile)
+        ));
+
+        assert!(device.remove_profile("bridge").is_some());
+        assert!(device.profile("bridge").is_none());
+        device.destroy().done();
+    }
+
+    #[test]
+    fn profile_smoothing_keeps_its_state() {
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        let mut transactions = expect_oneshot_read_config(DEVICE_ADDRESS, config.bits(), 0);
+        transactions.extend(expect_oneshot_read_config(
+            DEVICE_ADDRESS,
+            config.bits(),
+            0x7fff,
+        ));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.add_profile("smooth", Profile::new(config).with_smoothing(0.5));
+
+        assert_eq!(device.read_profile("smooth").unwrap(), 0.0);
+        // halfway to the 2.048 V full scale
+        let volts = device.read_profile("smooth").unwrap();
+        assert!((volts - 1.024).abs() < 1e-4);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn external_reference_needs_its_voltage() {
+        let config = Config::new(InputSelection::AN0SingleEnded)
+            .with_voltage_reference(VoltageReference::External);
+        let transactions = expect_oneshot_read_config(DEVICE_ADDRESS, config.bits(), 0x4000);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.add_profile("unknown", Profile::new(config.clone()));
+        device.add_profile("bridge", Profile::new(config).with_reference_voltage(5.0));
+
+        // refused before converting
+        assert!(matches!(
+            device.read_profile("unknown"),
+            Err(Ads1119Err::UnknownReference)
+        ));
+        assert_eq!(device.profile("unknown").unwrap().to_volts(0x4000), None);
+        // half of the 5 V reference
+        let volts = device.read_profile("bridge").unwrap();
+        assert!((volts - 2.5).abs() < 1e-3);
+        device.destroy().done();
+    }
+}
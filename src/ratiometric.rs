@@ -0,0 +1,73 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, Ads1119Err, Config, ConversionMode, Gain, VoltageReference};
+
+/// Interpret raw data converted against an external reference as a fraction of that reference.
+///
+/// The result is `V_in / V_ref`, from -1/gain to +1/gain. It doesn't depend on the actual
+/// reference voltage, which is what makes ratiometric measurements insensitive to supply drift.
+/// See 8.5.2 Data Format
+pub fn rdata_to_ratio(raw_data: i16, gain: Gain) -> f32 {
+    raw_data as f32 / 0x7FFF as f32 / gain.factor()
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Single-shot ratiometric read, for bridges and potentiometers excited from the same
+    /// supply as REFP.
+    ///
+    /// The voltage reference and conversion mode of `config` are overridden with
+    /// [VoltageReference::External] and [ConversionMode::SingleShot].
+    /// Returns the input as a fraction of the reference, see [rdata_to_ratio].
+    pub fn read_ratiometric(&mut self, config: &Config) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let config = config
+            .clone()
+            .with_voltage_reference(VoltageReference::External)
+            .with_conversion_mode(ConversionMode::SingleShot);
+        let raw = self.read_oneshot(config.bits(), None)?;
+        Ok(rdata_to_ratio(raw, config.gain))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, InputSelection, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+    const EPS: f32 = 0.0001;
+
+    #[test]
+    fn ratio_depends_on_gain() {
+        assert!((rdata_to_ratio(0x7FFF, Gain::One) - 1.0).abs() < EPS);
+        assert!((rdata_to_ratio(0x4000, Gain::One) - 0.5).abs() < EPS);
+        assert!((rdata_to_ratio(0x4000, Gain::Four) - 0.125).abs() < EPS);
+    }
+
+    #[test]
+    fn can_read_ratiometric() {
+        let config = Config::new(InputSelection::AN1SingleEnded);
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                // external reference bit is set
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, 0b1000_0001],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0x20, 0x00]),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        assert!((device.read_ratiometric(&config).unwrap() - 0.25).abs() < EPS);
+        device.destroy().done();
+    }
+}
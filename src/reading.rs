@@ -0,0 +1,271 @@
+//! A conversion result together with the configuration that produced it, so logging and
+//! telemetry don't have to keep track of which input, gain and reference each number was
+//! read with.
+
+use core::time::Duration;
+
+use crate::clipping::{Clipping, DEFAULT_CLIPPING_MARGIN};
+use crate::{rdata_to_voltage, Config, Gain, InputSelection, VoltageReference};
+
+/// A conversion result with its metadata, returned by [crate::Ads1119::read]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reading {
+    pub raw: i16,
+    /// The raw value converted with the gain it was read with, before calibration.
+    /// None with the external reference, whose voltage the driver doesn't know
+    /// (see [crate::ratiometric]).
+    pub volts: Option<f32>,
+    pub channel: InputSelection,
+    pub gain: Gain,
+    pub vref: VoltageReference,
+    /// When the result was read, see [crate::clock::TimestampSource]
+    pub timestamp: Duration,
+    /// Whether the result is at or near full scale, and so not trustworthy
+    pub clipping: Clipping,
+}
+
+impl Reading {
+    /// Reading of `raw`, converted with the given config, at `timestamp`.
+    /// Clipping is checked with [DEFAULT_CLIPPING_MARGIN].
+    pub fn new(config: &Config, raw: i16, timestamp: Duration) -> Self {
+        let volts = match config.voltage_reference {
+            VoltageReference::Internal => Some(rdata_to_voltage(raw, config.gain)),
+            VoltageReference::External => None,
+        };
+        Reading {
+            raw,
+            volts,
+            channel: config.input.clone(),
+            gain: config.gain,
+            vref: config.voltage_reference,
+            timestamp,
+            clipping: Clipping::of(raw, DEFAULT_CLIPPING_MARGIN),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I2C> crate::Ads1119<I2C>
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    /// Run a single-shot conversion with `config` and return it as a [Reading], timestamped
+    /// when the result was read and checked for clipping with the driver's margin
+    /// (see [crate::Ads1119::set_clipping_margin]).
+    ///
+    /// The conversion mode of `config` is ignored. The same exclusive-access precondition
+    /// as [crate::Ads1119::read_input_oneshot] applies.
+    pub fn read(&mut self, config: &Config) -> Result<Reading, crate::Ads1119Err<I2C::Error>> {
+        let config = config
+            .clone()
+            .with_conversion_mode(crate::ConversionMode::SingleShot);
+        let raw = self.read_oneshot(config.bits(), None)?;
+        let mut reading = Reading::new(&config, raw, self.timestamp());
+        reading.clipping = self.clipping(raw);
+        Ok(reading)
+    }
+
+    /// Read each of `inputs` in turn as a single-shot conversion, with the gain, data rate
+    /// and reference of the config last written by the driver (the defaults if it's unknown).
+    ///
+    /// The config register is only written when it changes, so repeating an input, or
+    /// starting with the input already selected, saves the write. The same exclusive-access
+    /// precondition as [crate::Ads1119::read_input_oneshot] applies. See
+    /// [crate::Ads1119::read_inputs_into] to read into a buffer instead.
+    pub fn read_inputs(
+        &mut self,
+        inputs: &[InputSelection],
+    ) -> Result<Vec<Reading>, crate::Ads1119Err<I2C::Error>> {
+        // overwritten by the readings
+        let placeholder = Reading::new(&Config::from_bits(0), 0, Duration::ZERO);
+        let mut readings = vec![placeholder; inputs.len()];
+        self.read_inputs_into(inputs, &mut readings)?;
+        Ok(readings)
+    }
+
+    /// Same as [crate::Ads1119::read_inputs_into_with_delay], waiting with the driver's
+    /// delay (see [crate::Ads1119::set_delay])
+    pub fn read_inputs_into(
+        &mut self,
+        inputs: &[InputSelection],
+        readings: &mut [Reading],
+    ) -> Result<(), crate::Ads1119Err<I2C::Error>> {
+        let mut delay = core::mem::replace(&mut self.delay, Box::new(crate::clock::StdDelay));
+        let result = self.read_inputs_into_with_delay(inputs, readings, &mut &mut *delay);
+        self.delay = delay;
+        result
+    }
+}
+
+impl<I2C> crate::Ads1119<I2C>
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    /// Same as [crate::Ads1119::read_inputs], without allocating: the reading of
+    /// `inputs[i]` goes to `readings[i]`, and the readings past the inputs are left as they
+    /// are. Waits between status checks with `delay`, so it's available without `std`.
+    ///
+    /// Panics if `readings` is shorter than `inputs`. If a read fails, the readings before
+    /// it are already written.
+    pub fn read_inputs_into_with_delay(
+        &mut self,
+        inputs: &[InputSelection],
+        readings: &mut [Reading],
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<(), crate::Ads1119Err<I2C::Error>> {
+        assert!(
+            readings.len() >= inputs.len(),
+            "there must be a reading for each input"
+        );
+        // 0 is the config register's reset value
+        let base = Config::from_bits(self.config.unwrap_or(0));
+        for (input, slot) in inputs.iter().zip(readings) {
+            let config = Config {
+                input: input.clone(),
+                conversion_mode: crate::ConversionMode::SingleShot,
+                ..base.clone()
+            };
+            if self.config != Some(config.bits()) {
+                self.write_config(config.bits())?;
+            }
+            self.start_sync()?;
+            self.wait_for_conversion_with_delay(crate::READ_INPUT_TIMEOUT, delay)?;
+            let raw = self.read_data()?;
+            let mut reading = Reading::new(&config, raw, self.timestamp());
+            reading.clipping = self.clipping(raw);
+            *slot = reading;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clock::TimestampSource;
+    use crate::test_support::{
+        expect_oneshot_read_config, expect_read_data, expect_read_status, expect_start_sync,
+        RecordingDelay,
+    };
+    use crate::{Ads1119, ConversionMode, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn reading_is_stamped_by_the_timestamp_source() {
+        struct VehicleTime;
+
+        impl TimestampSource for VehicleTime {
+            fn timestamp(&self) -> Duration {
+                Duration::from_secs(1_000_000)
+            }
+        }
+
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        let mut transactions = expect_oneshot_read_config(DEVICE_ADDRESS, config.bits(), 1);
+        transactions.extend(expect_oneshot_read_config(DEVICE_ADDRESS, config.bits(), 2));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        device.set_timestamp_source(&VehicleTime);
+        let reading = device.read(&config).unwrap();
+        assert_eq!(reading.timestamp, Duration::from_secs(1_000_000));
+        device.clear_timestamp_source();
+        let reading = device.read(&config).unwrap();
+        assert!(reading.timestamp < Duration::from_secs(1_000_000));
+        device.destroy().done();
+    }
+
+    #[test]
+    fn reading_carries_its_config() {
+        let config = Config::new(InputSelection::AN1AN2Differential)
+            .with_gain(Gain::Four)
+            .with_conversion_mode(ConversionMode::Continuous);
+        let single_shot = config
+            .clone()
+            .with_conversion_mode(ConversionMode::SingleShot);
+        let external = single_shot
+            .clone()
+            .with_voltage_reference(VoltageReference::External);
+        let mut transactions =
+            expect_oneshot_read_config(DEVICE_ADDRESS, single_shot.bits(), 0x7FFF);
+        transactions.extend(expect_oneshot_read_config(
+            DEVICE_ADDRESS,
+            external.bits(),
+            100,
+        ));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let reading = device.read(&config).unwrap();
+        assert_eq!(reading.raw, 0x7FFF);
+        assert_eq!(reading.volts, Some(0.512));
+        assert_eq!(reading.channel, InputSelection::AN1AN2Differential);
+        assert_eq!(reading.gain, Gain::Four);
+        assert_eq!(reading.vref, VoltageReference::Internal);
+        assert_eq!(reading.clipping, Clipping::Clipped);
+
+        let later = device.read(&external).unwrap();
+        assert_eq!(later.volts, None);
+        assert_eq!(later.vref, VoltageReference::External);
+        assert_eq!(later.clipping, Clipping::InRange);
+        assert!(later.timestamp >= reading.timestamp);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn read_inputs_writes_the_config_only_when_it_changes() {
+        let an0 = Config::new(InputSelection::AN0SingleEnded).with_gain(Gain::Four);
+        let an1 = Config::new(InputSelection::AN1SingleEnded).with_gain(Gain::Four);
+        let mut transactions = expect_oneshot_read_config(DEVICE_ADDRESS, an0.bits(), 10);
+        transactions.extend([
+            expect_start_sync(DEVICE_ADDRESS),
+            expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            expect_read_data(DEVICE_ADDRESS, 20),
+        ]);
+        transactions.extend(expect_oneshot_read_config(DEVICE_ADDRESS, an1.bits(), 30));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.config = Some(an1.bits());
+
+        let readings = device
+            .read_inputs(&[
+                InputSelection::AN0SingleEnded,
+                InputSelection::AN0SingleEnded,
+                InputSelection::AN1SingleEnded,
+            ])
+            .unwrap();
+        let summary: Vec<_> = readings
+            .iter()
+            .map(|reading| (reading.channel.clone(), reading.gain, reading.raw))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (InputSelection::AN0SingleEnded, Gain::Four, 10),
+                (InputSelection::AN0SingleEnded, Gain::Four, 20),
+                (InputSelection::AN1SingleEnded, Gain::Four, 30),
+            ]
+        );
+        device.destroy().done();
+    }
+
+    #[test]
+    fn read_inputs_into_a_buffer() {
+        let an2 = Config::new(InputSelection::AN2SingleEnded);
+        let transactions = expect_oneshot_read_config(DEVICE_ADDRESS, an2.bits(), 7);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let untouched = Reading::new(&an2, -1, Duration::ZERO);
+        let mut readings = [untouched.clone(), untouched.clone()];
+        device
+            .read_inputs_into_with_delay(
+                &[InputSelection::AN2SingleEnded],
+                &mut readings,
+                &mut RecordingDelay::default(),
+            )
+            .unwrap();
+        assert_eq!(readings[0].raw, 7);
+        assert_eq!(readings[1], untouched);
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,275 @@
+//! Redundant conversions guarding against transient glitches, e.g. for threshold decisions
+//! that matter for safety, or in radiation- or EMI-prone environments where a single read
+//! can be corrupted.
+
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, Ads1119Err, InputSelection};
+
+/// How many readings [Ads1119::read_input_consistent] needs to agree, and how closely
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Consistency {
+    /// Number of consecutive readings that must agree
+    pub count: usize,
+    /// Largest spread between the agreeing readings, in raw ADC codes
+    pub tolerance: u16,
+    /// Conversions to take at most before giving up
+    pub max_conversions: usize,
+}
+
+impl Consistency {
+    /// `count` consecutive readings at most `tolerance` codes apart, giving up after
+    /// 4 times as many conversions
+    pub fn new(count: usize, tolerance: u16) -> Self {
+        Consistency {
+            count,
+            tolerance,
+            max_conversions: count.saturating_mul(4),
+        }
+    }
+
+    pub fn with_max_conversions(mut self, max_conversions: usize) -> Self {
+        self.max_conversions = max_conversions;
+        self
+    }
+}
+
+/// Result of [Ads1119::read_input_consistent], in raw ADC codes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConsistentReading {
+    /// Mean of the agreeing readings
+    pub mean: f32,
+    pub min: i16,
+    pub max: i16,
+    /// Conversions taken in total, the rejected ones included
+    pub conversions: usize,
+}
+
+/// How the three readings of [Ads1119::read_input_voted] agreed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Agreement {
+    /// All three within the tolerance of each other
+    All,
+    /// Two within the tolerance; the reading at this index is off
+    Majority { outlier: usize },
+    /// Neither of the other readings is within the tolerance of the median
+    None,
+}
+
+/// Result of [Ads1119::read_input_voted], in raw ADC codes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VotedReading {
+    /// The median of the readings
+    pub value: i16,
+    /// The readings, in the order they were taken
+    pub readings: [i16; 3],
+    pub agreement: Agreement,
+}
+
+impl VotedReading {
+    /// Vote on three readings, which agree when at most `tolerance` codes apart
+    pub fn vote(readings: [i16; 3], tolerance: u16) -> Self {
+        let mut sorted = readings;
+        sorted.sort_unstable();
+        let [low, value, high] = sorted;
+        let agrees = |a: i16, b: i16| a.abs_diff(b) <= tolerance;
+        let agreement = match (agrees(low, value), agrees(value, high)) {
+            (true, true) if agrees(low, high) => Agreement::All,
+            (false, false) => Agreement::None,
+            // the one farther from the median is off
+            (low_agrees, high_agrees) => {
+                let off =
+                    if !low_agrees || (high_agrees && value.abs_diff(low) > high.abs_diff(value)) {
+                        low
+                    } else {
+                        high
+                    };
+                let outlier = readings.iter().rposition(|&reading| reading == off);
+                Agreement::Majority {
+                    outlier: outlier.unwrap_or_default(),
+                }
+            }
+        };
+        VotedReading {
+            value,
+            readings,
+            agreement,
+        }
+    }
+
+    /// Whether all three readings agreed
+    pub fn is_unanimous(&self) -> bool {
+        self.agreement == Agreement::All
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Take single-shot conversions of the given input until `consistency.count`
+    /// consecutive ones lie within `consistency.tolerance` of each other, so a transient
+    /// glitch can't decide the result. A reading outside the band starts a new run.
+    ///
+    /// The config is written once, like [Ads1119::read_input_averaged]. Returns
+    /// [Ads1119Err::NoSamples] if the count is 0, or [Ads1119Err::Inconsistent] if no run
+    /// agreed within `consistency.max_conversions`.
+    pub fn read_input_consistent(
+        &mut self,
+        input: &InputSelection,
+        consistency: &Consistency,
+    ) -> Result<ConsistentReading, Ads1119Err<I2C::Error>> {
+        if consistency.count == 0 {
+            return Err(Ads1119Err::NoSamples);
+        }
+
+        self.write_config(input.bits())?;
+
+        let mut run = Run::default();
+        for conversions in 1..=consistency.max_conversions {
+            let sample = self.convert_oneshot()?;
+            if !run.accepts(sample, consistency.tolerance) {
+                run = Run::default();
+            }
+            run.push(sample);
+            if run.len == consistency.count {
+                return Ok(ConsistentReading {
+                    mean: (run.sum as f64 / run.len as f64) as f32,
+                    min: run.min,
+                    max: run.max,
+                    conversions,
+                });
+            }
+        }
+        Err(Ads1119Err::Inconsistent {
+            required: consistency.count,
+            conversions: consistency.max_conversions,
+        })
+    }
+
+    /// Take three single-shot conversions of the given input and return their median,
+    /// with whether they agreed within `tolerance` codes, see [VotedReading::vote].
+    ///
+    /// A single corrupted reading can't change the result; the disagreement is only
+    /// flagged, for the caller to decide whether to trust the value. The config is
+    /// written once, like [Ads1119::read_input_averaged].
+    pub fn read_input_voted(
+        &mut self,
+        input: &InputSelection,
+        tolerance: u16,
+    ) -> Result<VotedReading, Ads1119Err<I2C::Error>> {
+        self.write_config(input.bits())?;
+        let readings = [
+            self.convert_oneshot()?,
+            self.convert_oneshot()?,
+            self.convert_oneshot()?,
+        ];
+        Ok(VotedReading::vote(readings, tolerance))
+    }
+}
+
+// consecutive readings within the tolerance
+#[derive(Default)]
+struct Run {
+    len: usize,
+    sum: i64,
+    min: i16,
+    max: i16,
+}
+
+impl Run {
+    fn accepts(&self, sample: i16, tolerance: u16) -> bool {
+        let min = self.min.min(sample);
+        let max = self.max.max(sample);
+        self.len == 0 || max.abs_diff(min) <= tolerance
+    }
+
+    fn push(&mut self, sample: i16) {
+        if self.len == 0 {
+            (self.min, self.max) = (sample, sample);
+        } else {
+            self.min = self.min.min(sample);
+            self.max = self.max.max(sample);
+        }
+        self.sum += i64::from(sample);
+        self.len += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_repeated_oneshot_reads;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn a_glitch_starts_a_new_run() {
+        let input = InputSelection::AN0SingleEnded;
+        let transactions = expect_repeated_oneshot_reads(
+            DEVICE_ADDRESS,
+            input.bits(),
+            &[100, 102, 900, 101, 99, 100],
+        );
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let reading = device
+            .read_input_consistent(&input, &Consistency::new(3, 2))
+            .unwrap();
+        // 900 breaks the first run, and 101 the one it started
+        assert_eq!(reading.mean, 100.0);
+        assert_eq!((reading.min, reading.max), (99, 101));
+        assert_eq!(reading.conversions, 6);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn gives_up_after_max_conversions() {
+        let input = InputSelection::AN1SingleEnded;
+        let transactions =
+            expect_repeated_oneshot_reads(DEVICE_ADDRESS, input.bits(), &[0, 100, 0, 100]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let consistency = Consistency::new(2, 10).with_max_conversions(4);
+        assert!(matches!(
+            device.read_input_consistent(&input, &consistency),
+            Err(Ads1119Err::Inconsistent {
+                required: 2,
+                conversions: 4
+            })
+        ));
+        device.destroy().done();
+    }
+
+    #[test]
+    fn votes_on_three_readings() {
+        let vote = |readings| VotedReading::vote(readings, 5);
+        assert_eq!(vote([100, 103, 98]).agreement, Agreement::All);
+        assert_eq!(
+            vote([100, -7000, 102]).agreement,
+            Agreement::Majority { outlier: 1 }
+        );
+        assert_eq!(
+            VotedReading::vote([100, 104, 101], 3).agreement,
+            Agreement::Majority { outlier: 1 }
+        );
+        assert_eq!(vote([0, 100, 200]).agreement, Agreement::None);
+        assert_eq!(vote([100, -7000, 102]).value, 100);
+    }
+
+    #[test]
+    fn voted_read_returns_the_median() {
+        let input = InputSelection::AN3SingleEnded;
+        let transactions =
+            expect_repeated_oneshot_reads(DEVICE_ADDRESS, input.bits(), &[500, 32767, 502]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let reading = device.read_input_voted(&input, 10).unwrap();
+        assert_eq!(reading.value, 502);
+        assert_eq!(reading.readings, [500, 32767, 502]);
+        assert_eq!(reading.agreement, Agreement::Majority { outlier: 1 });
+        assert!(!reading.is_unanimous());
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,220 @@
+//! Bit-level layouts of the config and status registers.
+//!
+//! [ConfigRegister] and [StatusRegister] are generated with `bitfield-struct`, so every field
+//! has a getter, a `with_*` builder and a `set_*` setter whose width is checked at compile time.
+//! [Config] and the field enums ([InputSelection::bits], [Gain::from_bits], ...) encode and
+//! decode through them instead of masking the raw byte by hand.
+//!
+//! See 8.6.2 Register Descriptions
+
+use bitfield_struct::bitfield;
+
+#[allow(unused_imports)] // used by the docs
+use crate::Config;
+use crate::{ConversionMode, DataRate, Gain, InputSelection, VoltageReference};
+
+/// Layout of the config register, fields from the least significant bit.
+/// See 8.6.2.1 Configuration Register
+#[bitfield(u8)]
+#[derive(PartialEq, Eq)]
+pub struct ConfigRegister {
+    /// VREF, bit 0
+    #[bits(1, from = VoltageReference::from_field, into = VoltageReference::into_field)]
+    pub voltage_reference: VoltageReference,
+    /// CM, bit 1
+    #[bits(1, from = ConversionMode::from_field, into = ConversionMode::into_field)]
+    pub conversion_mode: ConversionMode,
+    /// DR, bits 3:2
+    #[bits(2, from = DataRate::from_field, into = DataRate::into_field)]
+    pub data_rate: DataRate,
+    /// GAIN, bit 4
+    #[bits(1, from = Gain::from_field, into = Gain::into_field)]
+    pub gain: Gain,
+    /// MUX, bits 7:5
+    #[bits(3, from = InputSelection::from_field, into = InputSelection::into_field)]
+    pub input: InputSelection,
+}
+
+/// Layout of the status register.
+/// See 8.6.2.2 Status Register
+#[bitfield(u8)]
+#[derive(PartialEq, Eq)]
+pub struct StatusRegister {
+    /// Reserved
+    #[bits(7)]
+    __: u8,
+    /// DRDY, set when a new conversion result is ready, see [crate::STATUS_CONV_RDY]
+    pub conversion_ready: bool,
+}
+
+impl InputSelection {
+    /// Value of the 3-bit MUX field
+    pub(crate) const fn into_field(self) -> u8 {
+        match self {
+            InputSelection::AN0AN1Differential => 0,
+            InputSelection::AN2AN3Differential => 1,
+            InputSelection::AN1AN2Differential => 2,
+            InputSelection::AN0SingleEnded => 3,
+            InputSelection::AN1SingleEnded => 4,
+            InputSelection::AN2SingleEnded => 5,
+            InputSelection::AN3SingleEnded => 6,
+            InputSelection::ShortedToMidSupply => 7,
+        }
+    }
+
+    pub(crate) const fn from_field(field: u8) -> Self {
+        match field {
+            0 => InputSelection::AN0AN1Differential,
+            1 => InputSelection::AN2AN3Differential,
+            2 => InputSelection::AN1AN2Differential,
+            3 => InputSelection::AN0SingleEnded,
+            4 => InputSelection::AN1SingleEnded,
+            5 => InputSelection::AN2SingleEnded,
+            6 => InputSelection::AN3SingleEnded,
+            _ => InputSelection::ShortedToMidSupply,
+        }
+    }
+}
+
+impl Gain {
+    /// Value of the 1-bit GAIN field
+    pub(crate) const fn into_field(self) -> u8 {
+        match self {
+            Gain::One => 0,
+            Gain::Four => 1,
+        }
+    }
+
+    pub(crate) const fn from_field(field: u8) -> Self {
+        match field {
+            0 => Gain::One,
+            _ => Gain::Four,
+        }
+    }
+}
+
+impl DataRate {
+    /// Value of the 2-bit DR field
+    pub(crate) const fn into_field(self) -> u8 {
+        match self {
+            DataRate::Sps20 => 0,
+            DataRate::Sps90 => 1,
+            DataRate::Sps330 => 2,
+            DataRate::Sps1000 => 3,
+        }
+    }
+
+    pub(crate) const fn from_field(field: u8) -> Self {
+        match field {
+            0 => DataRate::Sps20,
+            1 => DataRate::Sps90,
+            2 => DataRate::Sps330,
+            _ => DataRate::Sps1000,
+        }
+    }
+}
+
+impl ConversionMode {
+    /// Value of the 1-bit CM field
+    pub(crate) const fn into_field(self) -> u8 {
+        match self {
+            ConversionMode::SingleShot => 0,
+            ConversionMode::Continuous => 1,
+        }
+    }
+
+    pub(crate) const fn from_field(field: u8) -> Self {
+        match field {
+            0 => ConversionMode::SingleShot,
+            _ => ConversionMode::Continuous,
+        }
+    }
+}
+
+impl VoltageReference {
+    /// Value of the 1-bit VREF field
+    pub(crate) const fn into_field(self) -> u8 {
+        match self {
+            VoltageReference::Internal => 0,
+            VoltageReference::External => 1,
+        }
+    }
+
+    pub(crate) const fn from_field(field: u8) -> Self {
+        match field {
+            0 => VoltageReference::Internal,
+            _ => VoltageReference::External,
+        }
+    }
+}
+
+impl From<&Config> for ConfigRegister {
+    fn from(config: &Config) -> Self {
+        ConfigRegister::new()
+            .with_input(config.input.clone())
+            .with_gain(config.gain)
+            .with_data_rate(config.data_rate)
+            .with_conversion_mode(config.conversion_mode)
+            .with_voltage_reference(config.voltage_reference)
+    }
+}
+
+impl From<ConfigRegister> for Config {
+    fn from(register: ConfigRegister) -> Self {
+        Config {
+            input: register.input(),
+            gain: register.gain(),
+            data_rate: register.data_rate(),
+            conversion_mode: register.conversion_mode(),
+            voltage_reference: register.voltage_reference(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::STATUS_CONV_RDY;
+
+    #[test]
+    fn config_register_field_positions() {
+        let register = ConfigRegister::new()
+            .with_input(InputSelection::AN1SingleEnded)
+            .with_gain(Gain::Four)
+            .with_data_rate(DataRate::Sps330)
+            .with_conversion_mode(ConversionMode::Continuous)
+            .with_voltage_reference(VoltageReference::External);
+        assert_eq!(register.into_bits(), 0b1001_1011);
+    }
+
+    #[test]
+    fn config_register_set_one_field() {
+        let mut register = ConfigRegister::from_bits(0xff);
+        register.set_data_rate(DataRate::Sps20);
+        assert_eq!(register.into_bits(), 0b1111_0011);
+        assert_eq!(register.input(), InputSelection::ShortedToMidSupply);
+    }
+
+    #[test]
+    fn config_register_round_trip() {
+        for bits in 0..=u8::MAX {
+            let register = ConfigRegister::from_bits(bits);
+            assert_eq!(
+                ConfigRegister::from(&Config::from(register)).into_bits(),
+                bits
+            );
+        }
+    }
+
+    #[test]
+    fn status_register_conversion_ready() {
+        assert!(StatusRegister::from_bits(STATUS_CONV_RDY).conversion_ready());
+        assert!(!StatusRegister::from_bits(!STATUS_CONV_RDY).conversion_ready());
+        assert_eq!(
+            StatusRegister::new()
+                .with_conversion_ready(true)
+                .into_bits(),
+            STATUS_CONV_RDY
+        );
+    }
+}
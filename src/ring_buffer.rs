@@ -0,0 +1,170 @@
+//! Allocation-free buffer of the latest readings, e.g. for "the last second of data" displays.
+//!
+//! Enabled with the `heapless` feature. It doesn't need `std`, so it can be filled on an MCU
+//! from [crate::continuous::Samples::timestamped], or on `std` from scan readings
+//! (see [crate::scan::ScanReading]).
+
+use core::time::Duration;
+
+use heapless::HistoryBuffer;
+
+use crate::clock::Timestamped;
+
+/// Min, max and mean of the readings of a [ReadingBuffer], in raw ADC codes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowStatistics {
+    pub min: i16,
+    pub max: i16,
+    pub mean: f32,
+    /// Number of readings
+    pub count: usize,
+}
+
+/// Ring buffer of the last `N` timestamped readings; the oldest is overwritten when it's full
+#[derive(Clone)]
+pub struct ReadingBuffer<const N: usize> {
+    readings: HistoryBuffer<Timestamped<i16>, N>,
+}
+
+impl<const N: usize> ReadingBuffer<N> {
+    pub fn new() -> Self {
+        ReadingBuffer {
+            readings: HistoryBuffer::new(),
+        }
+    }
+
+    pub fn push(&mut self, reading: Timestamped<i16>) {
+        self.readings.write(reading);
+    }
+
+    /// The most recent reading, or `None` if the buffer is empty
+    pub fn latest(&self) -> Option<Timestamped<i16>> {
+        self.readings.recent().copied()
+    }
+
+    /// Copy of the readings, oldest first
+    pub fn snapshot(&self) -> heapless::Vec<Timestamped<i16>, N> {
+        self.iter().collect()
+    }
+
+    /// The readings, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = Timestamped<i16>> + '_ {
+        self.readings.oldest_ordered().copied()
+    }
+
+    /// The readings at most `window` older than the latest one, oldest first
+    pub fn window(&self, window: Duration) -> impl Iterator<Item = Timestamped<i16>> + '_ {
+        let since = self
+            .latest()
+            .map(|latest| latest.timestamp.saturating_sub(window))
+            .unwrap_or_default();
+        self.iter()
+            .filter(move |reading| reading.timestamp >= since)
+    }
+
+    /// Statistics of the readings at most `window` older than the latest one,
+    /// or `None` if the buffer is empty
+    pub fn window_statistics(&self, window: Duration) -> Option<WindowStatistics> {
+        let mut readings = self.window(window);
+        let first = readings.next()?.value;
+        let mut statistics = WindowStatistics {
+            min: first,
+            max: first,
+            mean: 0.0,
+            count: 1,
+        };
+        let mut sum = first as i64;
+        for reading in readings {
+            statistics.min = statistics.min.min(reading.value);
+            statistics.max = statistics.max.max(reading.value);
+            statistics.count += 1;
+            sum += reading.value as i64;
+        }
+        statistics.mean = sum as f32 / statistics.count as f32;
+        Some(statistics)
+    }
+
+    /// Statistics of all the readings, or `None` if the buffer is empty
+    pub fn statistics(&self) -> Option<WindowStatistics> {
+        self.window_statistics(Duration::MAX)
+    }
+
+    /// Number of readings in the buffer, at most `N`
+    pub fn len(&self) -> usize {
+        self.readings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.readings.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.readings.clear();
+    }
+}
+
+impl<const N: usize> Default for ReadingBuffer<N> {
+    fn default() -> Self {
+        ReadingBuffer::new()
+    }
+}
+
+impl<const N: usize> Extend<Timestamped<i16>> for ReadingBuffer<N> {
+    fn extend<T: IntoIterator<Item = Timestamped<i16>>>(&mut self, iter: T) {
+        for reading in iter {
+            self.push(reading);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reading(millis: u64, value: i16) -> Timestamped<i16> {
+        Timestamped {
+            timestamp: Duration::from_millis(millis),
+            value,
+        }
+    }
+
+    #[test]
+    fn keeps_the_latest_readings() {
+        let mut buffer = ReadingBuffer::<3>::new();
+        assert_eq!(buffer.latest(), None);
+        assert_eq!(buffer.statistics(), None);
+
+        buffer.extend((0..5).map(|i| reading(i * 100, i as i16 * 10)));
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.latest(), Some(reading(400, 40)));
+        assert_eq!(
+            buffer.snapshot().as_slice(),
+            [reading(200, 20), reading(300, 30), reading(400, 40)]
+        );
+    }
+
+    #[test]
+    fn statistics_over_a_window() {
+        let mut buffer = ReadingBuffer::<8>::new();
+        buffer.extend([
+            reading(0, -50),
+            reading(500, 10),
+            reading(900, 20),
+            reading(1000, 60),
+        ]);
+
+        let statistics = buffer
+            .window_statistics(Duration::from_millis(500))
+            .unwrap();
+        assert_eq!(
+            statistics,
+            WindowStatistics {
+                min: 10,
+                max: 60,
+                mean: 30.0,
+                count: 3
+            }
+        );
+        assert_eq!(buffer.statistics().unwrap().min, -50);
+    }
+}
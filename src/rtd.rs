@@ -0,0 +1,153 @@
+//! Temperature from a platinum RTD (PT100/PT1000) in a ratiometric front-end.
+//!
+//! The RTD is read differentially, while the excitation current also flows through a precision
+//! reference resistor whose voltage drives REFP/REFN. The conversion result is then the ratio
+//! of the RTD resistance to the reference resistance, independent of the excitation current.
+
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, Ads1119Err, Config, Gain, InputSelection};
+
+/// Callendar–Van Dusen coefficients: `R(T) = R0 * (1 + A*T + B*T^2 + C*(T - 100)*T^3)`,
+/// with `C` only used below 0°C.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CallendarVanDusen {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl CallendarVanDusen {
+    /// Standard platinum coefficients (IEC 60751, alpha = 0.00385)
+    pub const IEC_60751: CallendarVanDusen = CallendarVanDusen {
+        a: 3.9083e-3,
+        b: -5.775e-7,
+        c: -4.183e-12,
+    };
+
+    /// Resistance ratio `R(T) / R0` at the given temperature, in °C
+    pub fn resistance_ratio(&self, temperature: f32) -> f32 {
+        let t = temperature;
+        let mut ratio = 1.0 + self.a * t + self.b * t * t;
+        if t < 0.0 {
+            ratio += self.c * (t - 100.0) * t * t * t;
+        }
+        ratio
+    }
+
+    /// Temperature, in °C, for the given resistance ratio `R / R0`
+    pub fn temperature(&self, resistance_ratio: f32) -> f32 {
+        // Above 0°C the equation is quadratic and can be solved directly
+        let quadratic = (-self.a
+            + (self.a * self.a - 4.0 * self.b * (1.0 - resistance_ratio)).sqrt())
+            / (2.0 * self.b);
+        if resistance_ratio >= 1.0 {
+            return quadratic;
+        }
+
+        // Below 0°C, refine the quadratic solution with Newton's method
+        let mut t = quadratic;
+        for _ in 0..10 {
+            let error = self.resistance_ratio(t) - resistance_ratio;
+            let slope = self.a + 2.0 * self.b * t + self.c * (4.0 * t * t * t - 300.0 * t * t);
+            t -= error / slope;
+        }
+        t
+    }
+}
+
+/// An RTD on a differential input of the ADS1119
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rtd {
+    /// Differential input the RTD is connected to
+    pub input: InputSelection,
+    /// Resistance at 0°C, in ohms
+    pub r0: f32,
+    /// Resistance of the reference resistor across REFP/REFN, in ohms.
+    /// It must be at least `gain` times the largest RTD resistance, or the reading clips.
+    pub reference_resistance: f32,
+    pub gain: Gain,
+    pub coefficients: CallendarVanDusen,
+}
+
+impl Rtd {
+    /// A PT100 with standard coefficients, read at gain 4
+    pub fn pt100(input: InputSelection, reference_resistance: f32) -> Self {
+        Rtd {
+            input,
+            r0: 100.0,
+            reference_resistance,
+            gain: Gain::Four,
+            coefficients: CallendarVanDusen::IEC_60751,
+        }
+    }
+
+    /// A PT1000 with standard coefficients, read at gain 1
+    pub fn pt1000(input: InputSelection, reference_resistance: f32) -> Self {
+        Rtd {
+            input,
+            r0: 1000.0,
+            reference_resistance,
+            gain: Gain::One,
+            coefficients: CallendarVanDusen::IEC_60751,
+        }
+    }
+
+    /// RTD resistance, in ohms, for a ratiometric reading (see [crate::ratiometric::rdata_to_ratio])
+    pub fn resistance(&self, ratio: f32) -> f32 {
+        ratio * self.reference_resistance
+    }
+
+    /// Temperature, in °C, for a ratiometric reading
+    pub fn temperature_from_ratio(&self, ratio: f32) -> f32 {
+        self.coefficients
+            .temperature(self.resistance(ratio) / self.r0)
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Read the RTD ratiometrically and convert it to a temperature, in °C.
+    ///
+    /// Returns [Ads1119Err::SensorOutOfRange] if the RTD reads as zero or negative resistance,
+    /// which usually means an open or shorted sensor.
+    pub fn read_rtd(&mut self, rtd: &Rtd) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let ratio = self.read_ratiometric(&Config::new(rtd.input.clone()).with_gain(rtd.gain))?;
+        if ratio <= 0.0 {
+            return Err(Ads1119Err::SensorOutOfRange);
+        }
+        Ok(rtd.temperature_from_ratio(ratio))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CVD: CallendarVanDusen = CallendarVanDusen::IEC_60751;
+
+    #[test]
+    fn cvd_reference_points() {
+        // PT100 table values
+        assert!((CVD.resistance_ratio(0.0) - 1.0).abs() < 1e-6);
+        assert!((CVD.resistance_ratio(100.0) - 1.385_055).abs() < 1e-5);
+        assert!((CVD.resistance_ratio(-100.0) - 0.602_56).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cvd_round_trips() {
+        for t in [-200.0, -40.0, -0.5, 0.0, 25.0, 100.0, 400.0] {
+            let back = CVD.temperature(CVD.resistance_ratio(t));
+            assert!((back - t).abs() < 0.01, "{t} came back as {back}");
+        }
+    }
+
+    #[test]
+    fn pt100_from_ratio() {
+        // 138.5055 ohms against a 1620 ohm reference is 100°C
+        let rtd = Rtd::pt100(InputSelection::AN0AN1Differential, 1620.0);
+        assert!((rtd.temperature_from_ratio(138.5055 / 1620.0) - 100.0).abs() < 0.01);
+    }
+}
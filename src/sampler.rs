@@ -0,0 +1,266 @@
+//! A background thread that owns the driver and delivers [Reading]s over a channel.
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use embedded_hal::i2c::I2c;
+
+use crate::reading::Reading;
+use crate::{Ads1119, Ads1119Err, Config};
+
+/// What [Ads1119::spawn_sampler] reads and how often
+#[derive(Clone, Debug, PartialEq)]
+pub struct SamplerConfig {
+    pub config: Config,
+    /// Time from the start of one reading to the start of the next
+    pub interval: Duration,
+    /// Number of failed readings in a row after which the sampler stops
+    pub max_consecutive_errors: u32,
+}
+
+impl SamplerConfig {
+    /// Read `config` every second, stopping after 3 failed readings in a row
+    pub fn new(config: Config) -> Self {
+        SamplerConfig {
+            config,
+            interval: Duration::from_secs(1),
+            max_consecutive_errors: 3,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_max_consecutive_errors(mut self, max_consecutive_errors: u32) -> Self {
+        self.max_consecutive_errors = max_consecutive_errors;
+        self
+    }
+}
+
+/// Commands handled by the sampler thread between readings
+#[derive(Clone, Debug, PartialEq)]
+pub enum SamplerCommand {
+    /// Read with this config from the next reading on
+    Reconfigure(Config),
+    /// Change the interval, from the next reading on
+    SetInterval(Duration),
+    Stop,
+}
+
+/// How the sampler thread ended, returned by [SamplerHandle::join] with the driver
+pub type SamplerResult<E> = Result<(), Ads1119Err<E>>;
+
+/// Handle of the thread started by [Ads1119::spawn_sampler].
+///
+/// Dropping the handle stops the sampler, and drops the driver with it.
+pub struct SamplerHandle<I2C>
+where
+    I2C: I2c,
+{
+    thread: JoinHandle<(Ads1119<I2C>, SamplerResult<I2C::Error>)>,
+    commands: Sender<SamplerCommand>,
+}
+
+impl<I2C> SamplerHandle<I2C>
+where
+    I2C: I2c,
+{
+    /// Send a command to the sampler; ignored if it has already stopped
+    pub fn send(&self, command: SamplerCommand) {
+        let _ = self.commands.send(command);
+    }
+
+    pub fn reconfigure(&self, config: Config) {
+        self.send(SamplerCommand::Reconfigure(config));
+    }
+
+    pub fn set_interval(&self, interval: Duration) {
+        self.send(SamplerCommand::SetInterval(interval));
+    }
+
+    /// Ask the sampler to stop; see [SamplerHandle::join] to wait for it
+    pub fn stop(&self) {
+        self.send(SamplerCommand::Stop);
+    }
+
+    /// Wait for the sampler to stop and get the driver back, along with the error that
+    /// stopped it if any.
+    ///
+    /// Panics if the sampler thread panicked.
+    pub fn join(self) -> (Ads1119<I2C>, SamplerResult<I2C::Error>) {
+        self.thread.join().expect("sampler thread panicked")
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c + Send + 'static,
+    I2C::Error: Send,
+{
+    /// Move the driver to a new thread that reads with [Ads1119::read] every interval and
+    /// sends the readings on the returned channel.
+    ///
+    /// A failed reading is skipped; the sampler stops after
+    /// [SamplerConfig::max_consecutive_errors] of them in a row, when a
+    /// [SamplerCommand::Stop] is received, or when the receiver or the handle is dropped.
+    /// Commands are handled as they arrive, between readings.
+    pub fn spawn_sampler(self, config: SamplerConfig) -> (SamplerHandle<I2C>, Receiver<Reading>) {
+        let (readings, receiver) = mpsc::channel();
+        let (commands, command_receiver) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let mut device = self;
+            let result = device.run_sampler(config, &readings, &command_receiver);
+            (device, result)
+        });
+        (SamplerHandle { thread, commands }, receiver)
+    }
+
+    fn run_sampler(
+        &mut self,
+        mut config: SamplerConfig,
+        readings: &Sender<Reading>,
+        commands: &Receiver<SamplerCommand>,
+    ) -> SamplerResult<I2C::Error> {
+        let mut errors = 0;
+        let mut next = self.now();
+        loop {
+            match self.read(&config.config) {
+                Ok(reading) => {
+                    errors = 0;
+                    if readings.send(reading).is_err() {
+                        return Ok(());
+                    }
+                }
+                Err(err) => {
+                    errors += 1;
+                    if errors >= config.max_consecutive_errors {
+                        return Err(err);
+                    }
+                }
+            }
+
+            // a reading slower than the interval delays the next one instead of leaving a
+            // backlog to catch up on in a burst
+            next = (next + config.interval).max(self.now());
+            loop {
+                match commands.recv_timeout(next.saturating_sub(self.now())) {
+                    Ok(SamplerCommand::Reconfigure(new)) => config.config = new,
+                    Ok(SamplerCommand::SetInterval(interval)) => {
+                        next = next - config.interval + interval;
+                        config.interval = interval;
+                    }
+                    Ok(SamplerCommand::Stop) | Err(RecvTimeoutError::Disconnected) => return Ok(()),
+                    Err(RecvTimeoutError::Timeout) => break,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clock::Clock;
+    use crate::test_support::{expect_oneshot_read, expect_write_config};
+    use crate::InputSelection;
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn delivers_readings_and_follows_reconfiguration() {
+        let mut transactions =
+            expect_oneshot_read(DEVICE_ADDRESS, &InputSelection::AN0SingleEnded, 100);
+        transactions.extend(expect_oneshot_read(
+            DEVICE_ADDRESS,
+            &InputSelection::AN1SingleEnded,
+            -100,
+        ));
+        let device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let config = SamplerConfig::new(Config::new(InputSelection::AN0SingleEnded))
+            .with_interval(Duration::from_millis(100));
+        let (handle, readings) = device.spawn_sampler(config);
+        let reading = readings.recv().unwrap();
+        assert_eq!(
+            (reading.channel, reading.raw),
+            (InputSelection::AN0SingleEnded, 100)
+        );
+
+        handle.reconfigure(Config::new(InputSelection::AN1SingleEnded));
+        let reading = readings.recv().unwrap();
+        assert_eq!(
+            (reading.channel, reading.raw),
+            (InputSelection::AN1SingleEnded, -100)
+        );
+
+        handle.stop();
+        let (device, result) = handle.join();
+        assert!(result.is_ok());
+        device.destroy().done();
+    }
+
+    #[test]
+    fn slow_reading_does_not_cause_a_burst() {
+        // the first reading takes 25 s, then the clock stands still
+        struct SlowReadClock(AtomicBool);
+
+        impl Clock for SlowReadClock {
+            fn now(&self) -> Duration {
+                if self.0.swap(true, Ordering::Relaxed) {
+                    Duration::from_secs(25)
+                } else {
+                    Duration::ZERO
+                }
+            }
+        }
+
+        static CLOCK: SlowReadClock = SlowReadClock(AtomicBool::new(false));
+
+        let input = InputSelection::AN0SingleEnded;
+        let mut transactions = expect_oneshot_read(DEVICE_ADDRESS, &input, 1);
+        transactions.extend(expect_oneshot_read(DEVICE_ADDRESS, &input, 2));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.set_clock(&CLOCK);
+
+        // two readings are overdue after the slow one: only the late one is taken right away
+        let config = SamplerConfig::new(Config::new(input)).with_interval(Duration::from_secs(10));
+        let (handle, readings) = device.spawn_sampler(config);
+        assert_eq!(readings.recv().unwrap().raw, 1);
+        assert_eq!(readings.recv().unwrap().raw, 2);
+        assert_eq!(
+            readings.recv_timeout(Duration::from_millis(100)),
+            Err(RecvTimeoutError::Timeout)
+        );
+
+        handle.stop();
+        let (device, result) = handle.join();
+        assert!(result.is_ok());
+        device.destroy().done();
+    }
+
+    #[test]
+    fn stops_after_consecutive_errors() {
+        let config = Config::new(InputSelection::AN0SingleEnded);
+        let transactions = [
+            expect_write_config(DEVICE_ADDRESS, config.bits()).with_error(ErrorKind::Other),
+            expect_write_config(DEVICE_ADDRESS, config.bits()).with_error(ErrorKind::Other),
+        ];
+        let device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let config = SamplerConfig::new(config)
+            .with_interval(Duration::ZERO)
+            .with_max_consecutive_errors(2);
+        let (handle, readings) = device.spawn_sampler(config);
+        assert!(readings.recv().is_err());
+
+        let (device, result) = handle.join();
+        assert!(matches!(result, Err(Ads1119Err::I2CError { .. })));
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,324 @@
+use embedded_hal::i2c::I2c;
+use std::time::Duration;
+
+use crate::clock::Timestamped;
+use crate::{Ads1119, Ads1119Err, DataRate, Gain, InputSelection};
+
+/// A single step of a [ScanPlan]: which input to read and how to configure the device for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScanEntry {
+    pub input: InputSelection,
+    pub gain: Gain,
+    pub data_rate: DataRate,
+    /// Time to wait after writing the config and before starting the conversion
+    pub settle: Option<Duration>,
+}
+
+impl ScanEntry {
+    /// Create an entry for the given input using the default gain and data rate, with no settle delay
+    pub fn new(input: InputSelection) -> Self {
+        ScanEntry {
+            input,
+            gain: Gain::default(),
+            data_rate: DataRate::default(),
+            settle: None,
+        }
+    }
+
+    pub fn with_gain(mut self, gain: Gain) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn with_data_rate(mut self, data_rate: DataRate) -> Self {
+        self.data_rate = data_rate;
+        self
+    }
+
+    pub fn with_settle(mut self, settle: Duration) -> Self {
+        self.settle = Some(settle);
+        self
+    }
+
+    /// The config register value used for this entry (single-shot mode, internal reference)
+    pub fn config_bits(&self) -> u8 {
+        self.input.bits() | self.gain.bits() | self.data_rate.bits()
+    }
+}
+
+/// An ordered list of [ScanEntry]s, run with [Ads1119::run_scan].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScanPlan {
+    entries: Vec<ScanEntry>,
+}
+
+impl ScanPlan {
+    pub fn new() -> Self {
+        ScanPlan::default()
+    }
+
+    /// Append an entry to the end of the plan
+    pub fn with_entry(mut self, entry: ScanEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    pub fn push(&mut self, entry: ScanEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[ScanEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The result of one [ScanEntry], tagged with its position in the plan.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScanReading {
+    /// Index of the entry in the [ScanPlan]
+    pub index: usize,
+    /// Sequence number of the reading among the driver's results, see [crate::sequence]
+    pub sequence: u64,
+    pub entry: ScanEntry,
+    pub raw: i16,
+    /// When the result was read, see [crate::clock::TimestampSource]
+    pub timestamp: Duration,
+}
+
+impl From<&ScanReading> for Timestamped<i16> {
+    fn from(reading: &ScanReading) -> Self {
+        Timestamped {
+            timestamp: reading.timestamp,
+            value: reading.raw,
+        }
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Run every entry of the plan in order, each as a single-shot conversion.
+    ///
+    /// The same exclusive-access precondition as [Ads1119::read_input_oneshot] applies.
+    /// The scan stops at the first error.
+    pub fn run_scan(
+        &mut self,
+        plan: &ScanPlan,
+    ) -> Result<Vec<ScanReading>, Ads1119Err<I2C::Error>> {
+        let mut readings = Vec::with_capacity(plan.len());
+        for (index, entry) in plan.entries().iter().enumerate() {
+            let raw = self.read_oneshot(entry.config_bits(), entry.settle)?;
+            readings.push(ScanReading {
+                index,
+                sequence: self.next_sequence(0),
+                entry: entry.clone(),
+                raw,
+                timestamp: self.timestamp(),
+            });
+        }
+        Ok(readings)
+    }
+
+    /// Run every entry of the plan in order like [Ads1119::run_scan], starting the conversion
+    /// of the next entry as soon as a result is read, before returning it.
+    ///
+    /// The next conversion then runs while the caller handles the reading, so each entry's
+    /// processing time (up to a conversion time) comes off the total scan time; a scan
+    /// that only collects the readings isn't faster. The iterator ends after the first error.
+    pub fn pipelined_scan<'a>(&'a mut self, plan: &'a ScanPlan) -> PipelinedScan<'a, I2C> {
+        PipelinedScan {
+            device: self,
+            plan,
+            next: 0,
+            started: false,
+            failed: None,
+        }
+    }
+}
+
+/// Iterator of the readings of a [ScanPlan], see [Ads1119::pipelined_scan]
+pub struct PipelinedScan<'a, I2C>
+where
+    I2C: I2c,
+{
+    device: &'a mut Ads1119<I2C>,
+    plan: &'a ScanPlan,
+    // index of the entry converting or to convert next
+    next: usize,
+    started: bool,
+    // error starting the next conversion, returned after the reading it came with
+    failed: Option<Ads1119Err<I2C::Error>>,
+}
+
+impl<I2C> PipelinedScan<'_, I2C>
+where
+    I2C: I2c,
+{
+    fn start(&mut self) -> Result<(), Ads1119Err<I2C::Error>> {
+        let entry = &self.plan.entries()[self.next];
+        self.device.write_config(entry.config_bits())?;
+        if let Some(settle) = entry.settle {
+            self.device.sleep(settle);
+        }
+        self.device.start_sync()?;
+        self.started = true;
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<ScanReading, Ads1119Err<I2C::Error>> {
+        if !self.started {
+            self.start()?;
+        }
+        self.device.wait_conversion_ready()?;
+        let raw = self.device.read_data()?;
+        let reading = ScanReading {
+            index: self.next,
+            sequence: self.device.next_sequence(0),
+            entry: self.plan.entries()[self.next].clone(),
+            raw,
+            timestamp: self.device.timestamp(),
+        };
+        self.next += 1;
+        self.started = false;
+        if self.next < self.plan.len() {
+            self.failed = self.start().err();
+        }
+        Ok(reading)
+    }
+}
+
+impl<I2C> Iterator for PipelinedScan<'_, I2C>
+where
+    I2C: I2c,
+{
+    type Item = Result<ScanReading, Ads1119Err<I2C::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.failed.take() {
+            self.next = self.plan.len();
+            return Some(Err(err));
+        }
+        if self.next >= self.plan.len() {
+            return None;
+        }
+        let reading = self.read();
+        if reading.is_err() {
+            self.next = self.plan.len();
+        }
+        Some(reading)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{
+        expect_oneshot_read_config, expect_read_data, expect_read_status, expect_start_sync,
+        expect_write_config, RecordingDelay,
+    };
+    use crate::wait::BusyPoll;
+    use crate::STATUS_CONV_RDY;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    fn oneshot_transactions(config: u8, value: i16) -> Vec<I2cTransaction> {
+        expect_oneshot_read_config(DEVICE_ADDRESS, config, value)
+    }
+
+    #[test]
+    fn config_bits_combines_fields() {
+        let entry = ScanEntry::new(InputSelection::AN2SingleEnded)
+            .with_gain(Gain::Four)
+            .with_data_rate(DataRate::Sps330);
+        assert_eq!(entry.config_bits(), 0b1011_1000);
+    }
+
+    #[test]
+    fn can_run_scan() {
+        let plan = ScanPlan::new()
+            .with_entry(ScanEntry::new(InputSelection::AN0SingleEnded))
+            .with_entry(
+                ScanEntry::new(InputSelection::AN3SingleEnded)
+                    .with_gain(Gain::Four)
+                    .with_data_rate(DataRate::Sps1000)
+                    .with_settle(Duration::from_millis(1)),
+            );
+        let mut transactions = oneshot_transactions(plan.entries()[0].config_bits(), 100);
+        transactions.extend(oneshot_transactions(plan.entries()[1].config_bits(), -5));
+
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        let delay = RecordingDelay::default();
+        device.set_delay(delay.clone());
+        device.set_wait_strategy(BusyPoll);
+        let readings = device.run_scan(&plan).unwrap();
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[0].index, 0);
+        assert_eq!(readings[0].raw, 100);
+        assert_eq!(readings[1].index, 1);
+        assert_eq!(readings[1].entry.gain, Gain::Four);
+        assert_eq!(readings[1].raw, -5);
+        // the settle delay of the second entry
+        assert_eq!(delay.delays(), [1_000_000]);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn scan_readings_are_numbered_across_scans() {
+        let plan = ScanPlan::new()
+            .with_entry(ScanEntry::new(InputSelection::AN0SingleEnded))
+            .with_entry(ScanEntry::new(InputSelection::AN1SingleEnded));
+        let mut transactions = Vec::new();
+        for _ in 0..2 {
+            for entry in plan.entries() {
+                transactions.extend(oneshot_transactions(entry.config_bits(), 0));
+            }
+        }
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        let mut detector = crate::sequence::GapDetector::new();
+        let mut sequences = Vec::new();
+        for _ in 0..2 {
+            for reading in device.run_scan(&plan).unwrap() {
+                assert_eq!(detector.check(reading.sequence), None);
+                sequences.push(reading.sequence);
+            }
+        }
+        assert_eq!(sequences, [0, 1, 2, 3]);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn pipelined_scan_starts_the_next_conversion_before_returning() {
+        let plan = ScanPlan::new()
+            .with_entry(ScanEntry::new(InputSelection::AN0SingleEnded))
+            .with_entry(ScanEntry::new(InputSelection::AN1SingleEnded).with_gain(Gain::Four));
+        let [first, second] = [0, 1].map(|index| plan.entries()[index].config_bits());
+        let i2c = I2cMock::new(&[
+            expect_write_config(DEVICE_ADDRESS, first),
+            expect_start_sync(DEVICE_ADDRESS),
+            expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            expect_read_data(DEVICE_ADDRESS, 10),
+            expect_write_config(DEVICE_ADDRESS, second),
+            expect_start_sync(DEVICE_ADDRESS),
+            expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            expect_read_data(DEVICE_ADDRESS, 20),
+        ]);
+        let mut device = Ads1119::new(i2c, DEVICE_ADDRESS);
+
+        let mut scan = device.pipelined_scan(&plan);
+        assert_eq!(scan.next().unwrap().unwrap().raw, 10);
+        let reading = scan.next().unwrap().unwrap();
+        assert_eq!((reading.index, reading.raw), (1, 20));
+        assert!(scan.next().is_none());
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,188 @@
+//! Config scrubbing: reading the config register back and rewriting it when it no longer
+//! holds the config the driver wrote, e.g. after a brownout reset the device or an upset
+//! flipped a bit.
+//!
+//! Call [Ads1119::scrub_config] before each scan, or let a [ConfigScrubber] do it on a
+//! schedule. Each rewrite is counted in [crate::stats::DriverStats::config_scrubs].
+
+use std::time::Duration;
+
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, Ads1119Err};
+
+/// A config register found not holding the config written, and rewritten
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrubEvent {
+    /// The config last written by the driver
+    pub expected: u8,
+    /// What the config register held instead
+    pub found: u8,
+    /// When it was found, see [crate::clock::TimestampSource]
+    pub timestamp: Duration,
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Read the config register and, if it doesn't hold the config last written by the
+    /// driver, write that again with [Ads1119::write_config_verified].
+    ///
+    /// Returns the event if the config was rewritten, and None if it matched or the driver
+    /// doesn't know the config (e.g. before the first write or after a failed one).
+    pub fn scrub_config(&mut self) -> Result<Option<ScrubEvent>, Ads1119Err<I2C::Error>> {
+        let Some(expected) = self.config else {
+            return Ok(None);
+        };
+        let found = self.read_config()?;
+        if found == expected {
+            return Ok(None);
+        }
+        let event = ScrubEvent {
+            expected,
+            found,
+            timestamp: self.timestamp(),
+        };
+        self.stats.config_scrubs += 1;
+        self.write_config_verified(expected)?;
+        Ok(Some(event))
+    }
+}
+
+/// Callback invoked for each [ScrubEvent], see [ConfigScrubber::set_callback]
+pub type ScrubCallback = Box<dyn FnMut(&ScrubEvent) + Send>;
+
+/// Scrubs the config at a fixed interval, for a loop to poll between readings
+pub struct ConfigScrubber {
+    interval: Duration,
+    // when the config was last scrubbed, on the driver's clock
+    last: Option<Duration>,
+    callback: Option<ScrubCallback>,
+}
+
+impl ConfigScrubber {
+    /// Scrub at most once per `interval`, the first time when first polled
+    pub fn new(interval: Duration) -> Self {
+        ConfigScrubber {
+            interval,
+            last: None,
+            callback: None,
+        }
+    }
+
+    /// Call `callback` for every rewrite, e.g. to log it
+    pub fn set_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&ScrubEvent) + Send + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Whether a scrub is due at the given time on the driver's clock
+    pub fn is_due(&self, at: Duration) -> bool {
+        self.last
+            .is_none_or(|last| at.saturating_sub(last) >= self.interval)
+    }
+
+    /// Scrub the config of `device` with [Ads1119::scrub_config] if it's due now on its
+    /// clock (see [Ads1119::set_clock])
+    pub fn poll<I2C>(
+        &mut self,
+        device: &mut Ads1119<I2C>,
+    ) -> Result<Option<ScrubEvent>, Ads1119Err<I2C::Error>>
+    where
+        I2C: I2c,
+    {
+        let now = device.now();
+        self.poll_at(device, now)
+    }
+
+    /// Same as [ConfigScrubber::poll], at the given time on the driver's clock
+    pub fn poll_at<I2C>(
+        &mut self,
+        device: &mut Ads1119<I2C>,
+        at: Duration,
+    ) -> Result<Option<ScrubEvent>, Ads1119Err<I2C::Error>>
+    where
+        I2C: I2c,
+    {
+        if !self.is_due(at) {
+            return Ok(None);
+        }
+        self.last = Some(at);
+        let event = device.scrub_config()?;
+        if let (Some(event), Some(callback)) = (&event, &mut self.callback) {
+            callback(event);
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{expect_read_config, expect_write_config};
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+    use std::sync::{Arc, Mutex};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn rewrites_a_corrupted_config() {
+        let transactions = [
+            expect_write_config(DEVICE_ADDRESS, 0b0110_0100),
+            expect_read_config(DEVICE_ADDRESS, 0b0110_0100),
+            // e.g. reset by a brownout
+            expect_read_config(DEVICE_ADDRESS, 0),
+            expect_write_config(DEVICE_ADDRESS, 0b0110_0100),
+            expect_read_config(DEVICE_ADDRESS, 0b0110_0100),
+        ];
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.write_config(0b0110_0100).unwrap();
+
+        assert_eq!(device.scrub_config().unwrap(), None);
+        let event = device.scrub_config().unwrap().unwrap();
+        assert_eq!((event.expected, event.found), (0b0110_0100, 0));
+        assert_eq!(device.stats().config_scrubs, 1);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn scrubber_runs_on_schedule_and_reports() {
+        let transactions = [
+            expect_write_config(DEVICE_ADDRESS, 0b0000_0010),
+            expect_read_config(DEVICE_ADDRESS, 0b1000_0010),
+            expect_write_config(DEVICE_ADDRESS, 0b0000_0010),
+            expect_read_config(DEVICE_ADDRESS, 0b0000_0010),
+        ];
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.write_config(0b0000_0010).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut scrubber = ConfigScrubber::new(Duration::from_secs(10));
+        let logged = events.clone();
+        scrubber.set_callback(move |event| logged.lock().unwrap().push(*event));
+
+        assert!(scrubber
+            .poll_at(&mut device, Duration::ZERO)
+            .unwrap()
+            .is_some());
+        // not due yet, so the device isn't touched
+        assert!(!scrubber.is_due(Duration::from_secs(5)));
+        assert_eq!(
+            scrubber
+                .poll_at(&mut device, Duration::from_secs(5))
+                .unwrap(),
+            None
+        );
+        assert!(scrubber.is_due(Duration::from_secs(10)));
+
+        assert_eq!(events.lock().unwrap()[0].found, 0b1000_0010);
+        device.destroy().done();
+    }
+}
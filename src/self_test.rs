@@ -0,0 +1,197 @@
+//! One-call health check of the device, e.g. at boot.
+
+use embedded_hal::i2c::I2c;
+
+use crate::clipping::Clipping;
+use crate::statistics::Statistics;
+use crate::{
+    rdata_to_voltage, Ads1119, Ads1119Err, Config, ConversionMode, DataRate, Gain, InputSelection,
+    VoltageReference,
+};
+
+/// Number of conversions of the shorted inputs taken by [Ads1119::self_test]
+pub const SELF_TEST_SAMPLES: usize = 8;
+
+/// Config written and read back by [Ads1119::self_test]: every field differs from its
+/// reset value, so a stuck or unwritten register is caught
+const ROUND_TRIP_CONFIG: Config = Config {
+    input: InputSelection::AN2SingleEnded,
+    gain: Gain::Four,
+    data_rate: DataRate::Sps1000,
+    conversion_mode: ConversionMode::Continuous,
+    voltage_reference: VoltageReference::External,
+};
+
+/// Pass/fail limits of [Ads1119::self_test_with_limits].
+///
+/// The defaults are generous compared to the typical offset and noise of the datasheet,
+/// so that only a faulty device or board fails.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelfTestLimits {
+    /// Largest magnitude of the mean of the shorted inputs at gain 1, in volts
+    pub max_offset: f32,
+    /// Largest RMS noise of the shorted inputs at gain 1, in volts
+    pub max_noise: f32,
+}
+
+impl Default for SelfTestLimits {
+    fn default() -> Self {
+        SelfTestLimits {
+            max_offset: 2e-3,
+            max_noise: 250e-6,
+        }
+    }
+}
+
+/// Result of [Ads1119::self_test]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelfTestReport {
+    /// Config register value written by the round trip check
+    pub config_written: u8,
+    /// Config register value read back by the round trip check
+    pub config_read: u8,
+    /// Statistics of the conversions of the inputs shorted to AVDD / 2 at gain 1, in raw codes
+    pub mid_supply: Statistics,
+    /// Mean of those conversions, in volts
+    pub offset: f32,
+    /// RMS noise of those conversions, in volts
+    pub noise: f32,
+    /// Whether any of those conversions clipped
+    pub clipped: bool,
+    pub limits: SelfTestLimits,
+}
+
+impl SelfTestReport {
+    /// The config register read back what was written
+    pub fn config_round_trip_ok(&self) -> bool {
+        self.config_written == self.config_read
+    }
+
+    /// The inputs shorted to AVDD / 2 convert without clipping and within the noise limit.
+    ///
+    /// The ADS1119 can't measure its supply directly: a missing or out-of-range AVDD shows
+    /// up as clipped or noisy conversions of the mid-supply common mode.
+    pub fn mid_supply_ok(&self) -> bool {
+        !self.clipped && self.noise <= self.limits.max_noise
+    }
+
+    /// The offset of the shorted inputs is within the limit
+    pub fn offset_ok(&self) -> bool {
+        self.offset.abs() <= self.limits.max_offset
+    }
+
+    /// All the checks passed
+    pub fn passed(&self) -> bool {
+        self.config_round_trip_ok() && self.mid_supply_ok() && self.offset_ok()
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// [Ads1119::self_test_with_limits] with the default limits
+    pub fn self_test(&mut self) -> Result<SelfTestReport, Ads1119Err<I2C::Error>> {
+        self.self_test_with_limits(&SelfTestLimits::default())
+    }
+
+    /// Check that the config register round-trips, then take [SELF_TEST_SAMPLES] single-shot
+    /// conversions of the inputs shorted to AVDD / 2 at gain 1 and check their noise and offset.
+    ///
+    /// The config last written by the driver, if known, is restored afterwards.
+    /// A failed check is reported in the [SelfTestReport]; only bus errors return an error.
+    pub fn self_test_with_limits(
+        &mut self,
+        limits: &SelfTestLimits,
+    ) -> Result<SelfTestReport, Ads1119Err<I2C::Error>> {
+        let previous = self.config;
+
+        let config_written = ROUND_TRIP_CONFIG.bits();
+        self.write_config(config_written)?;
+        let config_read = self.read_config()?;
+
+        let samples =
+            self.read_samples(InputSelection::ShortedToMidSupply.bits(), SELF_TEST_SAMPLES)?;
+        let mid_supply = Statistics::from_samples(&samples).ok_or(Ads1119Err::NoSamples)?;
+        let clipped = samples
+            .iter()
+            .any(|&sample| Clipping::of(sample, 0.0) == Clipping::Clipped);
+        let lsb = rdata_to_voltage(1, Gain::One);
+
+        if let Some(previous) = previous {
+            self.write_config(previous)?;
+        }
+
+        Ok(SelfTestReport {
+            config_written,
+            config_read,
+            offset: mid_supply.mean * lsb,
+            noise: mid_supply.std_dev * lsb,
+            mid_supply,
+            clipped,
+            limits: *limits,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{
+        expect_oneshot_read, expect_read_config, expect_repeated_oneshot_reads, expect_write_config,
+    };
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    fn self_test_transactions(config_read: u8, samples: &[i16]) -> Vec<I2cTransaction> {
+        let mut transactions = vec![
+            expect_write_config(DEVICE_ADDRESS, ROUND_TRIP_CONFIG.bits()),
+            expect_read_config(DEVICE_ADDRESS, config_read),
+        ];
+        transactions.extend(expect_repeated_oneshot_reads(
+            DEVICE_ADDRESS,
+            InputSelection::ShortedToMidSupply.bits(),
+            samples,
+        ));
+        transactions
+    }
+
+    #[test]
+    fn healthy_device_passes_and_config_is_restored() {
+        let input = InputSelection::AN0SingleEnded;
+        let mut transactions = expect_oneshot_read(DEVICE_ADDRESS, &input, 100);
+        transactions.extend(self_test_transactions(
+            ROUND_TRIP_CONFIG.bits(),
+            &[1, -1, 2, 0, 1, -1, 0, 2],
+        ));
+        transactions.push(expect_write_config(DEVICE_ADDRESS, input.bits()));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        device.read_input_oneshot(&input).unwrap();
+        let report = device.self_test().unwrap();
+        assert!(report.passed());
+        assert!((report.offset - 0.5 * 62.5e-6).abs() < 1e-9);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn reports_each_failed_check() {
+        let transactions = self_test_transactions(0x00, &[100, 100, 100, 100, 100, 100, 100, 100]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let report = device.self_test().unwrap();
+        assert!(!report.config_round_trip_ok());
+        assert!(report.mid_supply_ok());
+        assert!(!report.offset_ok());
+        assert!(!report.passed());
+        device.destroy().done();
+
+        let transactions = self_test_transactions(ROUND_TRIP_CONFIG.bits(), &[i16::MAX; 8]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        let report = device.self_test().unwrap();
+        assert!(report.clipped);
+        assert!(!report.mid_supply_ok());
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,106 @@
+//! Sequence numbers of results, to detect the ones lost on the way to a consumer.
+//!
+//! The driver numbers the results of continuous conversions ([crate::Ads1119::samples]) and
+//! scans ([crate::scan::ScanReading::sequence]) from one counter. In continuous mode, the
+//! conversions the device finished while nobody was reading (see
+//! [crate::continuous::Samples::overrun]) use up sequence numbers too, so a [GapDetector] at
+//! the end of a channel or stream reports both those and the results dropped in between.
+
+/// A value with its sequence number
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sequenced<T> {
+    pub sequence: u64,
+    pub value: T,
+}
+
+/// Sequence numbers skipped between two results, see [GapDetector::check]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SequenceGap {
+    /// The sequence number that should have come next
+    pub expected: u64,
+    /// The sequence number that came instead
+    pub received: u64,
+}
+
+impl SequenceGap {
+    /// Number of results lost
+    pub fn missed(&self) -> u64 {
+        self.received - self.expected
+    }
+}
+
+/// Checks that the sequence numbers of the results received follow each other
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GapDetector {
+    // None before the first result
+    next: Option<u64>,
+    gaps: u64,
+    missed: u64,
+}
+
+impl GapDetector {
+    pub fn new() -> Self {
+        GapDetector::default()
+    }
+
+    /// Record the sequence number of a result, returning the gap before it if results were
+    /// lost. A sequence number that goes back (e.g. after the driver was recreated) starts
+    /// over without a gap.
+    pub fn check(&mut self, sequence: u64) -> Option<SequenceGap> {
+        let gap = self
+            .next
+            .filter(|&expected| sequence > expected)
+            .map(|expected| SequenceGap {
+                expected,
+                received: sequence,
+            });
+        if let Some(gap) = gap {
+            self.gaps += 1;
+            self.missed += gap.missed();
+        }
+        self.next = Some(sequence.wrapping_add(1));
+        gap
+    }
+
+    /// Number of gaps seen
+    pub fn gaps(&self) -> u64 {
+        self.gaps
+    }
+
+    /// Total number of results lost in the gaps
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detects_gaps() {
+        let mut detector = GapDetector::new();
+        assert_eq!(detector.check(5), None);
+        assert_eq!(detector.check(6), None);
+        assert_eq!(
+            detector.check(9),
+            Some(SequenceGap {
+                expected: 7,
+                received: 9
+            })
+        );
+        assert_eq!(detector.check(10), None);
+        assert_eq!(detector.gaps(), 1);
+        assert_eq!(detector.missed(), 2);
+    }
+
+    #[test]
+    fn restarts_when_the_sequence_goes_back() {
+        let mut detector = GapDetector::new();
+        detector.check(100);
+        assert_eq!(detector.check(0), None);
+        assert_eq!(detector.check(1), None);
+        assert_eq!(detector.gaps(), 0);
+    }
+}
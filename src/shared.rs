@@ -0,0 +1,164 @@
+//! A driver that can be shared between threads.
+//!
+//! One-shot reads write the config register, start a conversion and poll for it, so two
+//! threads calling the driver at once would corrupt each other's readings (see
+//! [Ads1119::read_input_oneshot]). [SharedAds1119] serializes the calls instead.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use embedded_hal::i2c::I2c;
+
+use crate::average::AveragedReading;
+use crate::reading::Reading;
+use crate::scan::{ScanPlan, ScanReading};
+use crate::{Ads1119, Ads1119Err, Config, InputSelection};
+
+/// Thread-safe handle of an [Ads1119].
+///
+/// Clones share the same device, and each call locks it for its whole duration.
+pub struct SharedAds1119<I2C> {
+    device: Arc<Mutex<Ads1119<I2C>>>,
+}
+
+impl<I2C> Clone for SharedAds1119<I2C> {
+    fn clone(&self) -> Self {
+        SharedAds1119 {
+            device: self.device.clone(),
+        }
+    }
+}
+
+impl<I2C> SharedAds1119<I2C>
+where
+    I2C: I2c,
+{
+    pub fn new(device: Ads1119<I2C>) -> Self {
+        SharedAds1119 {
+            device: Arc::new(Mutex::new(device)),
+        }
+    }
+
+    /// Run a closure with exclusive access to the driver.
+    ///
+    /// This is how calls without a counterpart here can be made, or several calls made
+    /// without another thread's in between.
+    pub fn with_device<T>(&self, f: impl FnOnce(&mut Ads1119<I2C>) -> T) -> T {
+        f(&mut lock(&self.device))
+    }
+
+    /// See [Ads1119::read]
+    pub fn read(&self, config: &Config) -> Result<Reading, Ads1119Err<I2C::Error>> {
+        self.with_device(|device| device.read(config))
+    }
+
+    /// See [Ads1119::read_input_oneshot]
+    pub fn read_input_oneshot(
+        &self,
+        input: &InputSelection,
+    ) -> Result<i16, Ads1119Err<I2C::Error>> {
+        self.with_device(|device| device.read_input_oneshot(input))
+    }
+
+    /// See [Ads1119::read_input_voltage]
+    pub fn read_input_voltage(
+        &self,
+        input: &InputSelection,
+    ) -> Result<f32, Ads1119Err<I2C::Error>> {
+        self.with_device(|device| device.read_input_voltage(input))
+    }
+
+    /// See [Ads1119::read_input_averaged]
+    pub fn read_input_averaged(
+        &self,
+        input: &InputSelection,
+        n: usize,
+    ) -> Result<AveragedReading, Ads1119Err<I2C::Error>> {
+        self.with_device(|device| device.read_input_averaged(input, n))
+    }
+
+    /// See [Ads1119::run_scan]. No other call is made in the middle of the scan.
+    pub fn run_scan(&self, plan: &ScanPlan) -> Result<Vec<ScanReading>, Ads1119Err<I2C::Error>> {
+        self.with_device(|device| device.run_scan(plan))
+    }
+
+    /// See [Ads1119::read_config]
+    pub fn read_config(&self) -> Result<u8, I2C::Error> {
+        self.with_device(|device| device.read_config())
+    }
+
+    /// See [Ads1119::write_config]
+    pub fn write_config(&self, value: u8) -> Result<(), I2C::Error> {
+        self.with_device(|device| device.write_config(value))
+    }
+
+    /// See [Ads1119::read_status]
+    pub fn read_status(&self) -> Result<u8, I2C::Error> {
+        self.with_device(|device| device.read_status())
+    }
+
+    /// See [Ads1119::reset]
+    pub fn reset(&self) -> Result<(), I2C::Error> {
+        self.with_device(|device| device.reset())
+    }
+
+    /// See [Ads1119::power_down]
+    pub fn power_down(&self) -> Result<(), I2C::Error> {
+        self.with_device(|device| device.power_down())
+    }
+
+    /// Get the driver back.
+    ///
+    /// Returns `Err(self)` if other clones are still around.
+    pub fn into_inner(self) -> Result<Ads1119<I2C>, Self> {
+        match Arc::try_unwrap(self.device) {
+            Ok(device) => Ok(device.into_inner().unwrap_or_else(|e| e.into_inner())),
+            Err(device) => Err(SharedAds1119 { device }),
+        }
+    }
+}
+
+impl<I2C> From<Ads1119<I2C>> for SharedAds1119<I2C>
+where
+    I2C: I2c,
+{
+    fn from(device: Ads1119<I2C>) -> Self {
+        SharedAds1119::new(device)
+    }
+}
+
+// a panic in another thread while it held the lock doesn't leave the driver in a bad state
+fn lock<I2C>(device: &Mutex<Ads1119<I2C>>) -> MutexGuard<'_, Ads1119<I2C>> {
+    device.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_oneshot_read;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+    use std::thread;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn clones_read_from_several_threads() {
+        let input = InputSelection::AN1SingleEnded;
+        let mut transactions = vec![];
+        for _ in 0..4 {
+            transactions.extend(expect_oneshot_read(DEVICE_ADDRESS, &input, 0x1234));
+        }
+        let shared = SharedAds1119::new(Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS));
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                let input = input.clone();
+                thread::spawn(move || shared.read_input_oneshot(&input).unwrap())
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), 0x1234);
+        }
+        shared.into_inner().ok().unwrap().destroy().done();
+    }
+}
@@ -0,0 +1,139 @@
+//! Current measurement across a shunt resistor.
+
+use embedded_hal::i2c::I2c;
+
+use crate::{rdata_to_voltage, Ads1119, Ads1119Err, Config, Gain, InputSelection};
+
+/// A shunt resistor, optionally followed by a current-sense amplifier, on a (usually
+/// differential) input of the ADS1119.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShuntMonitor {
+    pub input: InputSelection,
+    /// Shunt resistance, in ohms
+    pub shunt_resistance: f32,
+    /// Gain of the external current-sense amplifier, 1.0 if the shunt is read directly
+    pub amplifier_gain: f32,
+    /// Gain of the ADS1119's PGA
+    pub adc_gain: Gain,
+    /// Magnitude of the current, in amps, above which readings are flagged as overcurrent
+    pub overcurrent_threshold: Option<f32>,
+}
+
+/// A current reading from a [ShuntMonitor]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShuntReading {
+    /// Current, in amps. The sign follows the input's polarity.
+    pub amps: f32,
+    /// The current magnitude exceeds the monitor's threshold
+    pub overcurrent: bool,
+}
+
+impl ShuntMonitor {
+    /// A shunt read directly (no external amplifier) at gain 4, without an overcurrent threshold
+    pub fn new(input: InputSelection, shunt_resistance: f32) -> Self {
+        ShuntMonitor {
+            input,
+            shunt_resistance,
+            amplifier_gain: 1.0,
+            adc_gain: Gain::Four,
+            overcurrent_threshold: None,
+        }
+    }
+
+    pub fn with_amplifier_gain(mut self, amplifier_gain: f32) -> Self {
+        self.amplifier_gain = amplifier_gain;
+        self
+    }
+
+    pub fn with_adc_gain(mut self, adc_gain: Gain) -> Self {
+        self.adc_gain = adc_gain;
+        self
+    }
+
+    pub fn with_overcurrent_threshold(mut self, amps: f32) -> Self {
+        self.overcurrent_threshold = Some(amps);
+        self
+    }
+
+    /// Current, in amps, for the voltage measured at the ADC input
+    pub fn current(&self, volts: f32) -> f32 {
+        volts / self.amplifier_gain / self.shunt_resistance
+    }
+
+    /// Convert the voltage measured at the ADC input into a reading, checking the threshold
+    pub fn reading(&self, volts: f32) -> ShuntReading {
+        let amps = self.current(volts);
+        ShuntReading {
+            amps,
+            overcurrent: self
+                .overcurrent_threshold
+                .is_some_and(|threshold| amps.abs() > threshold),
+        }
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Take a single-shot reading of the shunt and convert it to a current.
+    /// The input's calibration, if any, is applied to the measured voltage.
+    pub fn read_shunt(
+        &mut self,
+        monitor: &ShuntMonitor,
+    ) -> Result<ShuntReading, Ads1119Err<I2C::Error>> {
+        let config = Config::new(monitor.input.clone()).with_gain(monitor.adc_gain);
+        let raw = self.read_oneshot(config.bits(), None)?;
+        let volts = self.apply_calibration(&monitor.input, rdata_to_voltage(raw, monitor.adc_gain));
+        Ok(monitor.reading(volts))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CmdFlags, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+    const EPS: f32 = 0.0001;
+
+    #[test]
+    fn current_and_threshold() {
+        let monitor = ShuntMonitor::new(InputSelection::AN0AN1Differential, 0.01)
+            .with_amplifier_gain(20.0)
+            .with_overcurrent_threshold(2.5);
+        // 0.4V after a 20x amplifier is 20mV across 10 milliohms
+        assert!((monitor.current(0.4) - 2.0).abs() < EPS);
+        assert!(!monitor.reading(0.4).overcurrent);
+        assert!(monitor.reading(0.6).overcurrent);
+        assert!(monitor.reading(-0.6).overcurrent);
+    }
+
+    #[test]
+    fn can_read_shunt() {
+        let monitor = ShuntMonitor::new(InputSelection::AN2AN3Differential, 0.1);
+        let mut device = Ads1119::new(
+            I2cMock::new(&[
+                // AIN2-AIN3, gain 4
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, 0b0011_0000],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                // half of the 0.512V full scale
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0x40, 0x00]),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        let reading = device.read_shunt(&monitor).unwrap();
+        assert!((reading.amps - 2.56).abs() < 0.001);
+        assert!(!reading.overcurrent);
+        device.destroy().done();
+    }
+}
@@ -0,0 +1,520 @@
+//! Software simulation of the device, for testing application logic, filters and alarms
+//! without hardware or hand-written mock transactions.
+//!
+//! Enabled with the `sim` feature.
+//!
+//! [SimulatedDevice] answers the device's I2C commands like the real part, converting
+//! voltages produced by user-provided functions of time, one per analog input pin.
+//! [Ads1119Sim] is the driver on top of it, so it has the same API as on hardware:
+//!
+//! ```
+//! use ads1119::sim::{signal, Ads1119Sim, SimulatedDevice};
+//! use ads1119::InputSelection;
+//!
+//! let sim = SimulatedDevice::new().with_input(0, signal::constant(1.0));
+//! let mut device = Ads1119Sim::simulated(sim);
+//! let volts = device.read_input_voltage(&InputSelection::AN0SingleEnded).unwrap();
+//! assert!((volts - 1.0).abs() < 0.001);
+//! ```
+//!
+//! Time is simulated: each conversion advances the clock by one period of the configured
+//! data rate, so results are deterministic.
+//!
+//! Error handling can be exercised with [Faults], injected at configurable probabilities
+//! from a seeded generator, so a given seed always fails the same way.
+
+use core::time::Duration;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+use crate::{
+    Ads1119, Command, DataRate, InputSelection, Register, INTERNAL_REFERENCE_VOLTAGE,
+    STATUS_CONV_RDY,
+};
+
+/// I2C address of a [SimulatedDevice] unless set with [SimulatedDevice::with_address]
+pub const SIM_ADDRESS: u8 = 0x40;
+
+/// The driver on a simulated device
+pub type Ads1119Sim = Ads1119<SimulatedDevice>;
+
+impl Ads1119Sim {
+    /// A driver talking to the given simulated device, at its address
+    pub fn simulated(device: SimulatedDevice) -> Self {
+        let address = device.state().address;
+        Ads1119::new(device, address)
+    }
+}
+
+/// Voltage of an input pin as a function of the simulated time
+pub type Signal = Box<dyn FnMut(Duration) -> f32 + Send>;
+
+/// Common [Signal]s
+pub mod signal {
+    use super::Signal;
+
+    /// Always the same voltage
+    pub fn constant(volts: f32) -> Signal {
+        Box::new(move |_| volts)
+    }
+
+    /// `offset + amplitude * sin(2π * frequency * t)`
+    pub fn sine(amplitude: f32, frequency: f32, offset: f32) -> Signal {
+        Box::new(move |t| {
+            offset + amplitude * (core::f32::consts::TAU * frequency * t.as_secs_f32()).sin()
+        })
+    }
+
+    /// `start + volts_per_second * t`
+    pub fn ramp(start: f32, volts_per_second: f32) -> Signal {
+        Box::new(move |t| start + volts_per_second * t.as_secs_f32())
+    }
+
+    /// Uniform noise within `mean ± amplitude`, from a generator seeded with `seed`,
+    /// so the same seed gives the same sequence.
+    pub fn noise(mean: f32, amplitude: f32, seed: u32) -> Signal {
+        let mut rng = super::XorShift::new(seed);
+        Box::new(move |_| mean + amplitude * (2.0 * rng.next_unit() - 1.0))
+    }
+}
+
+// xorshift32, enough for reproducible test signals and faults
+struct XorShift(u32);
+
+impl XorShift {
+    fn new(seed: u32) -> Self {
+        // xorshift doesn't leave 0
+        XorShift(seed.max(1))
+    }
+
+    /// Uniform in [0, 1]
+    fn next_unit(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32
+    }
+
+    /// True with the given probability
+    fn chance(&mut self, probability: f32) -> bool {
+        probability > 0.0 && self.next_unit() < probability
+    }
+}
+
+/// Probabilities of the faults a [SimulatedDevice] injects, all 0 by default
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Faults {
+    seed: u32,
+    nack: f32,
+    stuck_not_ready: f32,
+    corrupt_config: f32,
+    saturation: f32,
+}
+
+impl Faults {
+    /// No faults, with `seed` for the generator deciding when they happen
+    pub fn new(seed: u32) -> Self {
+        Faults {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    /// Probability of a transaction not being acknowledged ([SimError::InjectedNack])
+    pub fn with_nack(mut self, probability: f32) -> Self {
+        self.nack = probability;
+        self
+    }
+
+    /// Probability of a conversion never completing: the status register doesn't report
+    /// it until the next START/SYNC or RESET, so the driver times out.
+    pub fn with_stuck_not_ready(mut self, probability: f32) -> Self {
+        self.stuck_not_ready = probability;
+        self
+    }
+
+    /// Probability of a config register read returning the value with one bit flipped
+    pub fn with_corrupt_config(mut self, probability: f32) -> Self {
+        self.corrupt_config = probability;
+        self
+    }
+
+    /// Probability of a conversion returning the full-scale code of the input's polarity
+    pub fn with_saturation(mut self, probability: f32) -> Self {
+        self.saturation = probability;
+        self
+    }
+}
+
+/// Errors of a [SimulatedDevice], reported to the driver as I2C errors
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SimError {
+    /// Nothing answered at the address of the transaction
+    NoDevice,
+    /// A command the device doesn't understand
+    InvalidCommand(u8),
+    /// A NACK injected by [Faults::with_nack]
+    InjectedNack,
+}
+
+impl embedded_hal::i2c::Error for SimError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            SimError::NoDevice => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            SimError::InvalidCommand(_) => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+            SimError::InjectedNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown),
+        }
+    }
+}
+
+/// Simulated ADS1119 on an I2C bus.
+///
+/// Clones are handles to the same device, so a test can keep one to change the inputs
+/// while the driver owns another.
+#[derive(Clone)]
+pub struct SimulatedDevice {
+    state: Arc<Mutex<SimState>>,
+}
+
+struct SimState {
+    address: u8,
+    config: u8,
+    // the result of the last conversion, not yet read if ready is set
+    data: i16,
+    ready: bool,
+    // the current conversion was made to never complete
+    stuck: bool,
+    // between START/SYNC and POWERDOWN or a single-shot conversion
+    running: bool,
+    // register selected by the last command, read by the next read operation
+    pending_read: Option<Command>,
+    inputs: [Option<Signal>; 4],
+    external_reference: f32,
+    time: Duration,
+    faults: Faults,
+    rng: XorShift,
+}
+
+impl Default for SimulatedDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimulatedDevice {
+    /// A device at [SIM_ADDRESS] with all inputs at 0V and a 2.048V external reference
+    pub fn new() -> Self {
+        SimulatedDevice {
+            state: Arc::new(Mutex::new(SimState {
+                address: SIM_ADDRESS,
+                config: 0,
+                data: 0,
+                ready: false,
+                stuck: false,
+                running: false,
+                pending_read: None,
+                inputs: [None, None, None, None],
+                external_reference: INTERNAL_REFERENCE_VOLTAGE,
+                time: Duration::ZERO,
+                faults: Faults::default(),
+                rng: XorShift::new(0),
+            })),
+        }
+    }
+
+    pub fn with_address(self, address: u8) -> Self {
+        self.state().address = address;
+        self
+    }
+
+    /// Drive the input pin AIN`pin` (0 to 3) with the given signal
+    pub fn with_input(self, pin: usize, signal: Signal) -> Self {
+        self.set_input(pin, signal);
+        self
+    }
+
+    /// Voltage between REFP and REFN, used when the external reference is selected
+    pub fn with_external_reference(self, volts: f32) -> Self {
+        self.state().external_reference = volts;
+        self
+    }
+
+    /// Inject the given faults from now on
+    pub fn with_faults(self, faults: Faults) -> Self {
+        self.set_faults(faults);
+        self
+    }
+
+    /// See [SimulatedDevice::with_input]
+    pub fn set_input(&self, pin: usize, signal: Signal) {
+        self.state().inputs[pin] = Some(signal);
+    }
+
+    /// See [SimulatedDevice::with_faults]. This also reseeds the generator.
+    pub fn set_faults(&self, faults: Faults) {
+        let mut state = self.state();
+        state.rng = XorShift::new(faults.seed);
+        state.faults = faults;
+    }
+
+    /// Simulated time, advanced by one data rate period per conversion
+    pub fn elapsed(&self) -> Duration {
+        self.state().time
+    }
+
+    /// The value of the config register
+    pub fn config(&self) -> u8 {
+        self.state().config
+    }
+
+    fn state(&self) -> MutexGuard<'_, SimState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SimState {
+    fn command(&mut self, bytes: &[u8]) -> Result<(), SimError> {
+        let Some(&command) = bytes.first() else {
+            return Ok(());
+        };
+        match Command::decode(command) {
+            Some(Command::Reset) => {
+                self.config = 0;
+                self.running = false;
+                self.ready = false;
+                self.stuck = false;
+            }
+            Some(Command::StartSync) => {
+                self.running = true;
+                self.stuck = false;
+                self.convert();
+            }
+            Some(Command::PowerDown) => self.running = false,
+            Some(read @ (Command::ReadData | Command::ReadRegister(_))) => {
+                self.pending_read = Some(read)
+            }
+            Some(Command::WriteRegister(Register::Config)) if bytes.len() == 2 => {
+                self.config = bytes[1];
+            }
+            _ => return Err(SimError::InvalidCommand(command)),
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) {
+        let response = match self.pending_read {
+            Some(Command::ReadData) => {
+                self.ready = false;
+                self.data.to_be_bytes().to_vec()
+            }
+            Some(Command::ReadRegister(Register::Config)) => {
+                let mut config = self.config;
+                if self.rng.chance(self.faults.corrupt_config) {
+                    config ^= 1 << (self.rng.next_unit() * 7.0).round() as u8;
+                }
+                vec![config]
+            }
+            Some(Command::ReadRegister(Register::Status)) => {
+                // in continuous mode, the next conversion is done by the time it's polled for
+                if self.running && !self.ready && !self.stuck {
+                    self.convert();
+                }
+                vec![if self.ready { STATUS_CONV_RDY } else { 0 }]
+            }
+            _ => vec![],
+        };
+        for (byte, value) in buffer.iter_mut().zip(response.into_iter().chain([0xFF; 8])) {
+            *byte = value;
+        }
+    }
+
+    fn continuous(&self) -> bool {
+        self.config & 0b0000_0010 != 0
+    }
+
+    fn convert(&mut self) {
+        self.time += self.period();
+        let volts = self.input_voltage();
+        let gain = if self.config & 0b0001_0000 != 0 {
+            4.0
+        } else {
+            1.0
+        };
+        let reference = if self.config & 0b0000_0001 != 0 {
+            self.external_reference
+        } else {
+            INTERNAL_REFERENCE_VOLTAGE
+        };
+        let code = (volts * gain / reference * 0x7FFF as f32).round();
+        self.data = if self.rng.chance(self.faults.saturation) {
+            if volts < 0.0 {
+                i16::MIN
+            } else {
+                i16::MAX
+            }
+        } else {
+            code.clamp(i16::MIN as f32, i16::MAX as f32) as i16
+        };
+        if self.rng.chance(self.faults.stuck_not_ready) {
+            self.stuck = true;
+            return;
+        }
+        self.ready = true;
+        if !self.continuous() {
+            self.running = false;
+        }
+    }
+
+    fn period(&self) -> Duration {
+        DataRate::from_bits(self.config).conversion_time()
+    }
+
+    fn input_voltage(&mut self) -> f32 {
+        let time = self.time;
+        let mut pin = |n: usize| self.inputs[n].as_mut().map_or(0.0, |signal| signal(time));
+        match InputSelection::from_bits(self.config) {
+            InputSelection::AN0AN1Differential => pin(0) - pin(1),
+            InputSelection::AN2AN3Differential => pin(2) - pin(3),
+            InputSelection::AN1AN2Differential => pin(1) - pin(2),
+            InputSelection::AN0SingleEnded => pin(0),
+            InputSelection::AN1SingleEnded => pin(1),
+            InputSelection::AN2SingleEnded => pin(2),
+            InputSelection::AN3SingleEnded => pin(3),
+            InputSelection::ShortedToMidSupply => 0.0,
+        }
+    }
+}
+
+impl ErrorType for SimulatedDevice {
+    type Error = SimError;
+}
+
+impl I2c for SimulatedDevice {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let mut state = self.state();
+        if address != state.address {
+            return Err(SimError::NoDevice);
+        }
+        let nack = state.faults.nack;
+        if state.rng.chance(nack) {
+            return Err(SimError::InjectedNack);
+        }
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => state.command(bytes)?,
+                Operation::Read(buffer) => state.read(buffer),
+            }
+        }
+        state.pending_read = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Ads1119Err, Config, ConversionMode, DataRate, Gain, VoltageReference};
+
+    #[test]
+    fn converts_the_selected_input() {
+        let sim = SimulatedDevice::new()
+            .with_input(0, signal::constant(1.5))
+            .with_input(1, signal::constant(0.5))
+            .with_external_reference(4.096);
+        let mut device = Ads1119Sim::simulated(sim.clone());
+
+        let volts = device
+            .read_input_voltage(&InputSelection::AN0SingleEnded)
+            .unwrap();
+        assert!((volts - 1.5).abs() < 0.001);
+
+        // differential, gain 4 and external reference
+        let config = Config::new(InputSelection::AN0AN1Differential)
+            .with_gain(Gain::Four)
+            .with_voltage_reference(VoltageReference::External);
+        let raw = device.read_oneshot(config.bits(), None).unwrap();
+        assert_eq!(raw, (1.0 * 4.0 / 4.096 * 32767.0_f32).round() as i16);
+
+        // saturates
+        sim.set_input(2, signal::constant(3.0));
+        assert_eq!(
+            device
+                .read_input_oneshot(&InputSelection::AN2SingleEnded)
+                .unwrap(),
+            i16::MAX
+        );
+        assert_eq!(sim.elapsed(), Duration::from_millis(150));
+    }
+
+    #[test]
+    fn continuous_samples_follow_the_signal() {
+        let sim = SimulatedDevice::new().with_input(3, signal::ramp(0.0, 1.0));
+        let mut device = Ads1119Sim::simulated(sim.clone());
+        let config = Config::new(InputSelection::AN3SingleEnded)
+            .with_data_rate(DataRate::Sps1000)
+            .with_conversion_mode(ConversionMode::Continuous);
+        device.start_continuous(&config).unwrap();
+
+        let samples: Vec<i16> = device.samples().take(3).map(Result::unwrap).collect();
+        // 1 mV per conversion
+        assert_eq!(samples, vec![16, 32, 48]);
+        assert_eq!(sim.elapsed(), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn wrong_address_is_not_acknowledged() {
+        let mut device = Ads1119::new(SimulatedDevice::new(), 0x41);
+        assert_eq!(device.read_config(), Err(SimError::NoDevice));
+    }
+
+    #[test]
+    fn injected_faults() {
+        let input = InputSelection::AN0SingleEnded;
+        let sim = SimulatedDevice::new()
+            .with_input(0, signal::constant(1.0))
+            .with_faults(Faults::new(1).with_saturation(1.0));
+        let mut device = Ads1119Sim::simulated(sim.clone());
+        assert_eq!(device.read_input_oneshot(&input).unwrap(), i16::MAX);
+
+        sim.set_faults(Faults::new(1).with_corrupt_config(1.0));
+        device.write_config(input.bits()).unwrap();
+        assert_ne!(device.read_config().unwrap(), input.bits());
+        assert_eq!(sim.config(), input.bits());
+
+        sim.set_faults(Faults::new(1).with_stuck_not_ready(1.0));
+        assert!(matches!(
+            device.read_input_oneshot(&input),
+            Err(Ads1119Err::ConversionTimeout(_))
+        ));
+    }
+
+    #[test]
+    fn injected_nacks_are_reproducible() {
+        let outcomes = |seed| {
+            let sim = SimulatedDevice::new().with_faults(Faults::new(seed).with_nack(0.5));
+            let mut device = Ads1119Sim::simulated(sim);
+            (0..64)
+                .map(|_| device.read_status().is_ok())
+                .collect::<Vec<_>>()
+        };
+        let first = outcomes(42);
+        assert_eq!(first, outcomes(42));
+        assert!(first.contains(&true) && first.contains(&false));
+    }
+
+    #[test]
+    fn noise_is_bounded_and_reproducible() {
+        let mut a = signal::noise(1.0, 0.1, 7);
+        let mut b = signal::noise(1.0, 0.1, 7);
+        for _ in 0..100 {
+            let value = a(Duration::ZERO);
+            assert!((0.9..=1.1).contains(&value));
+            assert_eq!(value, b(Duration::ZERO));
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Magnitude spectrum of a captured block, for hunting noise sources such as switching
+//! regulator spurs or mains pickup.
+//!
+//! Capture a block with [crate::Ads1119::capture], then turn it into a [Spectrum] with
+//! [Spectrum::from_capture]. The magnitudes are single-sided amplitudes in raw ADC codes:
+//! a sine of amplitude `A` codes at a bin's frequency shows as `A` in that bin.
+
+use realfft::RealFftPlanner;
+
+use crate::block::CaptureTiming;
+
+/// Window applied to the samples before the FFT
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Window {
+    /// No window: exact amplitudes for frequencies on a bin, but a lot of leakage in between
+    Rectangular,
+    /// Hann window: much less leakage, at the cost of wider peaks
+    #[default]
+    Hann,
+}
+
+impl Window {
+    fn weight(&self, index: usize, len: usize) -> f32 {
+        match self {
+            Window::Rectangular => 1.0,
+            Window::Hann => {
+                let phase = 2.0 * core::f32::consts::PI * index as f32 / len as f32;
+                0.5 * (1.0 - phase.cos())
+            }
+        }
+    }
+
+    // mean of the weights, by which a windowed sine's peak is scaled
+    fn coherent_gain(&self) -> f32 {
+        match self {
+            Window::Rectangular => 1.0,
+            Window::Hann => 0.5,
+        }
+    }
+}
+
+/// Single-sided magnitude spectrum
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spectrum {
+    /// Frequency step between two bins, in Hz
+    pub bin_width: f32,
+    /// Amplitude in raw ADC codes of each bin, from DC to half the sample rate
+    pub magnitudes: Vec<f32>,
+}
+
+impl Spectrum {
+    /// Spectrum of `samples` taken `sample_rate` times per second, with the Hann window.
+    /// None with fewer than two samples.
+    pub fn from_samples(samples: &[i16], sample_rate: f32) -> Option<Self> {
+        Spectrum::with_window(samples, sample_rate, Window::Hann)
+    }
+
+    /// Spectrum of a block captured with [crate::Ads1119::capture], at the rate achieved
+    /// during the capture. None with fewer than two samples.
+    pub fn from_capture(samples: &[i16], timing: &CaptureTiming) -> Option<Self> {
+        let sample_rate = timing.achieved_rate()?;
+        Spectrum::from_samples(&samples[..timing.samples], sample_rate)
+    }
+
+    /// Spectrum of `samples` taken `sample_rate` times per second, with `window`.
+    /// None with fewer than two samples.
+    pub fn with_window(samples: &[i16], sample_rate: f32, window: Window) -> Option<Self> {
+        let len = samples.len();
+        if len < 2 {
+            return None;
+        }
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(len);
+        let mut input: Vec<f32> = samples
+            .iter()
+            .enumerate()
+            .map(|(index, &sample)| f32::from(sample) * window.weight(index, len))
+            .collect();
+        let mut output = fft.make_output_vec();
+        fft.process(&mut input, &mut output)
+            .expect("the buffers are made for this FFT");
+
+        let scale = 1.0 / (len as f32 * window.coherent_gain());
+        let magnitudes = output
+            .iter()
+            .enumerate()
+            .map(|(bin, value)| {
+                // the other bins also stand for their negative frequency
+                let sides = if bin == 0 || 2 * bin == len { 1.0 } else { 2.0 };
+                value.norm() * scale * sides
+            })
+            .collect();
+        Some(Spectrum {
+            bin_width: sample_rate / len as f32,
+            magnitudes,
+        })
+    }
+
+    /// Frequency of `bin`, in Hz
+    pub fn frequency(&self, bin: usize) -> f32 {
+        bin as f32 * self.bin_width
+    }
+
+    /// Magnitude of the bin nearest to `frequency`, None above half the sample rate
+    pub fn magnitude_at(&self, frequency: f32) -> Option<f32> {
+        let bin = (frequency / self.bin_width).round() as usize;
+        self.magnitudes.get(bin).copied()
+    }
+
+    /// Frequency and magnitude of the largest bin, DC excluded
+    pub fn peak(&self) -> Option<(f32, f32)> {
+        self.peaks(1).into_iter().next()
+    }
+
+    /// Frequencies and magnitudes of the `n` largest local maxima, DC excluded, largest first
+    pub fn peaks(&self, n: usize) -> Vec<(f32, f32)> {
+        let magnitudes = &self.magnitudes;
+        let mut peaks: Vec<(f32, f32)> = (1..magnitudes.len())
+            .filter(|&bin| {
+                magnitudes[bin] >= magnitudes[bin - 1]
+                    && magnitudes
+                        .get(bin + 1)
+                        .is_none_or(|&next| magnitudes[bin] > next)
+            })
+            .map(|bin| (self.frequency(bin), magnitudes[bin]))
+            .collect();
+        peaks.sort_by(|a, b| b.1.total_cmp(&a.1));
+        peaks.truncate(n);
+        peaks
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    // `amplitude` codes at `frequency` Hz on top of `offset`, sampled at 1000 SPS
+    fn sine(len: usize, frequency: f32, amplitude: f32, offset: f32) -> Vec<i16> {
+        (0..len)
+            .map(|index| {
+                let t = index as f32 / 1000.0;
+                let phase = 2.0 * core::f32::consts::PI * frequency * t;
+                (offset + amplitude * phase.sin()).round() as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_a_tone() {
+        let samples = sine(1000, 50.0, 1000.0, 200.0);
+        let spectrum = Spectrum::from_samples(&samples, 1000.0).unwrap();
+        assert_eq!(spectrum.bin_width, 1.0);
+        assert_eq!(spectrum.magnitudes.len(), 501);
+        let (frequency, magnitude) = spectrum.peak().unwrap();
+        assert_eq!(frequency, 50.0);
+        assert!((magnitude - 1000.0).abs() < 1.0, "{magnitude}");
+        assert!((spectrum.magnitudes[0] - 200.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn separates_two_tones() {
+        let samples: Vec<i16> = sine(1000, 60.0, 500.0, 0.0)
+            .into_iter()
+            .zip(sine(1000, 180.0, 100.0, 0.0))
+            .map(|(a, b)| a + b)
+            .collect();
+        let spectrum = Spectrum::with_window(&samples, 1000.0, Window::Rectangular).unwrap();
+        let peaks = spectrum.peaks(2);
+        assert_eq!(peaks[0].0, 60.0);
+        assert_eq!(peaks[1].0, 180.0);
+        assert!((spectrum.magnitude_at(180.0).unwrap() - 100.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn uses_the_achieved_rate_of_a_capture() {
+        let samples = sine(100, 100.0, 1000.0, 0.0);
+        let timing = CaptureTiming {
+            samples: 100,
+            duration: Duration::from_millis(99),
+            nominal_interval: Duration::from_millis(1),
+            min_interval: Duration::from_millis(1),
+            max_interval: Duration::from_millis(1),
+            missed: 0,
+        };
+        let spectrum = Spectrum::from_capture(&samples, &timing).unwrap();
+        assert!((spectrum.bin_width - 10.0).abs() < 1e-3);
+        assert!(Spectrum::from_samples(&samples[..1], 1000.0).is_none());
+    }
+}
@@ -0,0 +1,86 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, Ads1119Err, InputSelection};
+
+/// Summary of a block of samples, in raw ADC codes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Statistics {
+    pub min: i16,
+    pub max: i16,
+    pub mean: f32,
+    /// Population standard deviation
+    pub std_dev: f32,
+    /// Number of samples
+    pub count: usize,
+}
+
+impl Statistics {
+    /// Compute the statistics of the given samples, or `None` if there are none
+    pub fn from_samples(samples: &[i16]) -> Option<Self> {
+        let min = *samples.iter().min()?;
+        let max = *samples.iter().max()?;
+        let count = samples.len();
+
+        let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / count as f64;
+        let variance = samples
+            .iter()
+            .map(|&s| (s as f64 - mean) * (s as f64 - mean))
+            .sum::<f64>()
+            / count as f64;
+
+        Some(Statistics {
+            min,
+            max,
+            mean: mean as f32,
+            std_dev: variance.sqrt() as f32,
+            count,
+        })
+    }
+
+    /// Difference between the largest and smallest sample
+    pub fn peak_to_peak(&self) -> u16 {
+        self.max.abs_diff(self.min)
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Take `n` single-shot conversions of the given input and summarize them.
+    ///
+    /// Handy for quantifying the noise of a board, e.g. on a shorted or DC input.
+    /// Returns [Ads1119Err::NoSamples] if `n` is 0.
+    pub fn measure_statistics(
+        &mut self,
+        input: &InputSelection,
+        n: usize,
+    ) -> Result<Statistics, Ads1119Err<I2C::Error>> {
+        let samples = self.read_samples(input.bits(), n)?;
+        // n > 0 was checked when reading the samples
+        Statistics::from_samples(&samples).ok_or(Ads1119Err::NoSamples)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn statistics_from_samples() {
+        let stats = Statistics::from_samples(&[2, 4, 4, 4, 5, 5, 7, 9]).unwrap();
+        assert_eq!(stats.min, 2);
+        assert_eq!(stats.max, 9);
+        assert_eq!(stats.count, 8);
+        assert_eq!(stats.peak_to_peak(), 7);
+        assert!((stats.mean - 5.0).abs() < 0.0001);
+        assert!((stats.std_dev - 2.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn statistics_of_full_range() {
+        let stats = Statistics::from_samples(&[i16::MIN, i16::MAX]).unwrap();
+        assert_eq!(stats.peak_to_peak(), u16::MAX);
+        assert_eq!(Statistics::from_samples(&[]), None);
+    }
+}
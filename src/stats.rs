@@ -0,0 +1,202 @@
+//! Counters kept by the driver, so long-running services can report its health.
+//! See [Ads1119::stats].
+
+use core::time::Duration;
+
+#[cfg(doc)]
+use crate::Ads1119;
+use crate::Ads1119Err;
+
+/// Snapshot of the driver counters, returned by [Ads1119::stats]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverStats {
+    /// Conversion results read
+    pub conversions: u64,
+    /// Failed I2C transactions
+    pub i2c_errors: u64,
+    /// Reads retried after an error
+    pub retries: u64,
+    /// Config register rewrites after it no longer held the config written, see
+    /// [crate::scrub]
+    pub config_scrubs: u64,
+    /// Waits for a conversion that timed out
+    pub timeouts: u64,
+    /// Waits for a conversion that succeeded
+    pub waits: u64,
+    /// Status register checks of the successful waits
+    pub total_polls: u64,
+    /// Fewest status register checks of a successful wait, zero before the first one
+    pub min_polls: u32,
+    /// Most status register checks of a successful wait
+    pub max_polls: u32,
+    /// Times measured between two consecutive continuous-mode samples, see
+    /// [Ads1119::samples]
+    pub intervals: u64,
+    /// Sum of the measured intervals
+    pub total_interval: Duration,
+    /// Sum of the squares of the measured intervals, in seconds squared
+    pub total_squared_interval: f64,
+    /// Shortest measured interval, zero before the first one
+    pub min_interval: Duration,
+    /// Longest measured interval
+    pub max_interval: Duration,
+}
+
+impl DriverStats {
+    /// Average number of status register checks of the successful waits
+    pub fn average_polls(&self) -> Option<f32> {
+        (self.waits > 0).then(|| self.total_polls as f32 / self.waits as f32)
+    }
+
+    /// Mean time between two continuous-mode samples, from the times they were read on the
+    /// driver's clock
+    pub fn mean_interval(&self) -> Option<Duration> {
+        let intervals = u32::try_from(self.intervals).ok()?;
+        (intervals > 0).then(|| self.total_interval / intervals)
+    }
+
+    /// Samples per second actually achieved in continuous mode
+    pub fn achieved_rate(&self) -> Option<f32> {
+        let interval = self.mean_interval()?;
+        (!interval.is_zero()).then(|| 1.0 / interval.as_secs_f32())
+    }
+
+    /// Standard deviation of the time between two continuous-mode samples. Scheduling
+    /// delays, bus contention and overruns (see [crate::continuous::Samples::overrun]) all
+    /// show up here.
+    #[cfg(feature = "std")]
+    pub fn interval_jitter(&self) -> Option<Duration> {
+        if self.intervals == 0 {
+            return None;
+        }
+        let count = self.intervals as f64;
+        let mean = self.total_interval.as_secs_f64() / count;
+        let variance = (self.total_squared_interval / count - mean * mean).max(0.0);
+        Some(Duration::from_secs_f64(variance.sqrt()))
+    }
+
+    /// Difference between the longest and the shortest time between two continuous-mode
+    /// samples
+    pub fn peak_to_peak_jitter(&self) -> Duration {
+        self.max_interval.saturating_sub(self.min_interval)
+    }
+
+    /// Count the time between two continuous-mode samples
+    #[cfg(feature = "std")]
+    pub(crate) fn record_interval(&mut self, interval: Duration) {
+        self.min_interval = if self.intervals == 0 {
+            interval
+        } else {
+            self.min_interval.min(interval)
+        };
+        self.max_interval = self.max_interval.max(interval);
+        self.total_interval = self.total_interval.saturating_add(interval);
+        self.total_squared_interval += interval.as_secs_f64() * interval.as_secs_f64();
+        self.intervals += 1;
+    }
+
+    /// Count the outcome of a wait for a conversion, which checked the status register
+    /// the returned number of times
+    pub(crate) fn record_wait<E>(&mut self, result: &Result<u32, Ads1119Err<E>>) {
+        match result {
+            Ok(polls) => {
+                self.min_polls = if self.waits == 0 {
+                    *polls
+                } else {
+                    self.min_polls.min(*polls)
+                };
+                self.max_polls = self.max_polls.max(*polls);
+                self.total_polls += u64::from(*polls);
+                self.waits += 1;
+            }
+            Err(Ads1119Err::ConversionTimeout(_)) => self.timeouts += 1,
+            Err(Ads1119Err::I2CError { .. }) => self.i2c_errors += 1,
+            Err(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{
+        expect_oneshot_read, expect_read_data, expect_read_status, expect_start_sync,
+        expect_write_config,
+    };
+    use crate::wait::BusyPoll;
+    use crate::{Ads1119, InputSelection, STATUS_CONV_RDY};
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+    const NOT_READY_STATUS: u8 = !STATUS_CONV_RDY;
+
+    #[test]
+    fn counts_conversions_polls_and_errors() {
+        let input = InputSelection::AN0SingleEnded;
+        let mut transactions = expect_oneshot_read(DEVICE_ADDRESS, &input, 1);
+        transactions.extend([
+            expect_write_config(DEVICE_ADDRESS, input.bits()),
+            expect_start_sync(DEVICE_ADDRESS),
+            expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            expect_read_status(DEVICE_ADDRESS, NOT_READY_STATUS),
+            expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY),
+            expect_read_data(DEVICE_ADDRESS, 2),
+            expect_start_sync(DEVICE_ADDRESS).with_error(ErrorKind::Other),
+        ]);
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.set_wait_strategy(BusyPoll);
+
+        assert_eq!(device.stats().average_polls(), None);
+        device.read_input_oneshot(&input).unwrap();
+        device.read_input_oneshot(&input).unwrap();
+        assert!(device.start_sync().is_err());
+
+        let stats = device.stats();
+        assert_eq!(stats.conversions, 2);
+        assert_eq!(stats.i2c_errors, 1);
+        assert_eq!(stats.timeouts, 0);
+        assert_eq!(stats.waits, 2);
+        assert_eq!((stats.min_polls, stats.max_polls), (1, 3));
+        assert_eq!(stats.average_polls(), Some(2.0));
+
+        device.reset_stats();
+        assert_eq!(device.stats(), DriverStats::default());
+        device.destroy().done();
+    }
+
+    #[test]
+    fn counts_the_intervals_of_continuous_samples() {
+        let mut transactions = vec![];
+        for value in 0..4 {
+            transactions.push(expect_read_status(DEVICE_ADDRESS, STATUS_CONV_RDY));
+            transactions.push(expect_read_data(DEVICE_ADDRESS, value));
+        }
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+        device.set_wait_strategy(BusyPoll);
+
+        assert_eq!(device.stats().mean_interval(), None);
+        assert_eq!(device.samples().take(4).count(), 4);
+        // the first sample has nothing to be measured against
+        assert_eq!(device.stats().intervals, 3);
+        device.destroy().done();
+    }
+
+    #[test]
+    fn measures_the_interval_jitter() {
+        let mut stats = DriverStats::default();
+        for ms in [10, 12, 8] {
+            stats.record_interval(Duration::from_millis(ms));
+        }
+        assert_eq!(stats.mean_interval(), Some(Duration::from_millis(10)));
+        assert_eq!(stats.achieved_rate(), Some(100.0));
+        assert_eq!(
+            (stats.min_interval, stats.max_interval),
+            (Duration::from_millis(8), Duration::from_millis(12))
+        );
+        assert_eq!(stats.peak_to_peak_jitter(), Duration::from_millis(4));
+        let jitter = stats.interval_jitter().unwrap().as_secs_f64();
+        assert!((jitter - (8.0f64 / 3.0).sqrt() / 1000.0).abs() < 1e-9);
+    }
+}
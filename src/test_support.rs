@@ -0,0 +1,153 @@
+//! Builders of the I2C transactions the driver makes, for unit-testing code that uses
+//! [crate::Ads1119] with `embedded-hal-mock`.
+//!
+//! Enabled with the `test-support` feature.
+//!
+//! ```
+//! use ads1119::test_support::expect_oneshot_read;
+//! use ads1119::{Ads1119, InputSelection};
+//! use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+//!
+//! let input = InputSelection::AN0SingleEnded;
+//! let mut device = Ads1119::new(I2cMock::new(&expect_oneshot_read(0x40, &input, 1234)), 0x40);
+//! assert_eq!(device.read_input_oneshot(&input).unwrap(), 1234);
+//! device.destroy().done();
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTransaction;
+
+use crate::clock::Clock;
+use crate::{Command, InputSelection, Register, STATUS_CONV_RDY};
+
+/// See [crate::Ads1119::write_config]
+pub fn expect_write_config(address: u8, value: u8) -> I2cTransaction {
+    I2cTransaction::write(
+        address,
+        vec![Command::WriteRegister(Register::Config).encode(), value],
+    )
+}
+
+/// See [crate::Ads1119::read_config]
+pub fn expect_read_config(address: u8, value: u8) -> I2cTransaction {
+    I2cTransaction::write_read(
+        address,
+        vec![Command::ReadRegister(Register::Config).encode()],
+        vec![value],
+    )
+}
+
+/// See [crate::Ads1119::read_status]
+pub fn expect_read_status(address: u8, status: u8) -> I2cTransaction {
+    I2cTransaction::write_read(
+        address,
+        vec![Command::ReadRegister(Register::Status).encode()],
+        vec![status],
+    )
+}
+
+/// See [crate::Ads1119::start_sync]
+pub fn expect_start_sync(address: u8) -> I2cTransaction {
+    I2cTransaction::write(address, vec![Command::StartSync.encode()])
+}
+
+/// See [crate::Ads1119::power_down]
+pub fn expect_power_down(address: u8) -> I2cTransaction {
+    I2cTransaction::write(address, vec![Command::PowerDown.encode()])
+}
+
+/// See [crate::Ads1119::reset]
+pub fn expect_reset(address: u8) -> I2cTransaction {
+    I2cTransaction::write(address, vec![Command::Reset.encode()])
+}
+
+/// See [crate::Ads1119::read_data]
+pub fn expect_read_data(address: u8, value: i16) -> I2cTransaction {
+    I2cTransaction::write_read(
+        address,
+        vec![Command::ReadData.encode()],
+        value.to_be_bytes().to_vec(),
+    )
+}
+
+/// A single-shot conversion with the given config register value that is ready on the
+/// first status poll and returns `value`
+pub fn expect_oneshot_read_config(address: u8, config: u8, value: i16) -> Vec<I2cTransaction> {
+    vec![
+        expect_write_config(address, config),
+        expect_start_sync(address),
+        expect_read_status(address, STATUS_CONV_RDY),
+        expect_read_data(address, value),
+    ]
+}
+
+/// Single-shot conversions in a row with the same config register value, written once, each
+/// ready on the first status poll and returning the next of `values`
+pub fn expect_repeated_oneshot_reads(
+    address: u8,
+    config: u8,
+    values: &[i16],
+) -> Vec<I2cTransaction> {
+    let mut transactions = vec![expect_write_config(address, config)];
+    for &value in values {
+        transactions.extend([
+            expect_start_sync(address),
+            expect_read_status(address, STATUS_CONV_RDY),
+            expect_read_data(address, value),
+        ]);
+    }
+    transactions
+}
+
+/// See [crate::Ads1119::read_input_oneshot], with the conversion ready on the first
+/// status poll
+pub fn expect_oneshot_read(address: u8, input: &InputSelection, value: i16) -> Vec<I2cTransaction> {
+    expect_oneshot_read_config(address, input.bits(), value)
+}
+
+/// `DelayNs` recording the delays instead of waiting, to check the waits of a driver given
+/// it with [crate::Ads1119::set_delay]. Its clones share the record.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingDelay(Arc<Mutex<Vec<u32>>>);
+
+impl RecordingDelay {
+    /// The delays so far, in ns, without the zero ones (e.g. of [crate::wait::BusyPoll])
+    pub fn delays(&self) -> Vec<u32> {
+        let delays = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        delays.iter().copied().filter(|&ns| ns > 0).collect()
+    }
+}
+
+impl DelayNs for RecordingDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).push(ns);
+    }
+}
+
+/// [Clock] reading `step` later each time it's read, starting from zero, for deterministic
+/// timeouts and timings. Set it up as a `static` to give it to
+/// [crate::Ads1119::set_clock], one per test as they run in parallel.
+#[derive(Debug)]
+pub struct SteppingClock {
+    step_ns: u64,
+    now_ns: AtomicU64,
+}
+
+impl SteppingClock {
+    pub const fn new(step: Duration) -> Self {
+        SteppingClock {
+            step_ns: step.as_nanos() as u64,
+            now_ns: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Clock for SteppingClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.now_ns.fetch_add(self.step_ns, Ordering::Relaxed))
+    }
+}
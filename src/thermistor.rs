@@ -0,0 +1,172 @@
+//! Temperature from an NTC thermistor in a resistor divider.
+
+use embedded_hal::i2c::I2c;
+
+use crate::{Ads1119, Ads1119Err, Config, InputSelection};
+
+const KELVIN_OFFSET: f32 = 273.15;
+
+/// Resistance/temperature model of an NTC thermistor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NtcModel {
+    /// Beta equation: `1/T = 1/T0 + ln(R/R0) / beta`
+    Beta {
+        beta: f32,
+        /// Resistance at `t0`, in ohms
+        r0: f32,
+        /// Reference temperature, in °C (usually 25)
+        t0: f32,
+    },
+    /// Steinhart–Hart equation: `1/T = a + b*ln(R) + c*ln(R)^3`, T in kelvin
+    SteinhartHart { a: f32, b: f32, c: f32 },
+}
+
+impl NtcModel {
+    /// Temperature, in °C, for the given thermistor resistance in ohms
+    pub fn temperature(&self, resistance: f32) -> f32 {
+        let inverse_kelvin = match *self {
+            NtcModel::Beta { beta, r0, t0 } => {
+                1.0 / (t0 + KELVIN_OFFSET) + (resistance / r0).ln() / beta
+            }
+            NtcModel::SteinhartHart { a, b, c } => {
+                let ln_r = resistance.ln();
+                a + b * ln_r + c * ln_r * ln_r * ln_r
+            }
+        };
+        1.0 / inverse_kelvin - KELVIN_OFFSET
+    }
+}
+
+/// Where the thermistor sits in the divider. The ADC measures the middle node.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DividerPosition {
+    /// Thermistor between the ADC input and ground, series resistor to the excitation
+    #[default]
+    LowSide,
+    /// Thermistor between the excitation and the ADC input, series resistor to ground
+    HighSide,
+}
+
+/// How the divider is excited
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Excitation {
+    /// The divider is powered from the external reference (REFP), so the reading is
+    /// taken ratiometrically and the excitation voltage doesn't need to be known
+    Ratiometric,
+    /// The divider is powered from a known voltage, in volts, and read against the internal
+    /// reference. The input's calibration is applied.
+    Voltage(f32),
+}
+
+/// A thermistor divider on one input of the ADS1119
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thermistor {
+    pub input: InputSelection,
+    pub model: NtcModel,
+    /// Resistance of the fixed divider resistor, in ohms
+    pub series_resistance: f32,
+    pub position: DividerPosition,
+    pub excitation: Excitation,
+}
+
+impl Thermistor {
+    /// Thermistor resistance, in ohms, for the given divider output as a fraction of the
+    /// excitation. Returns `None` for fractions outside of (0, 1), which indicate an open or
+    /// shorted sensor.
+    pub fn resistance(&self, ratio: f32) -> Option<f32> {
+        if ratio <= 0.0 || ratio >= 1.0 {
+            return None;
+        }
+        Some(match self.position {
+            DividerPosition::LowSide => self.series_resistance * ratio / (1.0 - ratio),
+            DividerPosition::HighSide => self.series_resistance * (1.0 - ratio) / ratio,
+        })
+    }
+
+    /// Temperature, in °C, for the given divider output as a fraction of the excitation
+    pub fn temperature_from_ratio(&self, ratio: f32) -> Option<f32> {
+        self.resistance(ratio)
+            .map(|resistance| self.model.temperature(resistance))
+    }
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Read the thermistor's input and convert it to a temperature, in °C.
+    ///
+    /// Returns [Ads1119Err::SensorOutOfRange] if the divider output is at either end of
+    /// its range, which usually means an open or shorted sensor.
+    pub fn read_thermistor(
+        &mut self,
+        thermistor: &Thermistor,
+    ) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let ratio = match thermistor.excitation {
+            Excitation::Ratiometric => {
+                self.read_ratiometric(&Config::new(thermistor.input.clone()))?
+            }
+            Excitation::Voltage(volts) => self.read_input_voltage(&thermistor.input)? / volts,
+        };
+        thermistor
+            .temperature_from_ratio(ratio)
+            .ok_or(Ads1119Err::SensorOutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // typical 10k NTC
+    const BETA: NtcModel = NtcModel::Beta {
+        beta: 3950.0,
+        r0: 10_000.0,
+        t0: 25.0,
+    };
+
+    fn thermistor(position: DividerPosition) -> Thermistor {
+        Thermistor {
+            input: InputSelection::AN0SingleEnded,
+            model: BETA,
+            series_resistance: 10_000.0,
+            position,
+            excitation: Excitation::Ratiometric,
+        }
+    }
+
+    #[test]
+    fn beta_model_at_reference_temperature() {
+        assert!((BETA.temperature(10_000.0) - 25.0).abs() < 0.001);
+        // NTC: resistance falls as temperature rises
+        assert!(BETA.temperature(5_000.0) > 25.0);
+    }
+
+    #[test]
+    fn steinhart_hart_model() {
+        // coefficients of a 10k NTC, ~25°C at 10k
+        let model = NtcModel::SteinhartHart {
+            a: 1.125_308_9e-3,
+            b: 2.347_118_7e-4,
+            c: 8.566_3e-8,
+        };
+        assert!((model.temperature(10_000.0) - 25.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn divider_resistance() {
+        let low = thermistor(DividerPosition::LowSide);
+        assert!((low.resistance(0.5).unwrap() - 10_000.0).abs() < 0.01);
+        assert!((low.resistance(0.25).unwrap() - 3_333.333).abs() < 0.01);
+        let high = thermistor(DividerPosition::HighSide);
+        assert!((high.resistance(0.25).unwrap() - 30_000.0).abs() < 0.01);
+        assert!((high.temperature_from_ratio(0.5).unwrap() - 25.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn open_or_shorted_sensor() {
+        let low = thermistor(DividerPosition::LowSide);
+        assert_eq!(low.temperature_from_ratio(0.0), None);
+        assert_eq!(low.temperature_from_ratio(1.0), None);
+    }
+}
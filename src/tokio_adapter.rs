@@ -0,0 +1,245 @@
+//! Async wrapper of the blocking driver for Tokio applications, e.g. around `I2cdev` on Linux.
+//!
+//! Enabled with the `tokio` feature.
+//!
+//! A one-shot read polls for up to a second, which would stall the runtime if called from a
+//! task. [Ads1119Tokio] runs each call on Tokio's blocking thread pool instead.
+//! For devices behind an `embedded-hal-async` bus, see the `async` feature.
+
+use std::panic;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use embedded_hal::i2c::I2c;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::reading::Reading;
+use crate::sampler::{SamplerConfig, SamplerResult};
+use crate::{Ads1119, Ads1119Err, InputSelection};
+
+/// Async adapter of [Ads1119], running the blocking calls with `spawn_blocking`.
+///
+/// Clones share the same device, and calls are serialized, so one-shot reads from
+/// different tasks don't interfere.
+pub struct Ads1119Tokio<I2C> {
+    device: Arc<Mutex<Ads1119<I2C>>>,
+}
+
+impl<I2C> Clone for Ads1119Tokio<I2C> {
+    fn clone(&self) -> Self {
+        Ads1119Tokio {
+            device: self.device.clone(),
+        }
+    }
+}
+
+impl<I2C> Ads1119Tokio<I2C>
+where
+    I2C: I2c + Send + 'static,
+    I2C::Error: Send + 'static,
+{
+    pub fn new(device: Ads1119<I2C>) -> Self {
+        Ads1119Tokio {
+            device: Arc::new(Mutex::new(device)),
+        }
+    }
+
+    /// Run a closure with exclusive access to the blocking driver, on the blocking thread pool.
+    ///
+    /// This is how calls without an async counterpart here can be made.
+    pub async fn with_device<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(&mut Ads1119<I2C>) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let device = self.device.clone();
+        let result = tokio::task::spawn_blocking(move || f(&mut lock(&device))).await;
+        match result {
+            Ok(value) => value,
+            // surface a panic of the closure in the calling task
+            Err(e) => panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    /// See [Ads1119::read_input_oneshot]
+    pub async fn read_input_oneshot(
+        &self,
+        input: &InputSelection,
+    ) -> Result<i16, Ads1119Err<I2C::Error>> {
+        let input = input.clone();
+        self.with_device(move |device| device.read_input_oneshot(&input))
+            .await
+    }
+
+    /// See [Ads1119::read_input_voltage]
+    pub async fn read_input_voltage(
+        &self,
+        input: &InputSelection,
+    ) -> Result<f32, Ads1119Err<I2C::Error>> {
+        let input = input.clone();
+        self.with_device(move |device| device.read_input_voltage(&input))
+            .await
+    }
+
+    /// See [Ads1119::read_config]
+    pub async fn read_config(&self) -> Result<u8, I2C::Error> {
+        self.with_device(|device| device.read_config()).await
+    }
+
+    /// See [Ads1119::write_config]
+    pub async fn write_config(&self, value: u8) -> Result<(), I2C::Error> {
+        self.with_device(move |device| device.write_config(value))
+            .await
+    }
+
+    /// See [Ads1119::read_status]
+    pub async fn read_status(&self) -> Result<u8, I2C::Error> {
+        self.with_device(|device| device.read_status()).await
+    }
+
+    /// See [Ads1119::reset]
+    pub async fn reset(&self) -> Result<(), I2C::Error> {
+        self.with_device(|device| device.reset()).await
+    }
+
+    /// See [Ads1119::power_down]
+    pub async fn power_down(&self) -> Result<(), I2C::Error> {
+        self.with_device(|device| device.power_down()).await
+    }
+
+    /// Spawn a task that reads with [Ads1119::read] every interval and publishes the readings
+    /// on a broadcast channel, so several consumers share one stream of samples.
+    ///
+    /// Subscribe to the returned sender for each consumer; a consumer that falls more than
+    /// `capacity` readings behind misses the oldest ones (see [broadcast::error::RecvError::Lagged]).
+    /// Other calls on the adapter are serialized with the readings as usual.
+    /// A failed reading is skipped; the task ends after
+    /// [SamplerConfig::max_consecutive_errors] of them in a row, otherwise it runs until
+    /// aborted. Must be called from within a Tokio runtime.
+    pub fn spawn_broadcast(
+        &self,
+        config: SamplerConfig,
+        capacity: usize,
+    ) -> (
+        JoinHandle<SamplerResult<I2C::Error>>,
+        broadcast::Sender<Reading>,
+    ) {
+        let (sender, _) = broadcast::channel(capacity);
+        let adapter = self.clone();
+        let readings = sender.clone();
+        let task = tokio::spawn(async move {
+            let mut interval = time::interval(config.interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let mut errors = 0;
+            loop {
+                interval.tick().await;
+                let reading_config = config.config.clone();
+                match adapter
+                    .with_device(move |device| device.read(&reading_config))
+                    .await
+                {
+                    Ok(reading) => {
+                        errors = 0;
+                        // without subscribers the reading is dropped
+                        let _ = readings.send(reading);
+                    }
+                    Err(err) => {
+                        errors += 1;
+                        if errors >= config.max_consecutive_errors {
+                            return Err(err);
+                        }
+                    }
+                }
+            }
+        });
+        (task, sender)
+    }
+
+    /// Get the blocking driver back.
+    ///
+    /// Returns `Err(self)` if other clones of the adapter are still around.
+    pub fn into_inner(self) -> Result<Ads1119<I2C>, Self> {
+        match Arc::try_unwrap(self.device) {
+            Ok(device) => Ok(device.into_inner().unwrap_or_else(|e| e.into_inner())),
+            Err(device) => Err(Ads1119Tokio { device }),
+        }
+    }
+}
+
+// a panic in an earlier call doesn't leave the driver in a bad state
+fn lock<I2C>(device: &Mutex<Ads1119<I2C>>) -> MutexGuard<'_, Ads1119<I2C>> {
+    device.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_oneshot_read;
+    use crate::{CmdFlags, Config, RegSelectFlags, STATUS_CONV_RDY};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use std::time::Duration;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn runs_calls_on_the_blocking_pool() {
+        let input = InputSelection::AN3SingleEnded;
+        let device = Ads1119::new(
+            I2cMock::new(&[
+                I2cTransaction::write(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::WREG | RegSelectFlags::CONFIG, input.bits()],
+                ),
+                I2cTransaction::write(DEVICE_ADDRESS, vec![CmdFlags::START_SYNC]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::STATUS],
+                    vec![STATUS_CONV_RDY],
+                ),
+                I2cTransaction::write_read(DEVICE_ADDRESS, vec![CmdFlags::RDATA], vec![0x12, 0x34]),
+                I2cTransaction::write_read(
+                    DEVICE_ADDRESS,
+                    vec![CmdFlags::RREG | RegSelectFlags::CONFIG],
+                    vec![input.bits()],
+                ),
+            ]),
+            DEVICE_ADDRESS,
+        );
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let adapter = Ads1119Tokio::new(device);
+        runtime.block_on(async {
+            assert_eq!(adapter.read_input_oneshot(&input).await.unwrap(), 0x1234);
+            assert_eq!(adapter.clone().read_config().await.unwrap(), input.bits());
+        });
+        adapter.into_inner().ok().unwrap().destroy().done();
+    }
+
+    #[test]
+    fn broadcasts_readings_to_every_subscriber() {
+        let input = InputSelection::AN0SingleEnded;
+        let device = Ads1119::new(
+            I2cMock::new(&expect_oneshot_read(DEVICE_ADDRESS, &input, 100)),
+            DEVICE_ADDRESS,
+        );
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let adapter = Ads1119Tokio::new(device);
+        runtime.block_on(async {
+            let config = SamplerConfig::new(Config::new(input.clone()))
+                .with_interval(Duration::from_secs(3600));
+            let (task, sender) = adapter.spawn_broadcast(config, 4);
+            let mut logger = sender.subscribe();
+            let mut alarms = sender.subscribe();
+            assert_eq!(logger.recv().await.unwrap().raw, 100);
+            assert_eq!(alarms.recv().await.unwrap().raw, 100);
+            task.abort();
+            assert!(task.await.unwrap_err().is_cancelled());
+        });
+        adapter.into_inner().ok().unwrap().destroy().done();
+    }
+}
@@ -0,0 +1,245 @@
+//! Unit-safe voltages, so readings can't be confused with raw codes (or volts with
+//! millivolts) in application math.
+//!
+//! With the `uom` feature, voltages also convert to and from `uom`'s `ElectricPotential`,
+//! for applications that already use its dimensional analysis.
+
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::{rdata_to_voltage, Gain};
+#[cfg(feature = "uom")]
+use uom::si::{
+    electric_potential::{millivolt, volt},
+    f32::ElectricPotential,
+};
+
+/// A voltage in volts
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Volts(pub f32);
+
+/// A voltage in whole millivolts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Millivolts(pub i32);
+
+impl Volts {
+    /// Voltage of a raw conversion result read with the given gain, see [rdata_to_voltage]
+    pub fn from_rdata(raw_data: i16, gain: Gain) -> Self {
+        Volts(rdata_to_voltage(raw_data, gain))
+    }
+}
+
+impl Millivolts {
+    /// Voltage of a raw conversion result read with the given gain, rounded to the
+    /// nearest millivolt
+    pub fn from_rdata(raw_data: i16, gain: Gain) -> Self {
+        Volts::from_rdata(raw_data, gain).into()
+    }
+}
+
+impl From<Millivolts> for Volts {
+    fn from(millivolts: Millivolts) -> Self {
+        Volts(millivolts.0 as f32 / 1000.0)
+    }
+}
+
+impl From<Volts> for Millivolts {
+    /// Rounded to the nearest millivolt, saturating at the range of `i32`
+    fn from(volts: Volts) -> Self {
+        let millivolts = volts.0 * 1000.0;
+        // `as` truncates towards zero, so this rounds half away from zero
+        let half = if millivolts < 0.0 { -0.5 } else { 0.5 };
+        Millivolts((millivolts + half) as i32)
+    }
+}
+
+impl Add for Volts {
+    type Output = Volts;
+
+    fn add(self, rhs: Volts) -> Volts {
+        Volts(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Volts {
+    type Output = Volts;
+
+    fn sub(self, rhs: Volts) -> Volts {
+        Volts(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Volts {
+    type Output = Volts;
+
+    fn neg(self) -> Volts {
+        Volts(-self.0)
+    }
+}
+
+impl Mul<f32> for Volts {
+    type Output = Volts;
+
+    fn mul(self, rhs: f32) -> Volts {
+        Volts(self.0 * rhs)
+    }
+}
+
+impl Div<f32> for Volts {
+    type Output = Volts;
+
+    fn div(self, rhs: f32) -> Volts {
+        Volts(self.0 / rhs)
+    }
+}
+
+/// Ratio of two voltages
+impl Div for Volts {
+    type Output = f32;
+
+    fn div(self, rhs: Volts) -> f32 {
+        self.0 / rhs.0
+    }
+}
+
+impl Add for Millivolts {
+    type Output = Millivolts;
+
+    fn add(self, rhs: Millivolts) -> Millivolts {
+        Millivolts(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Millivolts {
+    type Output = Millivolts;
+
+    fn sub(self, rhs: Millivolts) -> Millivolts {
+        Millivolts(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Millivolts {
+    type Output = Millivolts;
+
+    fn neg(self) -> Millivolts {
+        Millivolts(-self.0)
+    }
+}
+
+impl Mul<i32> for Millivolts {
+    type Output = Millivolts;
+
+    fn mul(self, rhs: i32) -> Millivolts {
+        Millivolts(self.0 * rhs)
+    }
+}
+
+impl Div<i32> for Millivolts {
+    type Output = Millivolts;
+
+    fn div(self, rhs: i32) -> Millivolts {
+        Millivolts(self.0 / rhs)
+    }
+}
+
+impl fmt::Display for Volts {
+    /// The value with the unit, e.g. `1.024 V`. The precision applies to the value.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)?;
+        f.write_str(" V")
+    }
+}
+
+impl fmt::Display for Millivolts {
+    /// The value with the unit, e.g. `1024 mV`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)?;
+        f.write_str(" mV")
+    }
+}
+
+/// Voltage of a raw conversion result read with the given gain, see [rdata_to_voltage]
+#[cfg(feature = "uom")]
+pub fn rdata_to_potential(raw_data: i16, gain: Gain) -> ElectricPotential {
+    Volts::from_rdata(raw_data, gain).into()
+}
+
+#[cfg(feature = "uom")]
+impl From<Volts> for ElectricPotential {
+    fn from(volts: Volts) -> Self {
+        ElectricPotential::new::<volt>(volts.0)
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<Millivolts> for ElectricPotential {
+    fn from(millivolts: Millivolts) -> Self {
+        ElectricPotential::new::<millivolt>(millivolts.0 as f32)
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<ElectricPotential> for Volts {
+    fn from(potential: ElectricPotential) -> Self {
+        Volts(potential.get::<volt>())
+    }
+}
+
+#[cfg(all(feature = "uom", feature = "std"))]
+impl<I2C> crate::Ads1119<I2C>
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    /// Same as [crate::Ads1119::read_input_voltage], as an `ElectricPotential`
+    pub fn read_input_potential(
+        &mut self,
+        input: &crate::InputSelection,
+    ) -> Result<ElectricPotential, crate::Ads1119Err<I2C::Error>> {
+        self.read_input_voltage(input)
+            .map(|volts| Volts(volts).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_between_units() {
+        assert_eq!(Millivolts::from(Volts(1.0245)), Millivolts(1025));
+        assert_eq!(Millivolts::from(Volts(-0.0006)), Millivolts(-1));
+        assert_eq!(Volts::from(Millivolts(-512)), Volts(-0.512));
+        assert_eq!(Millivolts::from_rdata(0x4000, Gain::One), Millivolts(1024));
+        assert_eq!(Millivolts::from_rdata(0x4000, Gain::Four), Millivolts(256));
+    }
+
+    #[test]
+    fn arithmetic_keeps_the_unit() {
+        assert_eq!(Volts(1.5) + Volts(0.5) - Volts(1.0), Volts(1.0));
+        assert_eq!(-(Volts(0.25) * 4.0) / 2.0, Volts(-0.5));
+        assert_eq!(Volts(1.0) / Volts(4.0), 0.25);
+        assert_eq!((Millivolts(100) + Millivolts(50)) * 2 / 3, Millivolts(100));
+        assert_eq!(-(Millivolts(10) - Millivolts(15)), Millivolts(5));
+    }
+
+    #[cfg(feature = "uom")]
+    #[test]
+    fn converts_to_quantities() {
+        let potential = rdata_to_potential(0x4000, Gain::Four);
+        assert!((potential.get::<millivolt>() - 256.0).abs() < 0.01);
+        assert_eq!(
+            Volts::from(potential),
+            Volts::from_rdata(0x4000, Gain::Four)
+        );
+        let potential = ElectricPotential::from(Millivolts(256));
+        assert!((potential.get::<volt>() - 0.256).abs() < 1e-6);
+    }
+
+    #[test]
+    fn displays_the_unit() {
+        assert_eq!(format!("{:.3}", Volts(1.0245)), "1.025 V");
+        assert_eq!(Millivolts(-42).to_string(), "-42 mV");
+    }
+}
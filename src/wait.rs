@@ -0,0 +1,271 @@
+//! How the read paths wait for a conversion to finish, trading latency, CPU time and bus
+//! traffic. See [WaitStrategy].
+
+use core::time::Duration;
+
+use embedded_hal::digital::InputPin;
+
+use crate::READ_INPUT_SLEEP;
+
+/// Decides when the driver checks the status register for a finished conversion.
+///
+/// The driver starts a conversion, waits [WaitStrategy::first_delay], then checks the
+/// status register. While the conversion isn't ready, it waits [WaitStrategy::next_delay]
+/// and checks again, until it times out.
+///
+/// The blocking driver waits through [WaitStrategy::wait]; the async driver waits the
+/// same delays on its timer. Either way, a wait is cut short where the driver's timeout
+/// ends, so a strategy waiting for another signal can ask for `Duration::MAX`.
+pub trait WaitStrategy {
+    /// Time between starting a conversion and the first status check.
+    /// `conversion_time` is the nominal time of a conversion, if the driver knows the data rate.
+    fn first_delay(&mut self, conversion_time: Option<Duration>) -> Duration {
+        let _ = conversion_time;
+        Duration::ZERO
+    }
+
+    /// Time before the next status check, after `checks` of them found the conversion not ready
+    fn next_delay(&mut self, checks: u32) -> Duration;
+
+    /// Wait for `delay` with `sleep`. Strategies that can tell when the conversion is done
+    /// some other way (e.g. [DrdyPin]) can return sooner.
+    fn wait(&mut self, delay: Duration, sleep: &mut dyn FnMut(Duration)) {
+        sleep(delay)
+    }
+}
+
+impl<W: WaitStrategy + ?Sized> WaitStrategy for &mut W {
+    fn first_delay(&mut self, conversion_time: Option<Duration>) -> Duration {
+        W::first_delay(self, conversion_time)
+    }
+
+    fn next_delay(&mut self, checks: u32) -> Duration {
+        W::next_delay(self, checks)
+    }
+
+    fn wait(&mut self, delay: Duration, sleep: &mut dyn FnMut(Duration)) {
+        W::wait(self, delay, sleep)
+    }
+}
+
+/// Check the status register back-to-back: the lowest latency, and the most bus traffic
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BusyPoll;
+
+impl WaitStrategy for BusyPoll {
+    fn next_delay(&mut self, _checks: u32) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// Check the status register at a fixed interval, e.g. the previous default of every 10 ms.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedSleep(pub Duration);
+
+impl Default for FixedSleep {
+    fn default() -> Self {
+        FixedSleep(READ_INPUT_SLEEP)
+    }
+}
+
+impl WaitStrategy for FixedSleep {
+    fn next_delay(&mut self, _checks: u32) -> Duration {
+        self.0
+    }
+}
+
+/// Sleep for the conversion time of the configured data rate plus a 10% margin, then check
+/// the status register once. If the conversion isn't ready yet, or the data rate isn't
+/// known, poll like [FixedSleep].
+///
+/// At 20 SPS, this is one status read per conversion instead of about five.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ComputedWait;
+
+impl WaitStrategy for ComputedWait {
+    fn first_delay(&mut self, conversion_time: Option<Duration>) -> Duration {
+        conversion_time.map_or(Duration::ZERO, |time| time + time / 10)
+    }
+
+    fn next_delay(&mut self, _checks: u32) -> Duration {
+        READ_INPUT_SLEEP
+    }
+}
+
+/// Poll with a delay starting at `initial` and doubling after every check, up to `max`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    /// From 1 ms to 50 ms, the conversion time at the default 20 SPS
+    fn default() -> Self {
+        Backoff {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(50),
+        }
+    }
+}
+
+impl WaitStrategy for Backoff {
+    fn next_delay(&mut self, checks: u32) -> Duration {
+        let doublings = checks.saturating_sub(1).min(31);
+        self.initial
+            .checked_mul(1 << doublings)
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+/// Sleep until shortly before the conversion of the configured data rate should finish,
+/// then poll at short intervals, doubling up to 10 ms if the conversion runs late.
+/// The default strategy.
+///
+/// The first check comes at 90% of the conversion time, to absorb the tolerance of the
+/// device's oscillator; the polling interval starts at 5% of it (at least 100 µs). At
+/// 20 SPS, that's a result within about 2.5 ms of being ready for two or three status reads,
+/// where [FixedSleep] takes up to 10 ms and five reads. Without a known data rate, it polls
+/// like [FixedSleep].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AdaptivePoll {
+    // polling interval of the current conversion, None if its data rate isn't known
+    interval: Option<Duration>,
+}
+
+impl AdaptivePoll {
+    const MIN_INTERVAL: Duration = Duration::from_micros(100);
+}
+
+impl WaitStrategy for AdaptivePoll {
+    fn first_delay(&mut self, conversion_time: Option<Duration>) -> Duration {
+        self.interval = conversion_time.map(|time| (time / 20).max(Self::MIN_INTERVAL));
+        conversion_time.map_or(Duration::ZERO, |time| time * 9 / 10)
+    }
+
+    fn next_delay(&mut self, checks: u32) -> Duration {
+        let Some(interval) = self.interval else {
+            return READ_INPUT_SLEEP;
+        };
+        let doublings = checks.saturating_sub(1).min(31);
+        interval
+            .checked_mul(1 << doublings)
+            .map_or(READ_INPUT_SLEEP, |delay| delay.min(READ_INPUT_SLEEP))
+    }
+}
+
+/// Wait for the DRDY pin to go low before checking the status register, polling the pin
+/// every `pin_poll` (100 µs by default). Only the status check goes over the bus.
+///
+/// This is for the blocking driver: the async driver doesn't call [WaitStrategy::wait],
+/// so it would only poll the status register every `pin_poll`. With an async pin, await
+/// `embedded_hal_async::digital::Wait::wait_for_low` and read the data directly instead.
+pub struct DrdyPin<P> {
+    pin: P,
+    pin_poll: Duration,
+}
+
+impl<P: InputPin> DrdyPin<P> {
+    pub fn new(pin: P) -> Self {
+        DrdyPin {
+            pin,
+            pin_poll: Duration::from_micros(100),
+        }
+    }
+
+    pub fn with_pin_poll(mut self, pin_poll: Duration) -> Self {
+        self.pin_poll = pin_poll;
+        self
+    }
+
+    /// Get the pin back
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: InputPin> WaitStrategy for DrdyPin<P> {
+    fn first_delay(&mut self, _conversion_time: Option<Duration>) -> Duration {
+        // until the pin goes low or the driver times out
+        Duration::MAX
+    }
+
+    fn next_delay(&mut self, _checks: u32) -> Duration {
+        self.pin_poll
+    }
+
+    fn wait(&mut self, delay: Duration, sleep: &mut dyn FnMut(Duration)) {
+        let mut waited = Duration::ZERO;
+        // a pin error falls back to checking the status register
+        while waited < delay && self.pin.is_high().unwrap_or(false) {
+            sleep(self.pin_poll);
+            waited += self.pin_poll;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use embedded_hal_mock::eh1::pin::{Mock as PinMock, State, Transaction as PinTransaction};
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let mut backoff = Backoff {
+            initial: Duration::from_millis(2),
+            max: Duration::from_millis(10),
+        };
+        let delays: Vec<_> = (1..=5).map(|checks| backoff.next_delay(checks)).collect();
+        assert_eq!(
+            delays,
+            [2, 4, 8, 10, 10].map(Duration::from_millis).to_vec()
+        );
+        assert_eq!(backoff.next_delay(u32::MAX), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn adaptive_poll_closes_in_on_the_conversion_time() {
+        let mut adaptive = AdaptivePoll::default();
+        let first = adaptive.first_delay(Some(Duration::from_millis(50)));
+        assert_eq!(first, Duration::from_millis(45));
+        let delays: Vec<_> = (1..=4).map(|checks| adaptive.next_delay(checks)).collect();
+        assert_eq!(
+            delays,
+            [2_500, 5_000, 10_000, 10_000]
+                .map(Duration::from_micros)
+                .to_vec()
+        );
+
+        // 1000 SPS polls at the minimum interval
+        adaptive.first_delay(Some(Duration::from_millis(1)));
+        assert_eq!(adaptive.next_delay(1), Duration::from_micros(100));
+
+        assert_eq!(adaptive.first_delay(None), Duration::ZERO);
+        assert_eq!(adaptive.next_delay(1), READ_INPUT_SLEEP);
+    }
+
+    #[test]
+    fn computed_wait_adds_margin() {
+        let mut computed = ComputedWait;
+        assert_eq!(
+            computed.first_delay(Some(Duration::from_millis(50))),
+            Duration::from_millis(55)
+        );
+        assert_eq!(computed.first_delay(None), Duration::ZERO);
+    }
+
+    #[test]
+    fn drdy_pin_waits_for_low() {
+        let pin = PinMock::new(&[
+            PinTransaction::get(State::High),
+            PinTransaction::get(State::High),
+            PinTransaction::get(State::Low),
+        ]);
+        let mut drdy = DrdyPin::new(pin);
+        let mut slept = Duration::ZERO;
+        let delay = drdy.first_delay(None);
+        drdy.wait(delay, &mut |time| slept += time);
+        assert_eq!(slept, Duration::from_micros(200));
+        drdy.into_inner().done();
+    }
+}
@@ -0,0 +1,156 @@
+//! Heuristic detection of disconnected inputs, for troubleshooting harnesses.
+//!
+//! The ADS1119 inputs are high impedance, so a floating input picks up noise and drifts
+//! (or sits at a rail) instead of reading a steady value. Each input is compared to the
+//! noise of the inputs shorted to mid-supply, which is what the converter alone produces.
+
+use embedded_hal::i2c::I2c;
+
+use crate::clipping::Clipping;
+use crate::statistics::Statistics;
+use crate::{Ads1119, Ads1119Err, InputSelection};
+
+/// An input is flagged as floating if its noise is this many times that of the shorted inputs
+pub const FLOATING_NOISE_FACTOR: f32 = 10.0;
+
+/// Smallest noise used as the reference, in LSB, so a noiseless baseline doesn't flag
+/// every input
+const MIN_BASELINE_NOISE: f32 = 1.0;
+
+/// Likely wiring of an input, see [Ads1119::check_wiring]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WiringStatus {
+    /// Steady readings within the range
+    Connected,
+    /// Noisy or drifting readings: likely disconnected
+    Floating,
+    /// Readings at full scale: open input pulled to a rail, or a short to a supply
+    OutOfRange,
+}
+
+/// Result of [Ads1119::check_wiring] for one input
+#[derive(Clone, Debug, PartialEq)]
+pub struct WiringReport {
+    pub input: InputSelection,
+    pub status: WiringStatus,
+    /// Statistics of the input's samples, in raw codes
+    pub statistics: Statistics,
+    /// Mean of the second half of the samples minus the mean of the first half, in raw codes
+    pub drift: f32,
+}
+
+impl<I2C> Ads1119<I2C>
+where
+    I2C: I2c,
+{
+    /// Take `n` single-shot conversions of the shorted inputs, then of each of `inputs`
+    /// (at the default gain and data rate), and report which inputs look disconnected.
+    ///
+    /// An input is [WiringStatus::OutOfRange] if any sample clips, and [WiringStatus::Floating]
+    /// if its standard deviation or its drift is over [FLOATING_NOISE_FACTOR] times the
+    /// standard deviation of the shorted inputs. This is a heuristic: a noisy sensor can look
+    /// floating, and a floating input next to a driven one can look connected.
+    /// Returns [Ads1119Err::NoSamples] if `n` is less than 2.
+    pub fn check_wiring(
+        &mut self,
+        inputs: &[InputSelection],
+        n: usize,
+    ) -> Result<Vec<WiringReport>, Ads1119Err<I2C::Error>> {
+        if n < 2 {
+            return Err(Ads1119Err::NoSamples);
+        }
+        let baseline = self.read_samples(InputSelection::ShortedToMidSupply.bits(), n)?;
+        let baseline = Statistics::from_samples(&baseline).ok_or(Ads1119Err::NoSamples)?;
+        let threshold = FLOATING_NOISE_FACTOR * baseline.std_dev.max(MIN_BASELINE_NOISE);
+
+        let mut reports = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let samples = self.read_samples(input.bits(), n)?;
+            let statistics = Statistics::from_samples(&samples).ok_or(Ads1119Err::NoSamples)?;
+            let (first, second) = samples.split_at(n / 2);
+            let drift = mean(second) - mean(first);
+
+            let clipped = samples
+                .iter()
+                .any(|&sample| Clipping::of(sample, 0.0) == Clipping::Clipped);
+            let status = if clipped {
+                WiringStatus::OutOfRange
+            } else if statistics.std_dev > threshold || drift.abs() > threshold {
+                WiringStatus::Floating
+            } else {
+                WiringStatus::Connected
+            };
+            reports.push(WiringReport {
+                input: input.clone(),
+                status,
+                statistics,
+                drift,
+            });
+        }
+        Ok(reports)
+    }
+}
+
+fn mean(samples: &[i16]) -> f32 {
+    samples.iter().map(|&sample| sample as f32).sum::<f32>() / samples.len() as f32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::expect_repeated_oneshot_reads;
+    use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+
+    const DEVICE_ADDRESS: u8 = 0b0000_0000;
+
+    #[test]
+    fn flags_floating_and_out_of_range_inputs() {
+        let inputs = [
+            InputSelection::AN0SingleEnded,
+            InputSelection::AN1SingleEnded,
+            InputSelection::AN2SingleEnded,
+            InputSelection::AN3SingleEnded,
+        ];
+        let mut transactions = expect_repeated_oneshot_reads(
+            DEVICE_ADDRESS,
+            InputSelection::ShortedToMidSupply.bits(),
+            &[0, 1, -1, 0],
+        );
+        // steady, noisy, drifting and railed
+        transactions.extend(expect_repeated_oneshot_reads(
+            DEVICE_ADDRESS,
+            inputs[0].bits(),
+            &[8000, 8003, 7998, 8001],
+        ));
+        transactions.extend(expect_repeated_oneshot_reads(
+            DEVICE_ADDRESS,
+            inputs[1].bits(),
+            &[100, 900, -400, 600],
+        ));
+        transactions.extend(expect_repeated_oneshot_reads(
+            DEVICE_ADDRESS,
+            inputs[2].bits(),
+            &[1000, 1010, 1040, 1060],
+        ));
+        transactions.extend(expect_repeated_oneshot_reads(
+            DEVICE_ADDRESS,
+            inputs[3].bits(),
+            &[i16::MAX; 4],
+        ));
+        let mut device = Ads1119::new(I2cMock::new(&transactions), DEVICE_ADDRESS);
+
+        let reports = device.check_wiring(&inputs, 4).unwrap();
+        let statuses: Vec<_> = reports.iter().map(|report| report.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                WiringStatus::Connected,
+                WiringStatus::Floating,
+                WiringStatus::Floating,
+                WiringStatus::OutOfRange
+            ]
+        );
+        assert_eq!(reports[2].drift, 45.0);
+        device.destroy().done();
+    }
+}
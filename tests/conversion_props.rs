@@ -0,0 +1,90 @@
+//! Property tests of the raw data to voltage (internal reference) and ratio (external
+//! reference) conversions, for every gain.
+
+use ads1119::ads1219::{ads1219_code_to_voltage, ADS1219_FULL_SCALE};
+use ads1119::ratiometric::rdata_to_ratio;
+use ads1119::{rdata_to_voltage, single_ended_rdata_to_scaled_voltage, Gain};
+use proptest::prelude::*;
+
+const REFERENCE: f32 = 2.048;
+
+fn gain() -> impl Strategy<Value = Gain> {
+    prop_oneof![Just(Gain::One), Just(Gain::Four)]
+}
+
+// one LSB at gain 1, relative to the reference
+const LSB: f32 = 1.0 / 0x7FFF as f32;
+
+proptest! {
+    #[test]
+    fn voltage_is_monotonic(a: i16, b: i16, gain in gain()) {
+        let (low, high) = (a.min(b), a.max(b));
+        prop_assert!(rdata_to_voltage(low, gain) <= rdata_to_voltage(high, gain));
+        prop_assert!(rdata_to_ratio(low, gain) <= rdata_to_ratio(high, gain));
+    }
+
+    #[test]
+    fn voltage_round_trips_to_the_code(raw: i16, gain in gain()) {
+        let volts = rdata_to_voltage(raw, gain);
+        let code = (volts * gain.factor() / REFERENCE * 0x7FFF as f32).round();
+        prop_assert_eq!(code, raw as f32);
+
+        let ratio = rdata_to_ratio(raw, gain);
+        prop_assert_eq!((ratio * gain.factor() * 0x7FFF as f32).round(), raw as f32);
+    }
+
+    #[test]
+    fn voltage_is_odd_except_at_negative_full_scale(raw in (i16::MIN + 1)..=i16::MAX, gain in gain()) {
+        prop_assert_eq!(rdata_to_voltage(-raw, gain), -rdata_to_voltage(raw, gain));
+        prop_assert_eq!(rdata_to_ratio(-raw, gain), -rdata_to_ratio(raw, gain));
+    }
+
+    #[test]
+    fn voltage_is_within_full_scale(raw in (i16::MIN + 1)..=i16::MAX, gain in gain()) {
+        let full_scale = REFERENCE / gain.factor();
+        prop_assert!(rdata_to_voltage(raw, gain).abs() <= full_scale);
+        prop_assert!(rdata_to_ratio(raw, gain).abs() <= 1.0 / gain.factor());
+    }
+
+    #[test]
+    fn gain_divides_the_voltage(raw: i16) {
+        let one = rdata_to_voltage(raw, Gain::One);
+        let four = rdata_to_voltage(raw, Gain::Four);
+        prop_assert!((one / 4.0 - four).abs() <= f32::EPSILON * one.abs());
+        prop_assert_eq!(single_ended_rdata_to_scaled_voltage(raw), one);
+    }
+
+    #[test]
+    fn ads1219_voltage_is_monotonic_and_odd(
+        a in -ADS1219_FULL_SCALE..=ADS1219_FULL_SCALE,
+        b in -ADS1219_FULL_SCALE..=ADS1219_FULL_SCALE,
+        gain in gain(),
+    ) {
+        let (low, high) = (a.min(b), a.max(b));
+        prop_assert!(ads1219_code_to_voltage(low, gain) <= ads1219_code_to_voltage(high, gain));
+        prop_assert_eq!(ads1219_code_to_voltage(-a, gain), -ads1219_code_to_voltage(a, gain));
+    }
+}
+
+// The two's complement range has one more negative code than positive ones, and the
+// codes are scaled by the positive full scale, so the most negative code is one LSB
+// past -VREF / gain.
+#[test]
+fn negative_full_scale_is_one_lsb_past_the_reference() {
+    for gain in [Gain::One, Gain::Four] {
+        let full_scale = REFERENCE / gain.factor();
+        let min = rdata_to_voltage(i16::MIN, gain);
+        assert!(min < -full_scale);
+        assert!((min + full_scale + full_scale * LSB).abs() < 1e-6);
+        assert_eq!(rdata_to_voltage(i16::MIN + 1, gain), -full_scale);
+        assert_eq!(rdata_to_voltage(i16::MAX, gain), full_scale);
+
+        let ratio = rdata_to_ratio(i16::MIN, gain);
+        assert!((ratio * gain.factor() + 1.0 + LSB).abs() < 1e-6);
+    }
+
+    // the 24-bit part is the same, with codes sign-extended from 24 bits
+    let min = ads1219_code_to_voltage(-ADS1219_FULL_SCALE - 1, Gain::One);
+    assert!(min < -REFERENCE);
+    assert!(min > -REFERENCE * 1.000_001);
+}
@@ -0,0 +1,128 @@
+//! Hardware-in-the-loop tests, run against a real device to validate releases.
+//!
+//! They are ignored by default. On the test rig (e.g. a Raspberry Pi), run them with
+//!
+//! ```sh
+//! ADS1119_I2C_BUS=/dev/i2c-1 ADS1119_ADDRESS=0x40 cargo test --test hardware -- --ignored
+//! ```
+//!
+//! `ADS1119_I2C_BUS` defaults to `/dev/i2c-1` and `ADS1119_ADDRESS` to `0x40`.
+//! The inputs can be left floating: only the shorted input's reading is checked.
+
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use ads1119::{
+    Ads1119, Config, ConversionMode, DataRate, Gain, InputSelection, VoltageReference,
+    STATUS_CONV_RDY,
+};
+use linux_embedded_hal::I2cdev;
+
+// the tests run in parallel by default, but share the device
+static DEVICE_LOCK: Mutex<()> = Mutex::new(());
+
+fn device() -> (MutexGuard<'static, ()>, Ads1119<I2cdev>) {
+    let guard = DEVICE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let bus = std::env::var("ADS1119_I2C_BUS").unwrap_or_else(|_| "/dev/i2c-1".to_string());
+    let address = match std::env::var("ADS1119_ADDRESS") {
+        Ok(address) => {
+            let digits = address.trim_start_matches("0x");
+            u8::from_str_radix(digits, 16).expect("ADS1119_ADDRESS should be a hex address")
+        }
+        Err(_) => 0x40,
+    };
+    let i2c = I2cdev::new(&bus).unwrap_or_else(|e| panic!("can't open {bus}: {e}"));
+    let mut device = Ads1119::new(i2c, address);
+    device.reset().unwrap();
+    (guard, device)
+}
+
+const INPUTS: [InputSelection; 8] = [
+    InputSelection::AN0AN1Differential,
+    InputSelection::AN2AN3Differential,
+    InputSelection::AN1AN2Differential,
+    InputSelection::AN0SingleEnded,
+    InputSelection::AN1SingleEnded,
+    InputSelection::AN2SingleEnded,
+    InputSelection::AN3SingleEnded,
+    InputSelection::ShortedToMidSupply,
+];
+
+const DATA_RATES: [(DataRate, u32); 4] = [
+    (DataRate::Sps20, 20),
+    (DataRate::Sps90, 90),
+    (DataRate::Sps330, 330),
+    (DataRate::Sps1000, 1000),
+];
+
+#[test]
+#[ignore = "needs an ADS1119, see the module docs"]
+fn reset_restores_default_config() {
+    let (_guard, mut device) = device();
+    device.write_config(0xFF).unwrap();
+    assert_eq!(device.read_config().unwrap(), 0xFF);
+    device.reset().unwrap();
+    assert_eq!(device.read_config().unwrap(), 0);
+}
+
+#[test]
+#[ignore = "needs an ADS1119, see the module docs"]
+fn config_round_trips() {
+    let (_guard, mut device) = device();
+    for input in INPUTS {
+        for gain in [Gain::One, Gain::Four] {
+            for (data_rate, _) in DATA_RATES {
+                for mode in [ConversionMode::SingleShot, ConversionMode::Continuous] {
+                    for reference in [VoltageReference::Internal, VoltageReference::External] {
+                        let config = Config::new(input.clone())
+                            .with_gain(gain)
+                            .with_data_rate(data_rate)
+                            .with_conversion_mode(mode)
+                            .with_voltage_reference(reference);
+                        device.write_config(config.bits()).unwrap();
+                        assert_eq!(device.read_config().unwrap(), config.bits(), "{config:?}");
+                    }
+                }
+            }
+        }
+    }
+    device.reset().unwrap();
+}
+
+#[test]
+#[ignore = "needs an ADS1119, see the module docs"]
+fn reads_every_input() {
+    let (_guard, mut device) = device();
+    for input in INPUTS {
+        let raw = device
+            .read_input_oneshot(&input)
+            .unwrap_or_else(|e| panic!("{input:?}: {e}"));
+        if input == InputSelection::ShortedToMidSupply {
+            // only the offset error, a few codes
+            assert!(raw.abs() < 100, "offset of {raw}");
+        }
+    }
+}
+
+#[test]
+#[ignore = "needs an ADS1119, see the module docs"]
+fn conversion_time_matches_data_rate() {
+    let (_guard, mut device) = device();
+    for (data_rate, sps) in DATA_RATES {
+        let config = Config::new(InputSelection::ShortedToMidSupply).with_data_rate(data_rate);
+        device.write_config(config.bits()).unwrap();
+
+        let period = Duration::from_secs(1) / sps;
+        let start = Instant::now();
+        device.start_sync().unwrap();
+        while device.read_status().unwrap() & STATUS_CONV_RDY == 0 {
+            assert!(start.elapsed() < period * 3, "{data_rate:?} timed out");
+        }
+        let elapsed = start.elapsed();
+        device.read_data().unwrap();
+
+        // the first conversion after START/SYNC takes a bit longer than the period,
+        // and the bus adds latency on top
+        assert!(elapsed >= period * 9 / 10, "{data_rate:?} took {elapsed:?}");
+    }
+}